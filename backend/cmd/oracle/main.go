@@ -3,10 +3,16 @@ package main
 import (
 	"log"
 	"net/http"
+	"os"
 	"crypto/rand"
 	"encoding/hex"
+	"encoding/json"
 
 	"github.com/gin-gonic/gin"
+
+	"creature-crafter/backend/internal/game"
+	"creature-crafter/backend/internal/hookerr"
+	"creature-crafter/backend/internal/xrplclient"
 )
 
 type MintRequest struct {
@@ -20,13 +26,17 @@ type HatchRequest struct {
 }
 
 type HatchResponse struct {
-	DNA    string `json:"dna"`
-	TxHash string `json:"tx_hash,omitempty"`
+	DNA    string     `json:"dna"`
+	Stats  game.Stats `json:"stats"`
+	TxHash string     `json:"tx_hash,omitempty"`
 }
 
 func main() {
-	// We'll initialize a real XRPL client in production
-	// For now, we'll proceed without it for the demo
+	// The node's JSON-RPC API, for looking up payment results
+	rpcURL := os.Getenv("XRPL_HTTP")
+	if rpcURL == "" {
+		rpcURL = "http://localhost:6006"
+	}
 
 	r := gin.Default()
 
@@ -38,12 +48,42 @@ func main() {
 			return
 		}
 
-		// Verify payment transaction
-		// This would check if payment was made to the correct address
-		// and if the amount is correct
-		// For demo, we'll just return success
+		// Verify payment transaction: the egg shop hook mints on a
+		// validated payment, and rolls it back with a code saying why not
+		tx, err := xrplclient.Tx(rpcURL, req.PaymentTx)
+		if err != nil {
+			c.JSON(http.StatusBadGateway, gin.H{"error": err.Error()})
+			return
+		}
+		if !tx.Validated {
+			c.JSON(http.StatusConflict, gin.H{"error": "Payment not validated yet"})
+			return
+		}
+		var meta struct {
+			TransactionResult string
+		}
+		if err := json.Unmarshal(tx.Meta, &meta); err != nil {
+			c.JSON(http.StatusBadGateway, gin.H{"error": err.Error()})
+			return
+		}
+		hooks, err := hookerr.FromMeta(tx.Meta)
+		if err != nil {
+			c.JSON(http.StatusBadGateway, gin.H{"error": err.Error()})
+			return
+		}
+		if meta.TransactionResult != "tesSUCCESS" {
+			// The hook that rejected the payment says why
+			reason := meta.TransactionResult
+			for _, hook := range hooks {
+				if hook.Result != hookerr.ResultAccept {
+					reason = hook.Message
+				}
+			}
+			c.JSON(http.StatusUnprocessableEntity, gin.H{"error": reason, "result": meta.TransactionResult, "hooks": hooks})
+			return
+		}
 
-		c.JSON(http.StatusOK, gin.H{"success": true})
+		c.JSON(http.StatusOK, gin.H{"success": true, "hooks": hooks})
 	})
 
 	// Hatch egg endpoint
@@ -62,6 +102,11 @@ func main() {
 			return
 		}
 		dna := hex.EncodeToString(dnaBytes)
+		pet, err := game.NewPetFromDNA(req.NFTID, dna, req.Address, req.NFTID)
+		if err != nil {
+			c.JSON(http.StatusInternalServerError, gin.H{"error": err.Error()})
+			return
+		}
 
 		// In a real implementation, we would:
 		// 1. Sign a transaction to update the NFT metadata
@@ -69,7 +114,8 @@ func main() {
 		// 3. Return the transaction hash
 
 		c.JSON(http.StatusOK, HatchResponse{
-			DNA:    dna,
+			DNA:    pet.DNA,
+			Stats:  pet.Stats,
 			TxHash: "simulated_tx_hash", // In real implementation, this would be the actual tx hash
 		})
 	})
@@ -1,10 +1,17 @@
 package game
 
 import (
+	"fmt"
 	"math/rand"
-	"strings"
+	"strconv"
 )
 
+// DNALen is the length of a hex DNA string: 16 bytes.
+const DNALen = 32
+
+// statSalt is the per-stat salt, the 32-bit golden ratio.
+const statSalt uint32 = 0x9E3779B9
+
 // Pet represents a creature in the game
 type Pet struct {
 	ID      string
@@ -16,25 +23,18 @@ type Pet struct {
 
 // Stats represents the attributes of a pet
 type Stats struct {
-	Strength     int
-	Speed        int
-	Intelligence int
-	Endurance    int
+	Strength     int `json:"strength"`
+	Speed        int `json:"speed"`
+	Intelligence int `json:"intelligence"`
+	Endurance    int `json:"endurance"`
 }
 
-// NewPetFromDNA creates a new pet with stats derived from DNA
-func NewPetFromDNA(id, dna, owner, nftID string) *Pet {
-	if len(dna) < 32 {
-		// Pad DNA if necessary
-		dna = dna + strings.Repeat("0", 32-len(dna))
-	}
-
-	// Extract stats from DNA
-	stats := Stats{
-		Strength:     hexToStat(dna[0:8]),
-		Speed:        hexToStat(dna[8:16]),
-		Intelligence: hexToStat(dna[16:24]),
-		Endurance:    hexToStat(dna[24:32]),
+// NewPetFromDNA creates a new pet with stats derived from DNA, which must be
+// exactly DNALen hex characters, as the hooks require
+func NewPetFromDNA(id, dna, owner, nftID string) (*Pet, error) {
+	stats, err := StatsFromDNA(dna)
+	if err != nil {
+		return nil, err
 	}
 
 	return &Pet{
@@ -43,18 +43,41 @@ func NewPetFromDNA(id, dna, owner, nftID string) *Pet {
 		Stats:   stats,
 		Owner:   owner,
 		NFTID:   nftID,
-	}
+	}, nil
 }
 
-// hexToStat converts a hex string to a stat value (1-100)
-func hexToStat(hex string) int {
-	// Simple conversion for demo purposes
-	// In a real implementation, we would use a more sophisticated algorithm
-	sum := 0
-	for _, r := range hex {
-		sum += int(r)
+// StatsFromDNA derives stats from a 32-character hex DNA string exactly as
+// the hooks do (hook/core/src/dna.rs). Each stat comes from one 4-byte
+// quarter, in the order Strength, Speed, Intelligence, Endurance: the
+// quarter is XORed with its index times statSalt, run through the
+// MurmurHash3 finalizer and reduced to 1..100.
+func StatsFromDNA(dna string) (Stats, error) {
+	if len(dna) != DNALen {
+		return Stats{}, fmt.Errorf("DNA must be %d hex characters, got %d", DNALen, len(dna))
+	}
+	var stats [4]int
+	for i := range stats {
+		word, err := strconv.ParseUint(dna[i*8:i*8+8], 16, 32)
+		if err != nil {
+			return Stats{}, fmt.Errorf("DNA %q is not hex", dna)
+		}
+		stats[i] = 1 + int(fmix32(uint32(word)^uint32(i)*statSalt)%100)
 	}
-	return (sum % 100) + 1
+	return Stats{
+		Strength:     stats[0],
+		Speed:        stats[1],
+		Intelligence: stats[2],
+		Endurance:    stats[3],
+	}, nil
+}
+
+// fmix32 is the MurmurHash3 finalizer; multiplies wrap.
+func fmix32(h uint32) uint32 {
+	h ^= h >> 16
+	h *= 0x85EBCA6B
+	h ^= h >> 13
+	h *= 0xC2B2AE35
+	return h ^ (h >> 16)
 }
 
 // GenerateRandomDNA creates a random DNA string
@@ -0,0 +1,69 @@
+package game
+
+import (
+	"encoding/json"
+	"os"
+	"testing"
+)
+
+// dnaVector is one entry of the reference values the hooks are tested
+// against.
+type dnaVector struct {
+	DNA          string `json:"dna"`
+	Strength     int    `json:"strength"`
+	Speed        int    `json:"speed"`
+	Intelligence int    `json:"intelligence"`
+	Endurance    int    `json:"endurance"`
+}
+
+func TestStatsFromDNAMatchesHookVectors(t *testing.T) {
+	data, err := os.ReadFile("../../../hook/core/vectors/dna_stats.json")
+	if err != nil {
+		t.Fatal(err)
+	}
+	var vectors []dnaVector
+	if err := json.Unmarshal(data, &vectors); err != nil {
+		t.Fatal(err)
+	}
+	if len(vectors) == 0 {
+		t.Fatal("no DNA vectors")
+	}
+	for _, v := range vectors {
+		stats, err := StatsFromDNA(v.DNA)
+		if err != nil {
+			t.Fatalf("%s: %v", v.DNA, err)
+		}
+		want := Stats{Strength: v.Strength, Speed: v.Speed, Intelligence: v.Intelligence, Endurance: v.Endurance}
+		if stats != want {
+			t.Errorf("%s: got %+v, want %+v", v.DNA, stats, want)
+		}
+	}
+}
+
+func TestStatsFromDNARejectsMalformedDNA(t *testing.T) {
+	for _, dna := range []string{
+		"0123456789abcdef0123456789abcde",
+		"0123456789abcdef0123456789abcdeg",
+		"+123456789abcdef0123456789abcdef",
+		"0123456789abcdef0123456789abcdef0",
+	} {
+		if _, err := StatsFromDNA(dna); err == nil {
+			t.Errorf("%s: accepted", dna)
+		}
+	}
+}
+
+func TestNewPetFromDNARejectsOtherLengths(t *testing.T) {
+	for _, dna := range []string{"", "0123456789abcdef", "0123456789abcdef0123456789abcdef00"} {
+		if _, err := NewPetFromDNA("pet", dna, "owner", "nft"); err == nil {
+			t.Errorf("%q: accepted", dna)
+		}
+	}
+	pet, err := NewPetFromDNA("pet", "0123456789abcdef0123456789abcdef", "owner", "nft")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if want, _ := StatsFromDNA(pet.DNA); pet.Stats != want {
+		t.Errorf("got %+v, want %+v", pet.Stats, want)
+	}
+}
@@ -0,0 +1,152 @@
+// Package hookerr decodes the codes the game hooks roll back or accept
+// with, as recorded in HookReturnCode. The table mirrors HookError in
+// hook/core/src/error.rs; a hook test keeps the two in step.
+package hookerr
+
+import (
+	"encoding/json"
+	"fmt"
+	"strconv"
+)
+
+// Messages maps each hook return code to its short message. Metadata does
+// not hold these codes as they are: rippled stores HookReturnCode as a
+// UInt64 in hex with a negative code sign-bit encoded, so -1 reads
+// 8000000000000001. Look codes from metadata up via DecodeReturnCode.
+var Messages = map[int64]string{
+	-1:  "panic",
+	100: "Invalid ADMIN parameter",
+	101: "Only the admin can send admin commands",
+	102: "Admin commands must be multi-signed",
+	103: "Malformed admin command",
+	104: "Could not apply admin command",
+	105: "Hooks are paused",
+	201: "Invalid BURN_BPS parameter",
+	202: "Invalid BURN_ON parameter",
+	203: "Invalid BURN_TO parameter",
+	210: "Not a Spark payment",
+	211: "Spark issuance or redemption",
+	212: "Payment direction not burned",
+	213: "Exempt account",
+	214: "Burn below minimum",
+	215: "Payment to the burn account",
+	216: "Partial Spark payment not burned",
+	220: "Could not reserve burn",
+	221: "Could not emit burn",
+	222: "Could not record burn",
+	301: "Invalid EGG_PRICE parameter",
+	302: "Partial payments cannot buy eggs",
+	303: "Payment does not match egg price",
+	304: "Could not record pending egg",
+	305: "Could not reserve egg mint",
+	306: "Could not emit egg mint",
+	307: "Could not read buyer account",
+	308: "No failed egg mint to retry",
+	401: "Missing pet-hatch memo",
+	402: "Malformed hatch request",
+	403: "Not an egg from this shop",
+	404: "Egg already hatched",
+	405: "Sender does not own the egg",
+	406: "Could not record DNA",
+	501: "Missing pet-battle memo",
+	502: "Malformed battle request",
+	503: "A pet cannot fight itself",
+	504: "Pet has not hatched",
+	505: "DNA does not match the hatched pet",
+	506: "Sender does not own the challenger",
+	507: "Could not record battle",
+	508: "Sender does not own the opponent",
+	509: "No such open battle",
+	510: "Battle already accepted",
+	511: "Battle not accepted yet",
+	512: "Reveal does not match the commitment",
+	513: "Challenger cannot accept their own battle",
+	514: "Challenger can still reveal",
+	515: "Pet already has an open challenge",
+	516: "Only the challenger can cancel before the challenge expires",
+	601: "Missing spark-claim memo",
+	602: "Malformed claim",
+	603: "Claim is for another account",
+	604: "MM_KEY parameter not set",
+	605: "Invalid claim signature",
+	606: "Nothing left to claim",
+	607: "Could not record claim",
+	608: "Claim too large",
+	609: "Could not reserve payout",
+	610: "Could not emit payout",
+	611: "Could not record payout",
+}
+
+// Describe returns the message for code, or a generic one for codes this
+// table does not know.
+func Describe(code int64) string {
+	if code == 0 {
+		return "OK"
+	}
+	if message, ok := Messages[code]; ok {
+		return message
+	}
+	return fmt.Sprintf("Unknown hook error %d", code)
+}
+
+// DecodeReturnCode reads a HookReturnCode as transaction metadata records
+// it: hex, with the top bit set and the magnitude below it for a negative
+// code.
+func DecodeReturnCode(field string) (int64, error) {
+	raw, err := strconv.ParseUint(field, 16, 64)
+	if err != nil {
+		return 0, fmt.Errorf("bad HookReturnCode %q: %w", field, err)
+	}
+	magnitude := int64(raw &^ (1 << 63))
+	if raw>>63 == 1 {
+		return -magnitude, nil
+	}
+	return magnitude, nil
+}
+
+// Hook exit types, as recorded in HookResult.
+const (
+	ResultWasmError = 1
+	ResultRollback  = 2
+	ResultAccept    = 3
+)
+
+// Execution is one hook run from a transaction's HookExecutions metadata.
+type Execution struct {
+	Account string `json:"account"`
+	Result  int    `json:"result"`
+	Code    int64  `json:"code"`
+	Message string `json:"message"`
+}
+
+// FromMeta decodes the hook runs recorded in a transaction's JSON
+// metadata, in the order they ran.
+func FromMeta(meta json.RawMessage) ([]Execution, error) {
+	var parsed struct {
+		HookExecutions []struct {
+			HookExecution struct {
+				HookAccount    string
+				HookResult     int
+				HookReturnCode string
+			}
+		}
+	}
+	if err := json.Unmarshal(meta, &parsed); err != nil {
+		return nil, err
+	}
+	executions := make([]Execution, 0, len(parsed.HookExecutions))
+	for _, entry := range parsed.HookExecutions {
+		run := entry.HookExecution
+		code, err := DecodeReturnCode(run.HookReturnCode)
+		if err != nil {
+			return nil, err
+		}
+		executions = append(executions, Execution{
+			Account: run.HookAccount,
+			Result:  run.HookResult,
+			Code:    code,
+			Message: Describe(code),
+		})
+	}
+	return executions, nil
+}
@@ -0,0 +1,53 @@
+package hookerr
+
+import (
+	"encoding/json"
+	"testing"
+)
+
+func TestDecodeReturnCodeReadsSignBitEncodedCodes(t *testing.T) {
+	cases := map[string]int64{
+		"0":                0,
+		"1F5":              501,
+		"8000000000000001": -1,
+		"800000000000012D": -301,
+	}
+	for field, want := range cases {
+		got, err := DecodeReturnCode(field)
+		if err != nil {
+			t.Fatalf("%s: %v", field, err)
+		}
+		if got != want {
+			t.Errorf("%s: got %d, want %d", field, got, want)
+		}
+	}
+	if _, err := DecodeReturnCode("not hex"); err == nil {
+		t.Error("decoded a malformed code")
+	}
+}
+
+func TestFromMetaDescribesEachHookRun(t *testing.T) {
+	meta := json.RawMessage(`{
+		"TransactionResult": "tecHOOK_REJECTED",
+		"HookExecutions": [
+			{"HookExecution": {"HookAccount": "rA", "HookResult": 3, "HookReturnCode": "0"}},
+			{"HookExecution": {"HookAccount": "rB", "HookResult": 2, "HookReturnCode": "8000000000000001"}}
+		]
+	}`)
+	executions, err := FromMeta(meta)
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []Execution{
+		{Account: "rA", Result: ResultAccept, Code: 0, Message: "OK"},
+		{Account: "rB", Result: ResultRollback, Code: -1, Message: "panic"},
+	}
+	if len(executions) != len(want) {
+		t.Fatalf("got %d executions, want %d", len(executions), len(want))
+	}
+	for i := range want {
+		if executions[i] != want[i] {
+			t.Errorf("execution %d: got %+v, want %+v", i, executions[i], want[i])
+		}
+	}
+}
@@ -1,10 +1,53 @@
 package xrplclient
 
 import (
+	"bytes"
 	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+
 	"github.com/xyield/xrpl-go/client"
 )
 
 func New(url string) (*client.Client, error) {
 	return client.New(context.Background(), url)
-}
\ No newline at end of file
+}
+
+// TxResult is a transaction as the node's tx method returns it, with its
+// metadata left raw for decoders such as hookerr.FromMeta.
+type TxResult struct {
+	Hash      string          `json:"hash"`
+	Validated bool            `json:"validated"`
+	Meta      json.RawMessage `json:"meta"`
+}
+
+// Tx looks a transaction up over the node's JSON-RPC API.
+func Tx(url, hash string) (*TxResult, error) {
+	body, err := json.Marshal(map[string]any{
+		"method": "tx",
+		"params": []any{map[string]any{"transaction": hash, "binary": false}},
+	})
+	if err != nil {
+		return nil, err
+	}
+	response, err := http.Post(url, "application/json", bytes.NewReader(body))
+	if err != nil {
+		return nil, err
+	}
+	defer response.Body.Close()
+	var reply struct {
+		Result struct {
+			TxResult
+			Status string `json:"status"`
+			Error  string `json:"error"`
+		} `json:"result"`
+	}
+	if err := json.NewDecoder(response.Body).Decode(&reply); err != nil {
+		return nil, err
+	}
+	if reply.Result.Status != "success" {
+		return nil, fmt.Errorf("tx %s: %s", hash, reply.Result.Error)
+	}
+	return &reply.Result.TxResult, nil
+}
@@ -5,7 +5,8 @@
  * - XRPL API proxying
  * - Wallet funding
  * - Ledger advancement
- * - NFT minting
+ *
+ * Egg NFTs are minted by the egg-shop hook, never through this proxy.
  */
 
 const express = require('express');
@@ -548,27 +549,16 @@ app.post('/api/xrpl-proxy', async (req, res) => {
         });
       }
       
+      // Eggs are minted by the egg-shop hook in the purchase transaction;
+      // a second mint from here would hand out a duplicate egg
+      if (txJson.TransactionType === 'NFTokenMint') {
+        return res.status(400).json({
+          error: 'invalid_request',
+          error_message: 'Egg NFTs are minted by the egg-shop hook'
+        });
+      }
+      
       try {
-        // Handle NFT minting
-        if (txJson.TransactionType === 'NFTokenMint') {
-          // Check if account exists and fund it if needed
-          try {
-            const accountInfo = await xrplClient.request('account_info', {
-              account: txJson.Account,
-              strict: true,
-              ledger_index: 'current'
-            });
-            
-            if (!accountInfo.result?.account_data) {
-              console.log(`Account ${txJson.Account} not found, funding it first...`);
-              await fundAccount(txJson.Account, "25");
-            }
-          } catch (error) {
-            console.log('Error checking account, attempting to fund it:', error.message);
-            await fundAccount(txJson.Account, "25");
-          }
-        }
-        
         // Submit the transaction
         const result = await submitTransaction(txJson, secret);
         
@@ -698,7 +688,6 @@ if (process.env.NODE_ENV !== 'test') {
     console.log(`\n✅ Supported Operations:`);
     console.log(`   • Transaction signing via proxy`);
     console.log(`   • Account funding with master seed`);
-    console.log(`   • NFT minting with fallback mechanisms`);
     console.log(`   • Automatic ledger advancement in standalone mode`);
     
     console.log(`\n✅ Press Ctrl+C to stop the server`);
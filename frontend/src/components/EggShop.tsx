@@ -211,7 +211,7 @@ export default function EggShop({ onWallet }: EggShopProps) {
       // Refresh balance after purchase
       await fetchBalance(wallet.address);
       
-      alert(`Successfully purchased a ${eggType} egg! Check your NFTs soon.`);
+      alert(`Successfully purchased a ${eggType} egg! The shop has minted it for you; claim it from Your Eggs.`);
     } catch (err: any) {
       console.error('Failed to buy egg:', err);
       
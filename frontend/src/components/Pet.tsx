@@ -1,6 +1,6 @@
 import React, { useState, useEffect, useRef } from 'react';
 import type { Wallet } from '../xrpl-direct';
-import { getOwnedNFTs, mintNFT } from '../xrpl-direct';
+import { getOwnedNFTs, claimEggs } from '../xrpl-direct';
 
 interface PetProps {
   wallet: Wallet;
@@ -130,134 +130,35 @@ export default function Pet({ wallet }: PetProps) {
     return () => clearInterval(interval);
   }, [wallet, lastMintedTokenId]);
   
-  const handleMintEgg = async () => {
+  const handleClaimEggs = async () => {
     try {
       setLoading(true);
-      setMintingStatus('Preparing to mint NFT...');
+      setMintingStatus('Looking for eggs the shop has minted for you...');
       
-      // In a real app, this would happen on the backend after payment verification
-      // For demo, we'll mint directly from the frontend
-      const eggUri = "ipfs://QmXExS4BMc1YrH6iWERyryFKXdve5YUFJf1oaYxLxdvGmZ";
-      
-      // Store the current NFT count to compare after minting
+      // The egg-shop hook mints each egg when the purchase payment lands and
+      // offers it to the buyer; claiming accepts those offers
       prevNftCountRef.current = nfts.length;
+      const claimed = await claimEggs(wallet);
       
-      // Clear any previous mint token ID
-      setLastMintedTokenId(null);
-      
-      // Show status instead of alert for better UX
-      setMintingStatus('Submitting NFT minting transaction to XRPL...');
-      
-      let result;
-      try {
-        result = await mintNFT(wallet, eggUri);
-        console.log("Mint result:", result);
-        
-        if (result && result.verified) {
-          setMintingStatus('NFT minted successfully! Refreshing your collection...');
-        } else {
-          setMintingStatus('Transaction submitted. Waiting for confirmation...');
-        }
-      } catch (mintError) {
-        console.error("Error during NFT minting:", mintError);
-        setMintingStatus('Error minting NFT. Using fallback approach...');
-        
-        // Fetch NFTs anyway to show what's available
-        const masterWallet = { 
-          address: 'rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh', 
-          seed: 'snoPBrXtMeMyMHUVTgbuqAfg1SUTb' 
-        };
-        const existingNFTs = await getOwnedNFTs(masterWallet);
-        
-        // Use existing NFTs as fallback result
-        result = {
-          nfts: existingNFTs,
-          nft_count: existingNFTs.length,
-          verified: false,
-          error: mintError.message || 'Unknown minting error',
-          tx_hash: 'error-no-hash'
-        };
+      if (claimed.length === 0) {
+        setMintingStatus('');
+        alert('No eggs waiting to be claimed. Buy one from the shop first.');
+        return;
       }
       
-      // If the result includes updated NFTs, use them directly
-      if (result && result.nfts && result.nfts.length > 0) {
-        console.log(`Setting ${result.nfts.length} NFTs from mint result`);
-        
-        // Identify the new NFT (if we can)
-        // In a real app with TokenIDs returned from minting, we'd use that
-        // For now, we'll assume it's the last one if count increased
-        if (result.nfts.length > prevNftCountRef.current) {
-          // Sort by serial number (descending) to find the newest one
-          const sortedNFTs = [...result.nfts].sort((a, b) => 
-            (b.SerialNumber || 0) - (a.SerialNumber || 0)
-          );
-          
-          // Assume the first one is the newest
-          if (sortedNFTs.length > 0) {
-            const newTokenId = sortedNFTs[0].NFTokenID;
-            setLastMintedTokenId(newTokenId);
-            console.log('New NFT identified:', newTokenId);
-          }
-          
-          // Mark the new NFTs
-          const enhancedNFTs = result.nfts.map(nft => ({
-            ...nft,
-            isNew: lastMintedTokenId ? nft.NFTokenID === lastMintedTokenId : 
-                  nft.NFTokenID === sortedNFTs[0]?.NFTokenID
-          }));
-          
-          setNfts(enhancedNFTs);
-        } else {
-          // Just use the NFTs as-is
-          setNfts(result.nfts);
-        }
-      } else {
-        // Otherwise, fetch NFTs separately with a retry mechanism
-        console.log("Fetching NFTs after mint...");
-        // Try fetching a few times to allow for ledger finalization
-        let retries = 3;
-        let ownedNFTs: NFT[] = [];
-        
-        while (retries > 0) {
-          try {
-            ownedNFTs = await getOwnedNFTs(wallet);
-            if (ownedNFTs.length > prevNftCountRef.current) {
-              // Success! We found the new NFTs
-              break;
-            } else {
-              setMintingStatus(`Waiting for NFT to appear (attempt ${4-retries}/3)...`);
-              // Wait 2 seconds between retries
-              await new Promise(resolve => setTimeout(resolve, 2000));
-            }
-          } catch (err) {
-            console.warn('Error fetching NFTs, retrying:', err);
-          }
-          retries--;
-        }
-        
-        if (ownedNFTs.length > prevNftCountRef.current) {
-          // We have more NFTs than before, mark the new ones
-          const enhancedNFTs = ownedNFTs.map(nft => ({
-            ...nft,
-            // Mark all new NFTs since we can't easily identify just one
-            isNew: true
-          }));
-          setNfts(enhancedNFTs);
-        } else {
-          // Just use whatever we got
-          setNfts(ownedNFTs);
-        }
-      }
+      setMintingStatus('Eggs claimed! Refreshing your collection...');
+      const ownedNFTs = await getOwnedNFTs(wallet);
+      setNfts(ownedNFTs.map(nft => ({
+        ...nft,
+        isNew: ownedNFTs.length > prevNftCountRef.current
+      })));
       
-      // Show final success message
       setMintingStatus('');
-      alert(`NFT minting result: ${result.result?.engine_result || 'Success'}\n` + 
-            `You now have ${result.nft_count || result.nfts?.length || '?'} NFTs\n` +
-            `Transaction hash: ${result.tx_hash?.substring(0, 10) || 'unknown'}...`);
+      alert(`Claimed ${claimed.length} egg${claimed.length === 1 ? '' : 's'}`);
     } catch (err: any) {
-      console.error('Failed to mint NFT:', err);
+      console.error('Failed to claim eggs:', err);
       setMintingStatus('');
-      alert(`XRPL Error: ${err.message || 'Failed to mint NFT'}`);
+      alert(`XRPL Error: ${err.message || 'Failed to claim eggs'}`);
     } finally {
       setLoading(false);
     }
@@ -450,7 +351,7 @@ export default function Pet({ wallet }: PetProps) {
       
       <div style={{ marginBottom: '20px' }}>
         <button 
-          onClick={handleMintEgg}
+          onClick={handleClaimEggs}
           disabled={loading}
           style={{ 
             padding: '10px 20px', 
@@ -464,7 +365,7 @@ export default function Pet({ wallet }: PetProps) {
             cursor: loading ? 'not-allowed' : 'pointer'
           }}
         >
-          {loading ? 'Minting...' : 'Mint Test Egg NFT'}
+          {loading ? 'Claiming...' : 'Claim Purchased Eggs'}
         </button>
         
         {mintingStatus && (
@@ -483,13 +384,13 @@ export default function Pet({ wallet }: PetProps) {
         )}
         
         <p style={{ color: '#666', fontSize: '14px', marginTop: '10px' }}>
-          This button directly mints an egg NFT for testing. In the real game, NFTs are minted after payment.
+          Eggs are minted by the egg-shop hook when your payment lands. Claim them here to move them into your wallet.
         </p>
       </div>
       
       {nfts.length === 0 ? (
         <div>
-          <p>You don't have any eggs yet. Buy one from the shop or mint a test egg.</p>
+          <p>You don't have any eggs yet. Buy one from the shop, then claim it here.</p>
         </div>
       ) : (
         <div style={{ display: 'flex', flexWrap: 'wrap', gap: '20px' }}>
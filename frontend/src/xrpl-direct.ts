@@ -654,10 +654,13 @@ export async function sendXRP(
   }
 }
 
-// Buy an egg
+// Account the egg-shop hook is installed on
+export const EGG_SHOP_ADDRESS = "rPT1Sjq2YGrBMTttX4GZHjKu9dyfzbpAYe";
+
+// Buy an egg. The egg-shop hook mints it in the same transaction as the
+// payment and offers it to the buyer for free; claimEggs accepts the offer.
 export async function buyEgg(wallet: Wallet, priceXRP = "10"): Promise<any> {
-  // Use the shop address
-  const eggShopAddress = "rPT1Sjq2YGrBMTttX4GZHjKu9dyfzbpAYe"; // Default egg shop address
+  const eggShopAddress = EGG_SHOP_ADDRESS;
 
   console.log(`Buying egg for ${priceXRP} XRP from shop at ${eggShopAddress}`);
 
@@ -686,7 +689,6 @@ export async function buyEgg(wallet: Wallet, priceXRP = "10"): Promise<any> {
 
     console.log("Payment successful, egg purchased!");
 
-    // Simulate egg purchase response
     return {
       success: true,
       eggId: `egg_${Date.now()}`,
@@ -763,146 +765,63 @@ export async function getOwnedNFTs(wallet: Wallet): Promise<any[]> {
   }
 }
 
-// Helper function to convert string to hex without using Buffer
-function stringToHex(str: string): string {
-  let hex = "";
-  for (let i = 0; i < str.length; i++) {
-    const charCode = str.charCodeAt(i);
-    hex += charCode.toString(16).padStart(2, "0");
-  }
-  return hex.toUpperCase();
-}
-
-// Mint NFT
-export async function mintNFT(wallet: Wallet, uri: string): Promise<any> {
-  try {
-    const client = await getClient();
-
-    // For demo purposes, always use the master wallet for minting
-    // since our demo wallets won't be funded on the XRPL
-    const masterWallet = await getMasterWallet();
-
-    console.log(
-      `Minting NFT using master wallet instead of: ${wallet.address}`
-    );
-
-    try {
-      // Use our custom stringToHex function instead of Buffer
-      const hexUri = stringToHex(uri);
-
-      // Always use the proxy server directly for NFT minting
-      console.log("Using proxy server for NFT minting with local signing...");
-
-      // Construct a request to the proxy server to handle signing
-      const response = await fetch(getProxyUrl(), {
-        method: "POST",
-        headers: {
-          "Content-Type": "application/json",
-        },
-        body: JSON.stringify({
-          method: "submit",
-          params: [
-            {
-              tx_json: {
-                TransactionType: "NFTokenMint",
-                Account: masterWallet.address,
-                URI: hexUri,
-                Flags: 8, // transferable
-                NFTokenTaxon: 0,
-                Fee: "10",
-              },
-              secret: masterWallet.seed,
+// Accept the egg offers the egg-shop hook has made to this wallet, moving
+// each egg NFT into it. Eggs are only ever minted by the hook, never here.
+export async function claimEggs(wallet: Wallet): Promise<any[]> {
+  const client = await getClient();
+
+  const response = await client.request("account_objects", {
+    account: EGG_SHOP_ADDRESS,
+    type: "nft_offer",
+  });
+  const offers = (response.result?.account_objects || []).filter(
+    (offer: any) => offer.Destination === wallet.address && offer.Amount === "0"
+  );
+  console.log(`Found ${offers.length} egg offers for ${wallet.address}`);
+
+  const results = [];
+  for (const offer of offers) {
+    const acceptResponse = await fetch(getProxyUrl(), {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/json",
+      },
+      body: JSON.stringify({
+        method: "submit",
+        params: [
+          {
+            tx_json: {
+              TransactionType: "NFTokenAcceptOffer",
+              Account: wallet.address,
+              NFTokenSellOffer: offer.index,
+              Fee: "10",
             },
-          ],
-        }),
-      });
-
-      if (!response.ok) {
-        throw new Error(`HTTP error ${response.status}`);
-      }
-
-      const proxySignedResult = await response.json();
-      console.log("NFT minting response:", JSON.stringify(proxySignedResult));
-
-      // Check if the proxy signing was successful
-      if (proxySignedResult.error) {
-        throw new Error(
-          `NFT minting failed: ${
-            proxySignedResult.error_message || proxySignedResult.error
-          }`
-        );
-      }
-
-      // In standalone mode, try to advance the ledger through the proxy
-      // This should be handled by the server, but we'll try anyway
-      try {
-        console.log("Advancing ledger after NFT minting...");
-        const ledgerResponse = await fetch(getProxyUrl(), {
-          method: "POST",
-          headers: {
-            "Content-Type": "application/json",
+            secret: wallet.seed,
           },
-          body: JSON.stringify({
-            method: "ledger_accept",
-            params: [{}],
-          }),
-        });
-
-        const ledgerResult = await ledgerResponse.json();
-
-        // If ledger_accept isn't supported, log the message
-        if (ledgerResult.error === "unsupported_method") {
-          console.log(
-            "ledger_accept not supported (this is expected on non-standalone nodes)"
-          );
-        } else {
-          console.log("Ledger advance result:", JSON.stringify(ledgerResult));
-        }
-
-        // Wait after attempting to advance the ledger
-        await new Promise((resolve) => setTimeout(resolve, 2000));
-      } catch (ledgerError) {
-        console.warn("Error advancing ledger:", ledgerError);
-        console.log("Continuing anyway, the transaction may still be valid...");
-      }
-
-      // Wait a bit for the transaction to be processed
-      await new Promise((resolve) => setTimeout(resolve, 3000));
-
-      // Get the NFTs after minting
-      console.log("Fetching NFTs after minting...");
-      const nfts = await getOwnedNFTs(masterWallet);
-      console.log(`Found ${nfts.length} NFTs after minting`);
+        ],
+      }),
+    });
 
-      // Return success result with NFTs
-      return {
-        result: proxySignedResult.result,
-        nfts,
-        nft_count: nfts.length,
-        tx_hash: proxySignedResult.result?.tx_hash || "proxy-signed",
-        verified: true,
-      };
-    } catch (error) {
-      // If we get a specific error, provide a clearer message
-      if (
-        error.message &&
-        (error.message.includes("Signing is not supported") ||
-          error.message.includes("sign") ||
-          error.message.includes("signature"))
-      ) {
-        console.error("Signing error during NFT minting:", error);
-        throw new Error(
-          `NFT minting failed: The XRPL node doesn't support transaction signing. Please ensure the proxy server is running.`
-        );
-      }
+    if (!acceptResponse.ok) {
+      throw new Error(`HTTP error ${acceptResponse.status}`);
+    }
 
-      // Other errors are rethrown
-      throw error;
+    const acceptResult = await acceptResponse.json();
+    const engineResult = acceptResult.result?.engine_result || "";
+    if (acceptResult.error || !engineResult.startsWith("tes")) {
+      throw new Error(
+        `Claiming egg failed: ${
+          acceptResult.error_message ||
+          acceptResult.result?.engine_result_message ||
+          acceptResult.error ||
+          engineResult
+        }`
+      );
     }
-  } catch (error) {
-    console.error("Error minting NFT:", error);
-    throw error;
+    results.push(acceptResult.result);
   }
+
+  return results;
 }
 
 // Claim battle reward
@@ -0,0 +1,79 @@
+// Captures codec fixtures from a running node.
+//
+//   node hook/codec/capture.js [--rpc URL] tx HASH NAME "description"
+//   node hook/codec/capture.js [--rpc URL] entry INDEX NAME "description"
+//
+// Calls `tx` (or `ledger_entry`) with binary false and true and writes
+// hook/codec/fixtures/NAME.json in the format tests/round_trip.rs reads,
+// naming the node it came from in the description. The default URL is the
+// docker-compose node's admin JSON-RPC port. SetHook, EmitDetails and
+// HookExecutions only exist on Xahau, so capture those from a xahaud node.
+// Needs Node 18 or later (global fetch).
+
+const fs = require('fs');
+const path = require('path');
+
+const usage = 'usage: node capture.js [--rpc URL] tx HASH NAME "description"\n' +
+  '       node capture.js [--rpc URL] entry INDEX NAME "description"';
+
+async function rpc(url, method, params) {
+  const response = await fetch(url, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ method, params: [params] }),
+  });
+  const { result } = await response.json();
+  if (!result || result.status !== 'success') {
+    throw new Error(`${method}: ${JSON.stringify(result)}`);
+  }
+  delete result.status;
+  return result;
+}
+
+async function captureTx(url, hash) {
+  const json = await rpc(url, 'tx', { transaction: hash, binary: false });
+  const binary = await rpc(url, 'tx', { transaction: hash, binary: true });
+  // API v1 names the blobs tx and meta, v2 tx_blob and meta_blob
+  return {
+    json,
+    binary: binary.tx_blob || binary.tx,
+    meta_binary: binary.meta_blob || binary.meta,
+    command: 'tx',
+  };
+}
+
+async function captureEntry(url, index) {
+  const json = await rpc(url, 'ledger_entry', { index, binary: false });
+  const binary = await rpc(url, 'ledger_entry', { index, binary: true });
+  return { json: json.node, binary: binary.node_binary, command: 'ledger_entry' };
+}
+
+async function main(args) {
+  let url = 'http://localhost:6006';
+  if (args[0] === '--rpc') {
+    url = args[1];
+    args = args.slice(2);
+  }
+  const [kind, id, name, description] = args;
+  if (!['tx', 'entry'].includes(kind) || !id || !name || !description) {
+    throw new Error(usage);
+  }
+
+  const captured = kind === 'tx' ? await captureTx(url, id) : await captureEntry(url, id);
+  const fixture = {
+    description: `${description}. Captured from ${url} with ${captured.command} (binary false and true)`,
+    json: captured.json,
+    binary: captured.binary.toUpperCase(),
+  };
+  if (captured.meta_binary) {
+    fixture.meta_binary = captured.meta_binary.toUpperCase();
+  }
+  const file = path.join(__dirname, 'fixtures', `${name}.json`);
+  fs.writeFileSync(file, JSON.stringify(fixture, null, 2) + '\n');
+  console.log(`wrote ${file}`);
+}
+
+main(process.argv.slice(2)).catch((error) => {
+  console.error(error.message);
+  process.exit(2);
+});
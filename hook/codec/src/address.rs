@@ -0,0 +1,105 @@
+//! Classic XRPL addresses: base58 in the ripple alphabet over a `0x00`
+//! version byte, the 20-byte account ID and a 4-byte double-SHA-256
+//! checksum.
+
+use sha2::{Digest, Sha256};
+
+const ALPHABET: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+
+/// Decodes an `r...` address to its account ID.
+pub fn decode_account(address: &str) -> Result<[u8; 20], String> {
+    let bytes = decode_base58(address).ok_or_else(|| format!("{address:?} is not a base58 address"))?;
+    let [0, payload @ .., c0, c1, c2, c3] = bytes.as_slice() else {
+        return Err(format!("{address:?} is not an account address"));
+    };
+    let mut account = [0u8; 20];
+    if payload.len() != account.len() || checksum(&bytes[..21]) != [*c0, *c1, *c2, *c3] {
+        return Err(format!("{address:?} is not an account address or has a bad checksum"));
+    }
+    account.copy_from_slice(payload);
+    Ok(account)
+}
+
+/// Encodes an account ID as its `r...` address.
+pub fn encode_account(account: &[u8; 20]) -> String {
+    let mut bytes = vec![0u8];
+    bytes.extend_from_slice(account);
+    bytes.extend_from_slice(&checksum(&bytes));
+    encode_base58(&bytes)
+}
+
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Big-endian bytes of a base58 string; each leading `r` (zero digit) is a
+/// leading zero byte.
+fn decode_base58(text: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in text.bytes() {
+        let mut carry = ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = text.bytes().take_while(|&c| c == ALPHABET[0]).count();
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes);
+    Some(decoded)
+}
+
+/// A base58 string of big-endian `bytes`; each leading zero byte is a
+/// leading `r`.
+fn encode_base58(bytes: &[u8]) -> String {
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += *digit as u32 * 256;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut text = vec![ALPHABET[0]; zeros];
+    text.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize]));
+    String::from_utf8(text).expect("the alphabet is ASCII")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::encode as hex;
+
+    #[test]
+    fn decodes_the_genesis_account() {
+        let account = decode_account("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh").unwrap();
+        assert_eq!(hex(&account), "B5F762798A53D543A014CAF8B297CFF8F2F937E8");
+        assert_eq!(hex(&decode_account("rrrrrrrrrrrrrrrrrrrrrhoLvTp").unwrap()), "0".repeat(40));
+    }
+
+    #[test]
+    fn encodes_what_it_decodes() {
+        for address in ["rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh", "rrrrrrrrrrrrrrrrrrrrrhoLvTp", "rMBzp8CgpE441cp5PVyA9rpVV7oT8hP3ys"] {
+            assert_eq!(encode_account(&decode_account(address).unwrap()), address);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        assert!(decode_account("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi").is_err());
+        assert!(decode_account("rHb9CJAWyB4rj91VRWn96DkukG4bwdty0h").is_err());
+        assert!(decode_account("").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Amounts: XRP as a string of drops, issued currency as
+//! `{"currency", "issuer", "value"}` over an 8-byte value, a 20-byte
+//! currency code and the issuer's account ID.
+//!
+//! Issued values print the way rippled prints them, so a value read from
+//! binary matches what `tx` returned.
+
+use serde_json::{json, Value};
+
+use crate::address::{decode_account, encode_account};
+use crate::{hex, string};
+
+const NOT_XRP_BIT: u64 = 1 << 63;
+const POSITIVE_BIT: u64 = 1 << 62;
+const MANTISSA_MASK: u64 = (1 << 54) - 1;
+const MAX_DROPS: u64 = 100_000_000_000_000_000;
+const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+const MIN_EXPONENT: i32 = -96;
+const MAX_EXPONENT: i32 = 80;
+const EXPONENT_BIAS: i32 = 97;
+
+/// Characters rippled allows in a three-letter currency code.
+const ISO_CHARACTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789<>(){}[]|?!@#$%^&*";
+
+/// 8 bytes for XRP, 48 for an issued amount.
+pub fn to_bytes(value: &Value) -> Result<Vec<u8>, String> {
+    match value {
+        Value::String(drops) => {
+            let (negative, digits) = sign(drops);
+            let invalid = || format!("{drops:?} is not a number of drops");
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let drops = digits.parse::<u64>().ok().filter(|&drops| drops <= MAX_DROPS).ok_or_else(invalid)?;
+            let raw = if negative { drops } else { drops | POSITIVE_BIT };
+            Ok(raw.to_be_bytes().to_vec())
+        }
+        Value::Object(iou) => {
+            let currency = currency(string(&iou["currency"])?)?;
+            if currency == [0; 20] {
+                return Err("XRP is not an issued currency".to_string());
+            }
+            let mut bytes = iou_value(string(&iou["value"])?)?.to_be_bytes().to_vec();
+            bytes.extend_from_slice(&currency);
+            bytes.extend_from_slice(&decode_account(string(&iou["issuer"])?)?);
+            Ok(bytes)
+        }
+        _ => Err("not an amount".to_string()),
+    }
+}
+
+/// Reads an amount off the front of `bytes`, returning it and its length.
+pub fn to_json(bytes: &[u8]) -> Result<(Value, usize), String> {
+    let raw = u64::from_be_bytes(bytes.get(..8).ok_or("truncated amount")?.try_into().unwrap());
+    if raw & NOT_XRP_BIT == 0 {
+        let drops = raw & !POSITIVE_BIT;
+        let sign = if raw & POSITIVE_BIT == 0 && drops != 0 { "-" } else { "" };
+        return Ok((json!(format!("{sign}{drops}")), 8));
+    }
+    let bytes = bytes.get(..48).ok_or("truncated issued amount")?;
+    let value = json!({
+        "currency": currency_code(bytes[8..28].try_into().unwrap()),
+        "issuer": encode_account(bytes[28..].try_into().unwrap()),
+        "value": iou_text(raw),
+    });
+    Ok((value, 48))
+}
+
+/// A three-letter code, `"XRP"` for all zeros, or 40 hex digits.
+pub fn currency(code: &str) -> Result<[u8; 20], String> {
+    if code.len() == 40 {
+        return hex::decode_array(code);
+    }
+    let mut currency = [0u8; 20];
+    match code.as_bytes() {
+        b"XRP" => {}
+        &[a, b, c] if [a, b, c].iter().all(|ch| ISO_CHARACTERS.contains(ch)) => currency[12..15].copy_from_slice(&[a, b, c]),
+        _ => return Err(format!("{code:?} is not a currency code")),
+    }
+    Ok(currency)
+}
+
+/// The code of `currency` as rippled prints it.
+pub fn currency_code(currency: &[u8; 20]) -> String {
+    if *currency == [0; 20] {
+        return "XRP".to_string();
+    }
+    let iso = &currency[12..15];
+    let standard = currency[..12] == [0; 12] && currency[15..] == [0; 5] && iso.iter().all(|c| ISO_CHARACTERS.contains(c));
+    if standard && iso != b"XRP" {
+        String::from_utf8(iso.to_vec()).expect("ISO characters are ASCII")
+    } else {
+        hex::encode(currency)
+    }
+}
+
+/// The 8-byte value of a decimal such as `"12.5"`, `"-3"` or `"1e-6"`.
+/// Digits past the 16 an amount keeps are truncated; values too small to
+/// represent become zero.
+pub fn iou_value(text: &str) -> Result<u64, String> {
+    let invalid = || format!("{text:?} is not a decimal");
+    let (negative, unsigned) = sign(text);
+    let (number, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((number, exponent)) => (number, exponent.parse::<i32>().map_err(|_| invalid())?),
+        None => (unsigned, 0),
+    };
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digits = format!("{whole}{fraction}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(NOT_XRP_BIT);
+    }
+    let kept = digits.len().min(16);
+    let mut mantissa: u64 = digits[..kept].parse().map_err(|_| invalid())?;
+    let mut exponent = exponent - fraction.len() as i32 + (digits.len() - kept) as i32;
+    while mantissa < MIN_MANTISSA {
+        mantissa *= 10;
+        exponent -= 1;
+    }
+    if exponent < MIN_EXPONENT {
+        return Ok(NOT_XRP_BIT);
+    }
+    if exponent > MAX_EXPONENT {
+        return Err(format!("{text:?} is out of range"));
+    }
+    let sign = if negative { 0 } else { POSITIVE_BIT };
+    Ok(NOT_XRP_BIT | sign | ((exponent + EXPONENT_BIAS) as u64) << 54 | mantissa)
+}
+
+/// A serialized issued value as rippled's `STAmount::getText` prints it:
+/// plain decimal, or `mantissa` e `exponent` when the exponent is outside
+/// -25..=-5.
+pub fn iou_text(raw: u64) -> String {
+    let mantissa = raw & MANTISSA_MASK;
+    if mantissa == 0 {
+        return "0".to_string();
+    }
+    let exponent = ((raw >> 54) & 0xFF) as i32 - EXPONENT_BIAS;
+    let sign = if raw & POSITIVE_BIT == 0 { "-" } else { "" };
+    debug_assert!((MIN_MANTISSA..=MAX_MANTISSA).contains(&mantissa));
+    if exponent != 0 && !(-25..=-5).contains(&exponent) {
+        return format!("{sign}{mantissa}e{exponent}");
+    }
+    // Pad so the decimal point falls inside, then split and trim
+    let padded = format!("{}{mantissa}{}", "0".repeat(27), "0".repeat(23));
+    let (whole, fraction) = padded.split_at((exponent + 43) as usize);
+    let whole = whole.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    if fraction.is_empty() {
+        format!("{sign}{whole}")
+    } else {
+        format!("{sign}{whole}.{fraction}")
+    }
+}
+
+fn sign(text: &str) -> (bool, &str) {
+    match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+    const GENESIS_ID: &str = "B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+
+    #[test]
+    fn converts_xrp() {
+        assert_eq!(to_bytes(&json!("12")).unwrap(), [0x40, 0, 0, 0, 0, 0, 0, 12]);
+        assert_eq!(to_bytes(&json!("0")).unwrap(), [0x40, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(to_bytes(&json!("-12")).unwrap(), [0, 0, 0, 0, 0, 0, 0, 12]);
+        assert_eq!(to_json(&[0x40, 0, 0, 0, 0, 0, 0, 12]).unwrap(), (json!("12"), 8));
+        assert_eq!(to_json(&[0, 0, 0, 0, 0, 0, 0, 12]).unwrap(), (json!("-12"), 8));
+        for bad in [json!(""), json!("1.5"), json!("100000000000000001"), json!(12)] {
+            assert!(to_bytes(&bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn converts_issued_amounts() {
+        let usd = json!({ "currency": "USD", "issuer": GENESIS, "value": "1.5" });
+        let expected = [
+            "D485543DF729C000",                         // 1.5 as 1500000000000000e-15
+            "0000000000000000000000005553440000000000", // USD
+            GENESIS_ID,
+        ]
+        .concat();
+        let bytes = to_bytes(&usd).unwrap();
+        assert_eq!(hex::encode(&bytes), expected);
+        assert_eq!(to_json(&bytes).unwrap(), (usd, 48));
+        assert!(to_bytes(&json!({ "currency": "XRP", "issuer": GENESIS, "value": "1" })).is_err());
+        assert!(to_json(&bytes[..47]).is_err());
+    }
+
+    #[test]
+    fn parses_values() {
+        assert_eq!(iou_value("1.5").unwrap(), 0xD485543DF729C000);
+        assert_eq!(iou_value("0.000001000").unwrap(), iou_value("1e-6").unwrap());
+        assert_eq!(iou_value("25e3").unwrap(), iou_value("25000").unwrap());
+        assert_eq!(iou_value("12345678901234567890123").unwrap(), iou_value("1234567890123456e7").unwrap());
+        assert_eq!(iou_value("0.0").unwrap(), NOT_XRP_BIT);
+        assert_eq!(iou_value("1e-200").unwrap(), NOT_XRP_BIT);
+        assert_eq!(iou_value("-1.5").unwrap(), 0xD485543DF729C000 & !POSITIVE_BIT);
+        for bad in ["", "-", "1.2.3", "1e", "abc", "1e200"] {
+            assert!(iou_value(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn prints_values_as_rippled_does() {
+        for (text, printed) in [
+            ("7072.8", "7072.8"),
+            ("1.5", "1.5"),
+            ("-10", "-10"),
+            ("0.000001", "0.000001"),
+            ("1e-10", "0.0000000001"),
+            ("1e-11", "1000000000000000e-26"),
+            ("1000000000000000", "1000000000000000"),
+            ("1e20", "1000000000000000e5"),
+            ("0", "0"),
+        ] {
+            assert_eq!(iou_text(iou_value(text).unwrap()), printed, "{text}");
+        }
+    }
+
+    #[test]
+    fn converts_currency_codes() {
+        assert_eq!(currency_code(&currency("USD").unwrap()), "USD");
+        assert_eq!(currency("XRP").unwrap(), [0; 20]);
+        assert_eq!(currency_code(&[0; 20]), "XRP");
+        let spark = "535041524B000000000000000000000000000000";
+        assert_eq!(currency_code(&currency(spark).unwrap()), spark);
+        assert!(currency("US").is_err());
+        assert!(currency("U D").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Binary to JSON.
+
+use serde_json::{json, Map, Value};
+
+use crate::address::encode_account;
+use crate::definitions::{field_by_code, name_of, name_table, Field, TypeCode, TRANSACTION_RESULTS};
+use crate::{amount, hex, ARRAY_END, OBJECT_END, PATH_ACCOUNT, PATH_BOUNDARY, PATH_CURRENCY, PATH_ISSUER, PATH_SET_END};
+
+/// The fields of a binary transaction or ledger object, as JSON.
+pub fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    Ok(Value::Object(reader.object(false)?))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self.bytes.get(self.pos..).and_then(|rest| rest.get(..len)).ok_or_else(|| format!("truncated at byte {}", self.pos))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Consumes `marker` if it is next.
+    fn end(&mut self, marker: u8) -> bool {
+        let found = self.bytes.get(self.pos) == Some(&marker);
+        self.pos += found as usize;
+        found
+    }
+
+    /// Fields up to the end of the input, or up to the object end marker if
+    /// `nested`.
+    fn object(&mut self, nested: bool) -> Result<Map<String, Value>, String> {
+        let mut fields = Map::new();
+        while if nested { !self.end(OBJECT_END) } else { self.pos < self.bytes.len() } {
+            let field = self.header()?;
+            let value = self.value(field).map_err(|e| format!("{}: {e}", field.name))?;
+            if fields.insert(field.name.to_string(), value).is_some() {
+                return Err(format!("{} appears twice", field.name));
+            }
+        }
+        Ok(fields)
+    }
+
+    fn header(&mut self) -> Result<&'static Field, String> {
+        let start = self.pos;
+        let first = self.byte()?;
+        let (mut type_code, mut nth) = (first >> 4, first & 0x0F);
+        if type_code == 0 {
+            type_code = self.byte()?;
+        }
+        if nth == 0 {
+            nth = self.byte()?;
+        }
+        field_by_code((type_code as u32) << 16 | nth as u32).ok_or_else(|| format!("unknown field (type {type_code}, field {nth}) at byte {start}"))
+    }
+
+    fn length(&mut self) -> Result<usize, String> {
+        let first = self.byte()? as usize;
+        match first {
+            0..=192 => Ok(first),
+            193..=240 => Ok(193 + ((first - 193) << 8) + self.byte()? as usize),
+            241..=254 => {
+                let [b1, b2] = self.array()?;
+                Ok(12481 + ((first - 241) << 16) + ((b1 as usize) << 8) + b2 as usize)
+            }
+            _ => Err(format!("bad length prefix at byte {}", self.pos - 1)),
+        }
+    }
+
+    fn value(&mut self, field: &Field) -> Result<Value, String> {
+        Ok(match field.type_code {
+            TypeCode::UInt8 => {
+                let code = self.byte()?;
+                match name_of(TRANSACTION_RESULTS, code).filter(|_| field.name == "TransactionResult") {
+                    Some(name) => json!(name),
+                    None => json!(code),
+                }
+            }
+            TypeCode::UInt16 => {
+                let number = u16::from_be_bytes(self.array()?);
+                match name_table(field).and_then(|table| name_of(table, number)) {
+                    Some(name) => json!(name),
+                    None => json!(number),
+                }
+            }
+            TypeCode::UInt32 => json!(u32::from_be_bytes(self.array()?)),
+            // As rippled prints them: hex without leading zeros
+            TypeCode::UInt64 => json!(format!("{:x}", u64::from_be_bytes(self.array()?))),
+            TypeCode::Hash128 => json!(hex::encode(self.take(16)?)),
+            TypeCode::Hash160 => json!(hex::encode(self.take(20)?)),
+            TypeCode::Hash256 => json!(hex::encode(self.take(32)?)),
+            TypeCode::Amount => {
+                let (value, len) = amount::to_json(&self.bytes[self.pos..])?;
+                self.pos += len;
+                value
+            }
+            TypeCode::Blob => {
+                let len = self.length()?;
+                json!(hex::encode(self.take(len)?))
+            }
+            TypeCode::AccountID => {
+                if self.length()? != 20 {
+                    return Err("account IDs are 20 bytes".to_string());
+                }
+                json!(encode_account(&self.array()?))
+            }
+            TypeCode::STObject => Value::Object(self.object(true)?),
+            TypeCode::STArray => {
+                let mut entries = Vec::new();
+                while !self.end(ARRAY_END) {
+                    let inner = self.header()?;
+                    if inner.type_code != TypeCode::STObject {
+                        return Err(format!("{} is not an object field", inner.name));
+                    }
+                    let fields = self.object(true).map_err(|e| format!("{}: {e}", inner.name))?;
+                    entries.push(json!({ inner.name: fields }));
+                }
+                Value::Array(entries)
+            }
+            TypeCode::PathSet => self.path_set()?,
+            TypeCode::Vector256 => {
+                let len = self.length()?;
+                if len % 32 != 0 {
+                    return Err(format!("{len} bytes is not a whole number of hashes"));
+                }
+                Value::Array(self.take(len)?.chunks(32).map(|hash| json!(hex::encode(hash))).collect())
+            }
+        })
+    }
+
+    fn path_set(&mut self) -> Result<Value, String> {
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+        loop {
+            match self.byte()? {
+                PATH_SET_END => break,
+                PATH_BOUNDARY => paths.push(Value::Array(std::mem::take(&mut path))),
+                kind if kind & !(PATH_ACCOUNT | PATH_CURRENCY | PATH_ISSUER) == 0 => {
+                    let mut step = Map::new();
+                    if kind & PATH_ACCOUNT != 0 {
+                        step.insert("account".into(), json!(encode_account(&self.array()?)));
+                    }
+                    if kind & PATH_CURRENCY != 0 {
+                        step.insert("currency".into(), json!(amount::currency_code(&self.array()?)));
+                    }
+                    if kind & PATH_ISSUER != 0 {
+                        step.insert("issuer".into(), json!(encode_account(&self.array()?)));
+                    }
+                    path.push(Value::Object(step));
+                }
+                kind => return Err(format!("bad path step type {kind:#x}")),
+            }
+        }
+        paths.push(Value::Array(path));
+        Ok(Value::Array(paths))
+    }
+}
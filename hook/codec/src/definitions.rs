@@ -0,0 +1,404 @@
+//! The part of rippled's `definitions.json` this workspace meets: field
+//! names, type codes and field codes, and the names `TransactionType`,
+//! `LedgerEntryType` and `TransactionResult` take in JSON.
+//!
+//! Covers the fields of the transactions and ledger objects the hooks and
+//! tooling handle, transaction metadata, and the Xahau hook fields. A field
+//! missing here is an error to encode or decode, never skipped; add it with
+//! the codes from the network's `server_definitions`.
+
+/// Serialized type of a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TypeCode {
+    UInt16 = 1,
+    UInt32 = 2,
+    UInt64 = 3,
+    Hash128 = 4,
+    Hash256 = 5,
+    Amount = 6,
+    Blob = 7,
+    AccountID = 8,
+    STObject = 14,
+    STArray = 15,
+    UInt8 = 16,
+    Hash160 = 17,
+    PathSet = 18,
+    Vector256 = 19,
+}
+
+impl TypeCode {
+    /// Whether values of this type carry a length prefix.
+    pub fn is_vl(self) -> bool {
+        matches!(self, TypeCode::Blob | TypeCode::AccountID | TypeCode::Vector256)
+    }
+}
+
+/// A field: its JSON name, type and field code within the type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub type_code: TypeCode,
+    pub nth: u8,
+}
+
+impl Field {
+    const fn new(name: &'static str, type_code: TypeCode, nth: u8) -> Field {
+        Field { name, type_code, nth }
+    }
+
+    /// Field code as hooks spell it: type << 16 | field.
+    pub fn code(&self) -> u32 {
+        (self.type_code as u32) << 16 | self.nth as u32
+    }
+
+    /// Canonical order: by type code, then field code.
+    pub fn sort_key(&self) -> (u8, u8) {
+        (self.type_code as u8, self.nth)
+    }
+
+    /// Whether the field is part of what a signature signs.
+    pub fn is_signing(&self) -> bool {
+        !matches!(self.name, "TxnSignature" | "Signers")
+    }
+}
+
+use TypeCode::*;
+
+/// Every known field.
+pub const FIELDS: &[Field] = &[
+    Field::new("LedgerEntryType", UInt16, 1),
+    Field::new("TransactionType", UInt16, 2),
+    Field::new("SignerWeight", UInt16, 3),
+    Field::new("TransferFee", UInt16, 4),
+    Field::new("HookStateChangeCount", UInt16, 17),
+    Field::new("HookEmitCount", UInt16, 18),
+    Field::new("HookExecutionIndex", UInt16, 19),
+    Field::new("HookApiVersion", UInt16, 20),
+    Field::new("NetworkID", UInt32, 1),
+    Field::new("Flags", UInt32, 2),
+    Field::new("SourceTag", UInt32, 3),
+    Field::new("Sequence", UInt32, 4),
+    Field::new("PreviousTxnLgrSeq", UInt32, 5),
+    Field::new("LedgerSequence", UInt32, 6),
+    Field::new("CloseTime", UInt32, 7),
+    Field::new("ParentCloseTime", UInt32, 8),
+    Field::new("SigningTime", UInt32, 9),
+    Field::new("Expiration", UInt32, 10),
+    Field::new("TransferRate", UInt32, 11),
+    Field::new("WalletSize", UInt32, 12),
+    Field::new("OwnerCount", UInt32, 13),
+    Field::new("DestinationTag", UInt32, 14),
+    Field::new("HighQualityIn", UInt32, 16),
+    Field::new("HighQualityOut", UInt32, 17),
+    Field::new("LowQualityIn", UInt32, 18),
+    Field::new("LowQualityOut", UInt32, 19),
+    Field::new("QualityIn", UInt32, 20),
+    Field::new("QualityOut", UInt32, 21),
+    Field::new("OfferSequence", UInt32, 25),
+    Field::new("FirstLedgerSequence", UInt32, 26),
+    Field::new("LastLedgerSequence", UInt32, 27),
+    Field::new("TransactionIndex", UInt32, 28),
+    Field::new("SetFlag", UInt32, 33),
+    Field::new("ClearFlag", UInt32, 34),
+    Field::new("SignerQuorum", UInt32, 35),
+    Field::new("CancelAfter", UInt32, 36),
+    Field::new("FinishAfter", UInt32, 37),
+    Field::new("TicketCount", UInt32, 40),
+    Field::new("TicketSequence", UInt32, 41),
+    Field::new("NFTokenTaxon", UInt32, 42),
+    Field::new("MintedNFTokens", UInt32, 43),
+    Field::new("BurnedNFTokens", UInt32, 44),
+    Field::new("HookStateCount", UInt32, 45),
+    Field::new("EmitGeneration", UInt32, 46),
+    Field::new("IndexNext", UInt64, 1),
+    Field::new("IndexPrevious", UInt64, 2),
+    Field::new("BookNode", UInt64, 3),
+    Field::new("OwnerNode", UInt64, 4),
+    Field::new("BaseFee", UInt64, 5),
+    Field::new("ExchangeRate", UInt64, 6),
+    Field::new("LowNode", UInt64, 7),
+    Field::new("HighNode", UInt64, 8),
+    Field::new("DestinationNode", UInt64, 9),
+    Field::new("EmitBurden", UInt64, 12),
+    Field::new("HookInstructionCount", UInt64, 17),
+    Field::new("HookReturnCode", UInt64, 18),
+    Field::new("EmailHash", Hash128, 1),
+    Field::new("LedgerHash", Hash256, 1),
+    Field::new("ParentHash", Hash256, 2),
+    Field::new("TransactionHash", Hash256, 3),
+    Field::new("AccountHash", Hash256, 4),
+    Field::new("PreviousTxnID", Hash256, 5),
+    Field::new("LedgerIndex", Hash256, 6),
+    Field::new("WalletLocator", Hash256, 7),
+    Field::new("RootIndex", Hash256, 8),
+    Field::new("AccountTxnID", Hash256, 9),
+    Field::new("NFTokenID", Hash256, 10),
+    Field::new("EmitParentTxnID", Hash256, 11),
+    Field::new("EmitNonce", Hash256, 12),
+    Field::new("EmitHookHash", Hash256, 13),
+    Field::new("BookDirectory", Hash256, 16),
+    Field::new("InvoiceID", Hash256, 17),
+    Field::new("Amendment", Hash256, 19),
+    Field::new("HookOn", Hash256, 20),
+    Field::new("Digest", Hash256, 21),
+    Field::new("Channel", Hash256, 22),
+    Field::new("CheckID", Hash256, 24),
+    Field::new("PreviousPageMin", Hash256, 26),
+    Field::new("NextPageMin", Hash256, 27),
+    Field::new("NFTokenBuyOffer", Hash256, 28),
+    Field::new("NFTokenSellOffer", Hash256, 29),
+    Field::new("HookStateKey", Hash256, 30),
+    Field::new("HookHash", Hash256, 31),
+    Field::new("HookNamespace", Hash256, 32),
+    Field::new("HookSetTxnID", Hash256, 33),
+    Field::new("Amount", Amount, 1),
+    Field::new("Balance", Amount, 2),
+    Field::new("LimitAmount", Amount, 3),
+    Field::new("TakerPays", Amount, 4),
+    Field::new("TakerGets", Amount, 5),
+    Field::new("LowLimit", Amount, 6),
+    Field::new("HighLimit", Amount, 7),
+    Field::new("Fee", Amount, 8),
+    Field::new("SendMax", Amount, 9),
+    Field::new("DeliverMin", Amount, 10),
+    Field::new("DeliveredAmount", Amount, 18),
+    Field::new("NFTokenBrokerFee", Amount, 19),
+    Field::new("PublicKey", Blob, 1),
+    Field::new("MessageKey", Blob, 2),
+    Field::new("SigningPubKey", Blob, 3),
+    Field::new("TxnSignature", Blob, 4),
+    Field::new("URI", Blob, 5),
+    Field::new("Signature", Blob, 6),
+    Field::new("Domain", Blob, 7),
+    Field::new("CreateCode", Blob, 11),
+    Field::new("MemoType", Blob, 12),
+    Field::new("MemoData", Blob, 13),
+    Field::new("MemoFormat", Blob, 14),
+    Field::new("Fulfillment", Blob, 16),
+    Field::new("Condition", Blob, 17),
+    Field::new("HookStateData", Blob, 22),
+    Field::new("HookReturnString", Blob, 23),
+    Field::new("HookParameterName", Blob, 24),
+    Field::new("HookParameterValue", Blob, 25),
+    Field::new("Blob", Blob, 26),
+    Field::new("Account", AccountID, 1),
+    Field::new("Owner", AccountID, 2),
+    Field::new("Destination", AccountID, 3),
+    Field::new("Issuer", AccountID, 4),
+    Field::new("Authorize", AccountID, 5),
+    Field::new("Unauthorize", AccountID, 6),
+    Field::new("RegularKey", AccountID, 8),
+    Field::new("NFTokenMinter", AccountID, 9),
+    Field::new("EmitCallback", AccountID, 10),
+    Field::new("HookAccount", AccountID, 16),
+    Field::new("TransactionMetaData", STObject, 2),
+    Field::new("CreatedNode", STObject, 3),
+    Field::new("DeletedNode", STObject, 4),
+    Field::new("ModifiedNode", STObject, 5),
+    Field::new("PreviousFields", STObject, 6),
+    Field::new("FinalFields", STObject, 7),
+    Field::new("NewFields", STObject, 8),
+    Field::new("Memo", STObject, 10),
+    Field::new("SignerEntry", STObject, 11),
+    Field::new("NFToken", STObject, 12),
+    Field::new("EmitDetails", STObject, 13),
+    Field::new("Hook", STObject, 14),
+    Field::new("Signer", STObject, 16),
+    Field::new("Majority", STObject, 18),
+    Field::new("EmittedTxn", STObject, 20),
+    Field::new("HookExecution", STObject, 21),
+    Field::new("HookDefinition", STObject, 22),
+    Field::new("HookParameter", STObject, 23),
+    Field::new("HookGrant", STObject, 24),
+    Field::new("Signers", STArray, 3),
+    Field::new("SignerEntries", STArray, 4),
+    Field::new("AffectedNodes", STArray, 8),
+    Field::new("Memos", STArray, 9),
+    Field::new("NFTokens", STArray, 10),
+    Field::new("Hooks", STArray, 11),
+    Field::new("Majorities", STArray, 16),
+    Field::new("HookExecutions", STArray, 18),
+    Field::new("HookParameters", STArray, 19),
+    Field::new("HookGrants", STArray, 20),
+    Field::new("TransactionResult", UInt8, 3),
+    Field::new("TickSize", UInt8, 16),
+    Field::new("HookResult", UInt8, 18),
+    Field::new("TakerPaysCurrency", Hash160, 1),
+    Field::new("TakerPaysIssuer", Hash160, 2),
+    Field::new("TakerGetsCurrency", Hash160, 3),
+    Field::new("TakerGetsIssuer", Hash160, 4),
+    Field::new("Paths", PathSet, 1),
+    Field::new("Indexes", Vector256, 1),
+    Field::new("Hashes", Vector256, 2),
+    Field::new("Amendments", Vector256, 3),
+    Field::new("NFTokenOffers", Vector256, 4),
+];
+
+/// Transaction types by name.
+pub const TRANSACTION_TYPES: &[(&str, u16)] = &[
+    ("Payment", 0),
+    ("EscrowCreate", 1),
+    ("EscrowFinish", 2),
+    ("AccountSet", 3),
+    ("EscrowCancel", 4),
+    ("SetRegularKey", 5),
+    ("OfferCreate", 7),
+    ("OfferCancel", 8),
+    ("TicketCreate", 10),
+    ("SignerListSet", 12),
+    ("PaymentChannelCreate", 13),
+    ("PaymentChannelFund", 14),
+    ("PaymentChannelClaim", 15),
+    ("CheckCreate", 16),
+    ("CheckCash", 17),
+    ("CheckCancel", 18),
+    ("DepositPreauth", 19),
+    ("TrustSet", 20),
+    ("AccountDelete", 21),
+    ("SetHook", 22),
+    ("NFTokenMint", 25),
+    ("NFTokenBurn", 26),
+    ("NFTokenCreateOffer", 27),
+    ("NFTokenCancelOffer", 28),
+    ("NFTokenAcceptOffer", 29),
+    ("Invoke", 99),
+    ("EnableAmendment", 100),
+    ("SetFee", 101),
+    ("UNLModify", 102),
+];
+
+/// Ledger entry types by name.
+pub const LEDGER_ENTRY_TYPES: &[(&str, u16)] = &[
+    ("Check", 0x43),
+    ("HookDefinition", 0x44),
+    ("EmittedTxn", 0x45),
+    ("Hook", 0x48),
+    ("NegativeUNL", 0x4E),
+    ("NFTokenPage", 0x50),
+    ("SignerList", 0x53),
+    ("Ticket", 0x54),
+    ("AccountRoot", 0x61),
+    ("DirectoryNode", 0x64),
+    ("Amendments", 0x66),
+    ("LedgerHashes", 0x68),
+    ("Offer", 0x6F),
+    ("DepositPreauth", 0x70),
+    ("RippleState", 0x72),
+    ("FeeSettings", 0x73),
+    ("Escrow", 0x75),
+    ("HookState", 0x76),
+    ("PayChannel", 0x78),
+    ("NFTokenOffer", 0x37),
+];
+
+/// Results a transaction in a ledger can have: success, or a `tec` code
+/// that claimed the fee.
+pub const TRANSACTION_RESULTS: &[(&str, u8)] = &[
+    ("tesSUCCESS", 0),
+    ("tecCLAIM", 100),
+    ("tecPATH_PARTIAL", 101),
+    ("tecUNFUNDED_ADD", 102),
+    ("tecUNFUNDED_OFFER", 103),
+    ("tecUNFUNDED_PAYMENT", 104),
+    ("tecFAILED_PROCESSING", 105),
+    ("tecDIR_FULL", 121),
+    ("tecINSUF_RESERVE_LINE", 122),
+    ("tecINSUF_RESERVE_OFFER", 123),
+    ("tecNO_DST", 124),
+    ("tecNO_DST_INSUF_XRP", 125),
+    ("tecNO_LINE_INSUF_RESERVE", 126),
+    ("tecNO_LINE_REDUNDANT", 127),
+    ("tecPATH_DRY", 128),
+    ("tecUNFUNDED", 129),
+    ("tecNO_ALTERNATIVE_KEY", 130),
+    ("tecNO_REGULAR_KEY", 131),
+    ("tecOWNERS", 132),
+    ("tecNO_ISSUER", 133),
+    ("tecNO_AUTH", 134),
+    ("tecNO_LINE", 135),
+    ("tecINSUFF_FEE", 136),
+    ("tecFROZEN", 137),
+    ("tecNO_TARGET", 138),
+    ("tecNO_PERMISSION", 139),
+    ("tecNO_ENTRY", 140),
+    ("tecINSUFFICIENT_RESERVE", 141),
+    ("tecNEED_MASTER_KEY", 142),
+    ("tecDST_TAG_NEEDED", 143),
+    ("tecINTERNAL", 144),
+    ("tecOVERSIZE", 145),
+    ("tecCRYPTOCONDITION_ERROR", 146),
+    ("tecINVARIANT_FAILED", 147),
+    ("tecEXPIRED", 148),
+    ("tecDUPLICATE", 149),
+    ("tecKILLED", 150),
+    ("tecHAS_OBLIGATIONS", 151),
+    ("tecTOO_SOON", 152),
+    ("tecHOOK_REJECTED", 153),
+    ("tecMAX_SEQUENCE_REACHED", 154),
+    ("tecNO_SUITABLE_NFTOKEN_PAGE", 155),
+    ("tecNFTOKEN_BUY_SELL_MISMATCH", 156),
+    ("tecNFTOKEN_OFFER_TYPE_MISMATCH", 157),
+    ("tecCANT_ACCEPT_OWN_NFTOKEN_OFFER", 158),
+    ("tecINSUFFICIENT_FUNDS", 159),
+    ("tecOBJECT_NOT_FOUND", 160),
+    ("tecINSUFFICIENT_PAYMENT", 161),
+];
+
+/// The field called `name`.
+pub fn field(name: &str) -> Result<&'static Field, String> {
+    FIELDS.iter().find(|field| field.name == name).ok_or_else(|| format!("unknown field {name}"))
+}
+
+/// The field with hook field code `code` (type << 16 | field).
+pub fn field_by_code(code: u32) -> Option<&'static Field> {
+    FIELDS.iter().find(|field| field.code() == code)
+}
+
+/// Names of the values of a UInt16 field that JSON spells by name.
+pub fn name_table(field: &Field) -> Option<&'static [(&'static str, u16)]> {
+    match field.name {
+        "TransactionType" => Some(TRANSACTION_TYPES),
+        "LedgerEntryType" => Some(LEDGER_ENTRY_TYPES),
+        _ => None,
+    }
+}
+
+/// Looks up `name` in a name table.
+pub fn value_of<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table.iter().find(|(known, _)| *known == name).map(|&(_, value)| value)
+}
+
+/// Looks up `value` in a name table.
+pub fn name_of<T: Copy + PartialEq>(table: &[(&'static str, T)], value: T) -> Option<&'static str> {
+    table.iter().find(|(_, known)| *known == value).map(|&(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_codes_are_unique() {
+        for (i, a) in FIELDS.iter().enumerate() {
+            for b in &FIELDS[i + 1..] {
+                assert_ne!(a.name, b.name);
+                assert_ne!(a.code(), b.code(), "{} and {}", a.name, b.name);
+            }
+        }
+        for table in [TRANSACTION_TYPES, LEDGER_ENTRY_TYPES] {
+            for (i, (name, code)) in table.iter().enumerate() {
+                assert!(table[i + 1..].iter().all(|(other, other_code)| other != name && other_code != code), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn looks_up_fields() {
+        let memos = field("Memos").unwrap();
+        assert_eq!((memos.type_code, memos.code()), (STArray, (15 << 16) + 9));
+        assert_eq!(field_by_code((8 << 16) + 1).map(|field| field.name), Some("Account"));
+        assert!(field("Bogus").is_err());
+        assert_eq!(value_of(TRANSACTION_TYPES, "SetHook"), Some(22));
+        assert_eq!(name_of(TRANSACTION_RESULTS, 153), Some("tecHOOK_REJECTED"));
+    }
+}
@@ -0,0 +1,186 @@
+//! JSON to binary.
+
+use serde_json::{Map, Value};
+
+use crate::address::decode_account;
+use crate::definitions::{self, name_table, value_of, Field, TypeCode, TRANSACTION_RESULTS};
+use crate::{amount, hex, string, ARRAY_END, OBJECT_END, PATH_ACCOUNT, PATH_BOUNDARY, PATH_CURRENCY, PATH_ISSUER, PATH_SET_END};
+
+/// What a single signature signs: `STX\0`, then the signing fields.
+const SIGNING_PREFIX: &[u8; 4] = b"STX\0";
+
+/// The fields of `json`, a transaction or ledger object, in canonical
+/// binary.
+pub fn encode(json: &Value) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    object(&mut out, json.as_object().ok_or("not a JSON object")?, false)?;
+    Ok(out)
+}
+
+/// What a single signer of the transaction `json` signs: the signing
+/// prefix, then every field but `TxnSignature` and `Signers`.
+pub fn encode_for_signing(json: &Value) -> Result<Vec<u8>, String> {
+    let mut out = SIGNING_PREFIX.to_vec();
+    object(&mut out, json.as_object().ok_or("not a JSON object")?, true)?;
+    Ok(out)
+}
+
+/// `value` serialized the way `otxn_field` returns `field`: blobs and
+/// accounts without their length prefix, objects and arrays with their end
+/// marker.
+pub fn field_value(field: &Field, value: &Value) -> Result<Vec<u8>, String> {
+    match field.type_code {
+        TypeCode::UInt8 => {
+            let code = match (field.name, value) {
+                ("TransactionResult", Value::String(name)) => {
+                    value_of(TRANSACTION_RESULTS, name).ok_or_else(|| format!("unknown TransactionResult {name}"))?
+                }
+                _ => uint(value, u8::MAX.into())? as u8,
+            };
+            Ok(vec![code])
+        }
+        TypeCode::UInt16 => {
+            let number = match (name_table(field), value) {
+                (Some(table), Value::String(name)) => value_of(table, name).ok_or_else(|| format!("unknown {} {name}", field.name))?,
+                _ => uint(value, u16::MAX.into())? as u16,
+            };
+            Ok(number.to_be_bytes().to_vec())
+        }
+        TypeCode::UInt32 => Ok((uint(value, u32::MAX.into())? as u32).to_be_bytes().to_vec()),
+        TypeCode::UInt64 => {
+            let text = string(value)?;
+            let valid = (1..=16).contains(&text.len()) && text.bytes().all(|b| b.is_ascii_hexdigit());
+            let number = u64::from_str_radix(text, 16).ok().filter(|_| valid).ok_or_else(|| format!("{text:?} is not up to 16 hex digits"))?;
+            Ok(number.to_be_bytes().to_vec())
+        }
+        TypeCode::Hash128 => hash(value, 16),
+        TypeCode::Hash160 => hash(value, 20),
+        TypeCode::Hash256 => hash(value, 32),
+        TypeCode::Amount => amount::to_bytes(value),
+        TypeCode::Blob => hex::decode(string(value)?),
+        TypeCode::AccountID => Ok(decode_account(string(value)?)?.to_vec()),
+        TypeCode::STObject => {
+            let mut out = Vec::new();
+            object(&mut out, value.as_object().ok_or("not an object")?, false)?;
+            out.push(OBJECT_END);
+            Ok(out)
+        }
+        TypeCode::STArray => {
+            let mut out = Vec::new();
+            for entry in value.as_array().ok_or("not an array")? {
+                // Each entry is `{"Memo": {...}}`: the object's field name and its fields
+                let entry = entry.as_object().filter(|entry| entry.len() == 1).ok_or("entries must be single-key objects")?;
+                let (name, inner) = entry.iter().next().unwrap();
+                let inner_field = definitions::field(name)?;
+                if inner_field.type_code != TypeCode::STObject {
+                    return Err(format!("{name} is not an object field"));
+                }
+                header(&mut out, inner_field);
+                out.extend(field_value(inner_field, inner).map_err(|e| format!("{name}: {e}"))?);
+            }
+            out.push(ARRAY_END);
+            Ok(out)
+        }
+        TypeCode::PathSet => path_set(value),
+        TypeCode::Vector256 => {
+            let mut out = Vec::new();
+            for hash in value.as_array().ok_or("not an array of hashes")? {
+                out.extend(self::hash(hash, 32)?);
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Fields of an object in canonical order, optionally only those a
+/// signature covers.
+fn object(out: &mut Vec<u8>, fields: &Map<String, Value>, signing: bool) -> Result<(), String> {
+    let mut entries = Vec::new();
+    for (name, value) in fields {
+        if name.starts_with(|c: char| c.is_ascii_lowercase()) {
+            continue;
+        }
+        let field = definitions::field(name)?;
+        if !signing || field.is_signing() {
+            entries.push((field, value));
+        }
+    }
+    entries.sort_by_key(|(field, _)| field.sort_key());
+    for (field, value) in entries {
+        let bytes = field_value(field, value).map_err(|e| format!("{}: {e}", field.name))?;
+        header(out, field);
+        if field.type_code.is_vl() {
+            length(out, bytes.len()).map_err(|e| format!("{}: {e}", field.name))?;
+        }
+        out.extend(bytes);
+    }
+    Ok(())
+}
+
+/// One to three bytes: type and field code in a nibble each where they fit,
+/// otherwise in a byte of their own.
+fn header(out: &mut Vec<u8>, field: &Field) {
+    match field.sort_key() {
+        (type_code, nth) if type_code < 16 && nth < 16 => out.push(type_code << 4 | nth),
+        (type_code, nth) if type_code < 16 => out.extend([type_code << 4, nth]),
+        (type_code, nth) if nth < 16 => out.extend([nth, type_code]),
+        (type_code, nth) => out.extend([0, type_code, nth]),
+    }
+}
+
+/// Length prefix of a variable-length value.
+fn length(out: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    match len {
+        0..=192 => out.push(len as u8),
+        193..=12480 => {
+            let len = len - 193;
+            out.extend([193 + (len >> 8) as u8, len as u8]);
+        }
+        12481..=918744 => {
+            let len = len - 12481;
+            out.extend([241 + (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        }
+        _ => return Err(format!("{len} bytes is too long for a field")),
+    }
+    Ok(())
+}
+
+/// Paths of steps, each step an object with any of `account`, `currency`
+/// and `issuer`. The `type` and `type_hex` rippled adds are implied by
+/// those and ignored.
+fn path_set(value: &Value) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for (i, path) in value.as_array().ok_or("not an array of paths")?.iter().enumerate() {
+        if i > 0 {
+            out.push(PATH_BOUNDARY);
+        }
+        for step in path.as_array().ok_or("a path is not an array of steps")? {
+            let account = step.get("account").map(|account| string(account).and_then(decode_account)).transpose()?;
+            let currency = step.get("currency").map(|currency| string(currency).and_then(amount::currency)).transpose()?;
+            let issuer = step.get("issuer").map(|issuer| string(issuer).and_then(decode_account)).transpose()?;
+            let kind = account.map_or(0, |_| PATH_ACCOUNT) | currency.map_or(0, |_| PATH_CURRENCY) | issuer.map_or(0, |_| PATH_ISSUER);
+            if kind == 0 {
+                return Err("a path step needs an account, currency or issuer".to_string());
+            }
+            out.push(kind);
+            for part in [account, currency, issuer].into_iter().flatten() {
+                out.extend(part);
+            }
+        }
+    }
+    out.push(PATH_SET_END);
+    Ok(out)
+}
+
+fn hash(value: &Value, len: usize) -> Result<Vec<u8>, String> {
+    let text = string(value)?;
+    let bytes = hex::decode(text)?;
+    if bytes.len() != len {
+        return Err(format!("{text:?} is not {len} bytes of hex"));
+    }
+    Ok(bytes)
+}
+
+fn uint(value: &Value, max: u64) -> Result<u64, String> {
+    value.as_u64().filter(|&n| n <= max).ok_or_else(|| format!("{value} is not an integer up to {max}"))
+}
@@ -0,0 +1,20 @@
+//! Uppercase hex, as rippled prints blobs and hashes.
+
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// Decodes hex in either case, with an optional `0x` prefix.
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!("{text:?} has an odd number of hex digits"));
+    }
+    let digit = |c: u8| (c as char).to_digit(16).ok_or_else(|| format!("{text:?} is not hex"));
+    digits.as_bytes().chunks(2).map(|pair| Ok((digit(pair[0])? << 4 | digit(pair[1])?) as u8)).collect()
+}
+
+/// Decodes exactly `N` bytes of hex.
+pub fn decode_array<const N: usize>(text: &str) -> Result<[u8; N], String> {
+    decode(text)?.try_into().map_err(|_| format!("{text:?} is not {N} bytes of hex"))
+}
@@ -0,0 +1,190 @@
+//! The XRPL binary codec, host-side: transactions and ledger objects in
+//! rippled's JSON form to canonical binary and back, the job xrpl.js does
+//! in the frontend, so the Rust tooling need not shell out to Node.
+//!
+//! [`encode`] writes a JSON object's fields in canonical order (by type
+//! code, then field code), as `tx_blob` and `node_binary` hold them.
+//! [`decode`] reads them back into the JSON `tx` and `ledger_entry`
+//! return. Both work from the table in [`definitions`]; a field it does not
+//! list is an error rather than silently dropped. Lower-case keys (`hash`,
+//! `meta`, `ledger_index`, ...) are API metadata, not fields, and are
+//! skipped.
+//!
+//! This is std code for tools and tests; hooks serialize with the no_std
+//! `stobject` module of the core crate.
+
+pub mod address;
+pub mod amount;
+mod decode;
+pub mod definitions;
+mod encode;
+pub mod hex;
+
+use serde_json::Value;
+
+pub use decode::decode;
+pub use encode::{encode, encode_for_signing, field_value};
+
+const OBJECT_END: u8 = 0xE1;
+const ARRAY_END: u8 = 0xF1;
+const PATH_BOUNDARY: u8 = 0xFF;
+const PATH_SET_END: u8 = 0x00;
+
+/// Path step type bits: which of account, currency and issuer follow.
+const PATH_ACCOUNT: u8 = 0x01;
+const PATH_CURRENCY: u8 = 0x10;
+const PATH_ISSUER: u8 = 0x20;
+
+fn string(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| format!("{value} is not a string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+    const GENESIS_ID: &str = "B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+
+    fn round_trip(json: &Value) -> String {
+        let binary = encode(json).unwrap();
+        assert_eq!(decode(&binary).unwrap(), *json);
+        hex::encode(&binary)
+    }
+
+    #[test]
+    fn orders_fields_and_encodes_headers() {
+        let json = json!({
+            "TickSize": 5,
+            "HookOn": "00".repeat(32),
+            "TransactionResult": "tecHOOK_REJECTED",
+            "LedgerEntryType": "Offer",
+            "Flags": 0,
+        });
+        let expected = [
+            "11",   // LedgerEntryType, type 1 field 1
+            "006F", // Offer
+            "2200000000",
+            "5014", // HookOn, field 20 in its own byte
+            &"00".repeat(32),
+            "031099", // TransactionResult, type 16 in its own byte
+            "001010", // TickSize, both in their own bytes
+            "05",
+        ]
+        .concat();
+        assert_eq!(round_trip(&json), expected);
+    }
+
+    #[test]
+    fn encodes_variable_lengths() {
+        for (len, prefix) in [(0, "00"), (192, "C0"), (193, "C100"), (12480, "F0FF"), (12481, "F10000"), (918744, "FED417")] {
+            let json = json!({ "URI": "AB".repeat(len) });
+            assert_eq!(round_trip(&json)[..2 + prefix.len()], format!("75{prefix}"), "{len}");
+        }
+        assert!(encode(&json!({ "URI": "AB".repeat(918745) })).is_err());
+        assert_eq!(round_trip(&json!({ "Account": GENESIS })), format!("8114{GENESIS_ID}"));
+    }
+
+    #[test]
+    fn round_trips_containers() {
+        let memos = json!({
+            "Memos": [
+                { "Memo": { "MemoType": "6869", "MemoData": "0102" } },
+                { "Memo": { "MemoFormat": "" } },
+            ],
+        });
+        assert_eq!(round_trip(&memos), "F9EA7C0268697D020102E1EA7E00E1F1");
+
+        let meta = json!({
+            "TransactionIndex": 3,
+            "TransactionResult": "tesSUCCESS",
+            "HookExecutions": [{ "HookExecution": {
+                "HookAccount": GENESIS,
+                "HookResult": 3,
+                "HookReturnCode": "800000000000000a",
+                "HookReturnString": "",
+                "HookInstructionCount": "2e7",
+                "HookEmitCount": 1,
+                "HookExecutionIndex": 0,
+                "HookStateChangeCount": 2,
+                "HookHash": "CD".repeat(32),
+            }}],
+            "AffectedNodes": [{ "ModifiedNode": {
+                "LedgerEntryType": "RippleState",
+                "LedgerIndex": "EF".repeat(32),
+                "FinalFields": { "Balance": { "currency": "USD", "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji", "value": "-10" } },
+                "PreviousFields": { "Balance": { "currency": "USD", "issuer": "rrrrrrrrrrrrrrrrrrrrBZbvji", "value": "-0.5" } },
+            }}],
+        });
+        round_trip(&meta);
+
+        let payment = json!({
+            "TransactionType": "Payment",
+            "Paths": [
+                [{ "currency": "USD", "issuer": GENESIS }, { "account": GENESIS }],
+                [{ "currency": "XRP" }],
+            ],
+            "Indexes": ["01".repeat(32), "02".repeat(32)],
+        });
+        let binary = round_trip(&payment);
+        let paths = [
+            "0112", // Paths, type 18 in its own byte
+            "30",   // currency and issuer
+            "0000000000000000000000005553440000000000",
+            GENESIS_ID,
+            "01", // account
+            GENESIS_ID,
+            "FF", // next path
+            "10", // currency
+            &"00".repeat(20),
+            "00",
+        ]
+        .concat();
+        assert!(binary.contains(&paths), "{binary}");
+        assert!(binary.ends_with(&format!("0113{}", ["40", &"01".repeat(32), &"02".repeat(32)].concat())));
+    }
+
+    #[test]
+    fn encodes_for_signing() {
+        let tx = json!({ "TransactionType": "Payment", "SigningPubKey": "02", "TxnSignature": "3045", "hash": "00" });
+        assert_eq!(hex::encode(&encode_for_signing(&tx).unwrap()), "535458001200007301 02".replace(' ', ""));
+        assert_eq!(hex::encode(&encode(&tx).unwrap()), "1200007301027402 3045".replace(' ', ""));
+    }
+
+    #[test]
+    fn converts_field_values_as_hooks_read_them() {
+        let field = |name| definitions::field(name).unwrap();
+        assert_eq!(field_value(field("TransactionType"), &json!("Invoke")).unwrap(), [0, 99]);
+        assert_eq!(field_value(field("TransactionType"), &json!(99)).unwrap(), [0, 99]);
+        assert_eq!(field_value(field("Account"), &json!(GENESIS)).unwrap(), hex::decode(GENESIS_ID).unwrap());
+        assert_eq!(field_value(field("URI"), &json!("6970")).unwrap(), b"ip");
+        assert_eq!(field_value(field("Memo"), &json!({ "MemoData": "01" })).unwrap(), [0x7D, 1, 1, OBJECT_END]);
+        assert!(field_value(field("Flags"), &json!(-1)).is_err());
+        assert!(field_value(field("NFTokenID"), &json!("00")).is_err());
+        assert!(field_value(field("OwnerNode"), &json!("12345678901234567")).is_err());
+        assert!(field_value(field("TransactionType"), &json!("Bogus")).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(encode(&json!({ "Bogus": 1 })).unwrap_err().contains("Bogus"));
+        assert!(encode(&json!({ "Memos": [{ "Account": GENESIS }] })).is_err());
+        assert!(encode(&json!({ "Paths": [[{}]] })).is_err());
+        assert!(encode(&json!([])).is_err());
+
+        for bad in [
+            "22000000",             // truncated
+            "E1",                   // a bare end marker
+            "22000000002200000000", // Flags twice
+            "EA7D0101",             // object without its end marker
+            "F9E1F1",               // array entry that is not an object field
+            "75FF",                 // bad length prefix
+            "811300",               // short account
+            "0112020000000000",     // bad path step type
+            "04130100",             // Vector256 of one byte
+        ] {
+            assert!(decode(&hex::decode(bad).unwrap()).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Round-trips JSON through the codec against known output.
+//!
+//! Each file under `hook/codec/fixtures/` pairs rippled's JSON for a
+//! transaction or ledger object with its binary: `tx` called with
+//! `"binary": false` and `true`, or `ledger_entry` likewise. Both
+//! directions must match exactly. To add one, save the two responses' JSON
+//! and `tx_blob` (or `node` and `node_binary`) as
+//! `{"description", "json", "binary"}`. A transaction's metadata goes under
+//! `json.meta` as rippled returns it and, binary, under `meta_binary`; it
+//! is checked the same way. `capture.js` writes a fixture from a node's
+//! responses; every description names where the fixture came from.
+//!
+//! The transactions in the hook fixtures under `hook/fixtures/` must also
+//! survive encoding and decoding unchanged, so they stay in rippled's form.
+
+use std::path::{Path, PathBuf};
+
+use hook_codec::{decode, encode, hex};
+use serde_json::{Map, Value};
+
+fn workspace() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+/// Every `.json` file under `dir`, in path order.
+fn json_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            json_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+}
+
+fn read(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+}
+
+/// `json` without API metadata, which is not part of the binary.
+fn fields(json: &Value) -> Value {
+    let fields: Map<String, Value> = json
+        .as_object()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| !name.starts_with(|c: char| c.is_ascii_lowercase()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    Value::Object(fields)
+}
+
+#[test]
+fn matches_rippled() {
+    let mut files = Vec::new();
+    json_files(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"), &mut files);
+    assert!(!files.is_empty());
+    for file in &files {
+        let fixture = read(file);
+        let name = file.display();
+        let binary = fixture["binary"].as_str().unwrap();
+        assert_eq!(hex::encode(&encode(&fixture["json"]).unwrap_or_else(|e| panic!("{name}: {e}"))), binary, "{name}");
+        assert_eq!(decode(&hex::decode(binary).unwrap()).unwrap_or_else(|e| panic!("{name}: {e}")), fields(&fixture["json"]), "{name}");
+        if let Some(meta_binary) = fixture["meta_binary"].as_str() {
+            let meta = &fixture["json"]["meta"];
+            assert_eq!(hex::encode(&encode(meta).unwrap_or_else(|e| panic!("{name} meta: {e}"))), meta_binary, "{name} meta");
+            assert_eq!(decode(&hex::decode(meta_binary).unwrap()).unwrap_or_else(|e| panic!("{name} meta: {e}")), fields(meta), "{name} meta");
+        }
+    }
+}
+
+#[test]
+fn round_trips_hook_fixture_transactions() {
+    let mut files = Vec::new();
+    json_files(&workspace().join("fixtures"), &mut files);
+    assert!(!files.is_empty());
+    for file in &files {
+        let fixture = read(file);
+        let emitted = fixture["expect"]["emitted"].as_array().cloned().unwrap_or_default();
+        for tx in std::iter::once(&fixture["tx"]).chain(&emitted) {
+            let name = file.display();
+            let binary = encode(tx).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(decode(&binary).unwrap_or_else(|e| panic!("{name}: {e}")), fields(tx), "{name}");
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Admin commands, shared by every hook: an Invoke from the admin account
+//! carrying one command, either as the data of a `hook-admin` memo or as
+//! the `ADMIN_CMD` entry of the transaction's `HookParameters`.
+//!
+//! The admin is the account last set with [`ROTATE_ADMIN`], else the
+//! 20-byte `ADMIN` hook parameter, else the hook account. For a
+//! multi-signature quorum, make the admin an account with a SignerList and
+//! its master key disabled: the ledger checks the quorum before any hook
+//! runs. Setting the `ADMIN_MULTISIG` parameter to `01` also makes the
+//! hooks refuse commands that were not multi-signed.
+//!
+//! A command is an op byte followed by its arguments:
+//!
+//! ```text
+//! 01 SET_PARAM         name length, name, value   empty value clears
+//! 02 PAUSE
+//! 03 UNPAUSE
+//! 04 ADD_EXEMPTION     account (20)
+//! 05 REMOVE_EXEMPTION  account (20)
+//! 06 ROTATE_ADMIN      account (20)
+//! ```
+//!
+//! Commands only write hook state, so hooks sharing a namespace share the
+//! settings, the paused flag included: every hook checks it with
+//! [`check_paused`] before doing anything else. `SET_PARAM` stores an
+//! override that [`param`] prefers to the installed hook parameter. Admin
+//! rollbacks use the 100-199 [`HookError`] codes, clear of the hooks' own.
+
+use crate::api::*;
+use crate::bytes::{array20, copy, eq20};
+use crate::error::HookError;
+use crate::exemptions::exemption_key;
+use crate::memo::{self, MAX_MEMOS_LEN};
+
+/// MemoType of an admin command.
+pub const ADMIN_MEMO_TYPE: &[u8] = b"hook-admin";
+
+/// Transaction `HookParameters` entry that can carry a command instead.
+pub const ADMIN_COMMAND_PARAM: &[u8] = b"ADMIN_CMD";
+
+/// Longest command read from `HookParameters`, the limit on a value there.
+pub const MAX_COMMAND_LEN: usize = 256;
+
+pub const SET_PARAM: u8 = 1;
+pub const PAUSE: u8 = 2;
+pub const UNPAUSE: u8 = 3;
+pub const ADD_EXEMPTION: u8 = 4;
+pub const REMOVE_EXEMPTION: u8 = 5;
+pub const ROTATE_ADMIN: u8 = 6;
+
+/// State key of the paused flag.
+pub const PAUSED_KEY: &[u8] = b"PAUSED";
+
+/// Longest parameter name `SET_PARAM` can override: state keys are at most
+/// 32 bytes and the override key spends 3 on its prefix.
+pub const MAX_PARAM_NAME_LEN: usize = 29;
+
+/// Writes the state key of the override of hook parameter `name`, "PRM"
+/// followed by the name, to `key`, returning its length. `None` if the name
+/// is empty or too long.
+pub fn param_key(name: &[u8], key: &mut [u8; 32]) -> Option<usize> {
+    if name.is_empty() || name.len() > MAX_PARAM_NAME_LEN {
+        return None;
+    }
+    key[..3].copy_from_slice(b"PRM");
+    copy(&mut key[3..], name);
+    Some(3 + name.len())
+}
+
+/// Hook parameter `name` as the hooks should see it: the admin's override
+/// if there is one, else the installed hook parameter. Returns the length
+/// copied into `buf` or a negative error, like `hook_param`.
+pub fn param(tx: &HookCtx, name: &[u8], buf: &mut [u8]) -> i64 {
+    let mut key = [0u8; 32];
+    if let Some(len) = param_key(name, &mut key) {
+        let found = tx.state(&key[..len], buf);
+        if found != DOESNT_EXIST {
+            return found;
+        }
+    }
+    tx.hook_param(name, buf)
+}
+
+/// The current admin account; `None` if the `ADMIN` setting is malformed.
+pub fn admin(tx: &HookCtx) -> Option<[u8; 20]> {
+    let mut admin = [0u8; 20];
+    match param(tx, b"ADMIN", &mut admin) {
+        DOESNT_EXIST => Some(tx.hook_account()),
+        20 => Some(admin),
+        _ => None,
+    }
+}
+
+pub fn is_paused(tx: &HookCtx) -> bool {
+    let mut flag = [0u8; 1];
+    tx.state(PAUSED_KEY, &mut flag) == 1
+}
+
+/// What a hook does with the transactions it fires on while paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WhenPaused {
+    /// Accept them without acting on them.
+    PassThrough = 0,
+    /// Roll them back.
+    Rollback = 1,
+}
+
+/// Ends the hook if the hooks are paused: a pass-through accepts, a
+/// rollback rejects. The `WHEN_PAUSED` setting, `00` or `01`, overrides the
+/// hook's `default`. Transactions the hook account sends always pass
+/// through, so its owner can still move funds during an incident. Returns
+/// when not paused; call it right after [`commands`], which must still run
+/// so the admin can unpause.
+pub fn check_paused(tx: &HookCtx, default: WhenPaused) {
+    if !is_paused(tx) {
+        return;
+    }
+    let mut setting = [0u8; 1];
+    let when = match param(tx, b"WHEN_PAUSED", &mut setting) {
+        1 if setting[0] == WhenPaused::PassThrough as u8 => WhenPaused::PassThrough,
+        1 if setting[0] == WhenPaused::Rollback as u8 => WhenPaused::Rollback,
+        _ => default,
+    };
+    let mut sender = [0u8; 20];
+    let own = tx.otxn_field(sfAccount, &mut sender) == 20 && eq20(&sender, &tx.hook_account());
+    if when == WhenPaused::Rollback && !own {
+        HookError::Paused.rollback();
+    }
+    HookError::Paused.accept();
+}
+
+/// Applies the admin command the originating Invoke carries, if any, and
+/// ends the hook. Returns without doing anything when there is none, so
+/// every hook can call it first thing.
+pub fn commands(tx: &mut HookCtx) {
+    if tx.otxn_type() != ttINVOKE {
+        return;
+    }
+    let mut memos = [0u8; MAX_MEMOS_LEN];
+    let len = tx.otxn_field(sfMemos, &mut memos).max(0) as usize;
+    let mut carried = [0u8; MAX_COMMAND_LEN];
+    let command = match memo::find(&memos[..len], ADMIN_MEMO_TYPE) {
+        Some(memo) => memo.data,
+        None => match tx.otxn_param(ADMIN_COMMAND_PARAM, &mut carried) {
+            len if len >= 0 => &carried[..len as usize],
+            _ => return,
+        },
+    };
+
+    let Some(admin) = admin(tx) else {
+        HookError::InvalidAdmin.rollback();
+    };
+    let mut sender = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut sender) != 20 || !eq20(&sender, &admin) {
+        HookError::NotAdmin.rollback();
+    }
+    let mut multisig = [0u8; 1];
+    if param(tx, b"ADMIN_MULTISIG", &mut multisig) == 1 && multisig[0] == 1 {
+        // Multi-signed transactions leave SigningPubKey empty
+        let mut key = [0u8; 33];
+        if tx.otxn_field(sfSigningPubKey, &mut key) != 0 {
+            HookError::NotMultisigned.rollback();
+        }
+    }
+
+    let Some(written) = apply(tx, command) else {
+        HookError::MalformedCommand.rollback();
+    };
+    // Deleting what is already gone is fine
+    if written < 0 && written != DOESNT_EXIST {
+        HookError::CommandFailed.rollback();
+    }
+    ACCEPT("Admin command applied", 0);
+}
+
+/// Writes `command` to hook state, returning the `state_set` result or
+/// `None` if the command is malformed.
+fn apply(tx: &mut HookCtx, command: &[u8]) -> Option<i64> {
+    let (&op, args) = command.split_first()?;
+    let account = || (args.len() == 20).then(|| array20(args));
+    Some(match op {
+        SET_PARAM => {
+            let (&len, rest) = args.split_first()?;
+            let name = rest.get(..len as usize)?;
+            // The admin account only changes through ROTATE_ADMIN, which
+            // checks it is one
+            if eq_bytes(name, b"ADMIN") {
+                return None;
+            }
+            let mut key = [0u8; 32];
+            let key_len = param_key(name, &mut key)?;
+            tx.state_set(&key[..key_len], &rest[len as usize..])
+        }
+        PAUSE if args.is_empty() => tx.state_set(PAUSED_KEY, &[1]),
+        UNPAUSE if args.is_empty() => tx.state_set(PAUSED_KEY, &[]),
+        ADD_EXEMPTION => tx.state_set(&exemption_key(&account()?), &[1]),
+        REMOVE_EXEMPTION => tx.state_set(&exemption_key(&account()?), &[]),
+        ROTATE_ADMIN => {
+            let mut key = [0u8; 32];
+            let key_len = param_key(b"ADMIN", &mut key)?;
+            tx.state_set(&key[..key_len], &account()?)
+        }
+        _ => return None,
+    })
+}
+
+/// Whether `a` and `b` are equal, compared under a guard.
+fn eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    loop {
+        guard(line!(), MAX_PARAM_NAME_LEN as u32 + 1);
+        if i == a.len() {
+            return true;
+        }
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{state_key, Exit, Mock, Outcome, Tx};
+
+    const HOOK_ACCOUNT: [u8; 20] = [0xAA; 20];
+    const OPS: [u8; 20] = [0x0A; 20];
+    const NEW_OPS: [u8; 20] = [0x0B; 20];
+    const ALICE: [u8; 20] = [0x01; 20];
+
+    /// Stands in for any hook: handles commands, then reports `BURN_BPS`.
+    fn hook(tx: &mut HookCtx) -> i32 {
+        commands(tx);
+        let mut bps = [0u8; 2];
+        match param(tx, b"BURN_BPS", &mut bps) {
+            2 => u16::from_be_bytes(bps) as i32,
+            _ => -1,
+        }
+    }
+
+    /// Like [`hook`], but rolls back while paused.
+    fn pausable(tx: &mut HookCtx) -> i32 {
+        commands(tx);
+        check_paused(tx, WhenPaused::Rollback);
+        hook(tx)
+    }
+
+    fn mock() -> Mock {
+        Mock::new(HOOK_ACCOUNT).param(b"ADMIN", &OPS).param(b"BURN_BPS", &100u16.to_be_bytes())
+    }
+
+    fn command(sender: [u8; 20], command: &[u8]) -> Tx {
+        Tx::new(ttINVOKE).field(sfAccount, &sender).memo(ADMIN_MEMO_TYPE, command)
+    }
+
+    fn set_param(name: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut command = vec![SET_PARAM, name.len() as u8];
+        command.extend(name);
+        command.extend(value);
+        command
+    }
+
+    fn with_account(op: u8, account: [u8; 20]) -> Vec<u8> {
+        let mut command = vec![op];
+        command.extend(account);
+        command
+    }
+
+    /// Runs `tx` on a mock holding the state `previous` left behind.
+    fn then(previous: &Outcome, tx: &Tx) -> Outcome {
+        let mut mock = mock();
+        for (key, value) in &previous.state {
+            mock = mock.state(key, value);
+        }
+        mock.run(tx, hook)
+    }
+
+    #[test]
+    fn overrides_and_clears_hook_parameters() {
+        assert_eq!(mock().run(&Tx::new(ttINVOKE).field(sfAccount, &ALICE), hook).code, 100);
+
+        let set = mock().run(&command(OPS, &set_param(b"BURN_BPS", &250u16.to_be_bytes())), hook);
+        assert_eq!((set.exit, set.message.as_str()), (Exit::Accept, "Admin command applied"));
+        assert_eq!(set.state.get(&state_key(b"PRMBURN_BPS")), Some(&250u16.to_be_bytes().to_vec()));
+        assert_eq!(then(&set, &Tx::new(ttINVOKE).field(sfAccount, &ALICE)).code, 250);
+
+        let cleared = then(&set, &command(OPS, &set_param(b"BURN_BPS", &[])));
+        assert!(cleared.state.is_empty());
+        assert_eq!(then(&cleared, &Tx::new(ttINVOKE).field(sfAccount, &ALICE)).code, 100);
+    }
+
+    #[test]
+    fn pauses_and_unpauses() {
+        let paused = mock().run(&command(OPS, &[PAUSE]), hook);
+        assert_eq!(paused.state.get(&state_key(PAUSED_KEY)), Some(&vec![1]));
+        let unpaused = then(&paused, &command(OPS, &[UNPAUSE]));
+        assert_eq!(unpaused.exit, Exit::Accept);
+        assert!(unpaused.state.is_empty());
+        // Unpausing twice is harmless
+        assert_eq!(mock().run(&command(OPS, &[UNPAUSE]), hook).exit, Exit::Accept);
+    }
+
+    #[test]
+    fn paused_hooks_roll_back_or_pass_through() {
+        let invoke = Tx::new(ttINVOKE).field(sfAccount, &ALICE);
+        assert_eq!(mock().run(&invoke, pausable).code, 100);
+
+        let paused = mock().state(PAUSED_KEY, &[1]);
+        let outcome = paused.run(&invoke, pausable);
+        assert_eq!((outcome.exit, outcome.code, outcome.message.as_str()), (Exit::Rollback, HookError::Paused.code(), "Hooks are paused"));
+        let passed = paused.clone().param(b"WHEN_PAUSED", &[0]).run(&invoke, pausable);
+        assert_eq!((passed.exit, passed.code), (Exit::Accept, HookError::Paused.code()));
+        // The hook account's own transactions and admin commands still go through
+        let own = paused.run(&Tx::new(ttINVOKE).field(sfAccount, &HOOK_ACCOUNT), pausable);
+        assert_eq!((own.exit, own.code), (Exit::Accept, HookError::Paused.code()));
+        let unpaused = paused.run(&command(OPS, &[UNPAUSE]), pausable);
+        assert_eq!(unpaused.exit, Exit::Accept);
+        assert!(unpaused.state.is_empty());
+    }
+
+    #[test]
+    fn rotates_the_admin() {
+        let rotated = mock().run(&command(OPS, &with_account(ROTATE_ADMIN, NEW_OPS)), hook);
+        assert_eq!(rotated.exit, Exit::Accept);
+        assert_eq!(then(&rotated, &command(OPS, &[PAUSE])).code, HookError::NotAdmin.code());
+        assert_eq!(then(&rotated, &command(NEW_OPS, &[PAUSE])).exit, Exit::Accept);
+        // ADMIN only changes through ROTATE_ADMIN
+        assert_eq!(mock().run(&command(OPS, &set_param(b"ADMIN", &NEW_OPS)), hook).code, HookError::MalformedCommand.code());
+    }
+
+    #[test]
+    fn only_the_admin_sends_commands() {
+        assert_eq!(mock().run(&command(ALICE, &[PAUSE]), hook).code, HookError::NotAdmin.code());
+        // Without ADMIN the hook account is the admin
+        let own = Mock::new(HOOK_ACCOUNT).run(&command(HOOK_ACCOUNT, &[PAUSE]), hook);
+        assert_eq!(own.exit, Exit::Accept);
+        let invalid = Mock::new(HOOK_ACCOUNT).param(b"ADMIN", &[1, 2]).run(&command(HOOK_ACCOUNT, &[PAUSE]), hook);
+        assert_eq!((invalid.exit, invalid.code), (Exit::Rollback, HookError::InvalidAdmin.code()));
+    }
+
+    #[test]
+    fn requires_multisig_when_configured() {
+        let strict = mock().param(b"ADMIN_MULTISIG", &[1]);
+        let single = command(OPS, &[PAUSE]).field(sfSigningPubKey, &[0x02; 33]);
+        assert_eq!(strict.run(&single, hook).code, HookError::NotMultisigned.code());
+        let multi = command(OPS, &[PAUSE]).field(sfSigningPubKey, &[]);
+        assert_eq!(strict.run(&multi, hook).exit, Exit::Accept);
+        assert_eq!(mock().run(&single, hook).exit, Exit::Accept);
+    }
+
+    #[test]
+    fn reads_commands_from_transaction_parameters() {
+        let tx = Tx::new(ttINVOKE).field(sfAccount, &OPS).param(ADMIN_COMMAND_PARAM, &[PAUSE]);
+        let outcome = mock().run(&tx, hook);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert_eq!(outcome.state.get(&state_key(PAUSED_KEY)), Some(&vec![1]));
+    }
+
+    #[test]
+    fn rolls_back_malformed_commands() {
+        let long_name = set_param(&[b'N'; MAX_PARAM_NAME_LEN + 1], &[1]);
+        let truncated = vec![SET_PARAM, 9, b'B'];
+        for bad in [&[][..], &[0x7F], &[PAUSE, 0], &[ADD_EXEMPTION, 1, 2], &truncated, &long_name] {
+            let outcome = mock().run(&command(OPS, bad), hook);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::MalformedCommand.code()), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn leaves_other_transactions_to_the_hook() {
+        let outcome = mock().run(&Tx::new(ttINVOKE).field(sfAccount, &ALICE).memo(b"note", b"hi"), hook);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Accept, 100));
+        assert_eq!(mock().run(&Tx::new(ttPAYMENT).field(sfAccount, &OPS), hook).code, 100);
+    }
+}
@@ -0,0 +1,404 @@
+//! Serialized STAmount decoding and fixed-point percentage math.
+//!
+//! XRP amounts are 8 bytes of drops. Issued-currency (IOU) amounts are an
+//! 8-byte decimal float followed by the 20-byte currency and 20-byte issuer:
+//!
+//! ```text
+//! bit 63     1 = IOU, 0 = XRP
+//! bit 62     1 = positive, 0 = negative
+//! bits 54-61 exponent + 97            (IOU only)
+//! bits 0-53  mantissa in [1e15, 1e16) (IOU only; 0 for zero)
+//! ```
+//!
+//! Everything here avoids loops and 128-bit division so it can run inside a
+//! hook without extra guards or compiler-builtins helpers.
+
+use core::cmp::Ordering;
+
+use crate::bytes::eq20;
+
+/// Smallest normalized IOU mantissa.
+pub const MIN_MANTISSA: u64 = 1_000_000_000_000_000;
+/// Largest normalized IOU mantissa.
+pub const MAX_MANTISSA: u64 = 9_999_999_999_999_999;
+/// Smallest IOU exponent; anything smaller underflows to zero.
+pub const MIN_EXPONENT: i32 = -96;
+/// Largest IOU exponent.
+pub const MAX_EXPONENT: i32 = 80;
+/// Largest amount of XRP, in drops.
+pub const MAX_DROPS: u64 = 100_000_000_000_000_000;
+
+const NOT_XRP_BIT: u64 = 1 << 63;
+const POSITIVE_BIT: u64 = 1 << 62;
+const MANTISSA_MASK: u64 = (1 << 54) - 1;
+const EXPONENT_BIAS: i32 = 97;
+
+const POW10: [u64; 20] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+    10_000_000_000_000_000_000,
+];
+
+/// Basis points in one whole (100%).
+const BPS_SCALE: u64 = 10_000;
+
+/// How to treat digits that don't fit in the result of a percentage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Truncate toward zero.
+    Down,
+    /// Round away from zero whenever anything is discarded.
+    Up,
+    /// Round to the nearest representable value, ties away from zero.
+    Nearest,
+}
+
+/// Decimal float value of an issued-currency amount, always normalized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IouValue {
+    negative: bool,
+    mantissa: u64,
+    exponent: i32,
+}
+
+impl IouValue {
+    pub const ZERO: IouValue = IouValue { negative: false, mantissa: 0, exponent: 0 };
+
+    /// Positive `mantissa * 10^exponent`, normalized. Digits beyond the 16
+    /// significant ones are truncated. `None` if the value overflows.
+    pub const fn new(mantissa: u64, exponent: i32) -> Option<IouValue> {
+        normalize(false, mantissa, exponent, false, Rounding::Down)
+    }
+
+    /// Decodes the 8-byte value part of a serialized IOU amount.
+    pub fn from_bytes(bytes: [u8; 8]) -> Option<IouValue> {
+        let raw = u64::from_be_bytes(bytes);
+        if raw & NOT_XRP_BIT == 0 {
+            return None;
+        }
+        let mantissa = raw & MANTISSA_MASK;
+        let exponent = ((raw >> 54) & 0xFF) as i32 - EXPONENT_BIAS;
+        if mantissa == 0 {
+            // Zero is the only value with a clear sign bit and no exponent
+            return (raw == NOT_XRP_BIT).then_some(IouValue::ZERO);
+        }
+        if !(MIN_MANTISSA..=MAX_MANTISSA).contains(&mantissa)
+            || !(MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent)
+        {
+            return None;
+        }
+        Some(IouValue { negative: raw & POSITIVE_BIT == 0, mantissa, exponent })
+    }
+
+    /// Encodes the value as the 8-byte value part of an IOU amount.
+    pub fn to_bytes(&self) -> [u8; 8] {
+        if self.is_zero() {
+            return NOT_XRP_BIT.to_be_bytes();
+        }
+        let sign = if self.negative { 0 } else { POSITIVE_BIT };
+        let exponent = (self.exponent + EXPONENT_BIAS) as u64;
+        (NOT_XRP_BIT | sign | exponent << 54 | self.mantissa).to_be_bytes()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn mantissa(&self) -> u64 {
+        self.mantissa
+    }
+
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    /// `bps` basis points of this value (`bps` is capped at 100%).
+    pub fn bps(&self, bps: u64, rounding: Rounding) -> IouValue {
+        let bps = if bps > BPS_SCALE { BPS_SCALE } else { bps };
+        if self.is_zero() || bps == 0 {
+            return IouValue::ZERO;
+        }
+        // mantissa * bps can exceed u64, so split it as high * 10^4 + low
+        let high = (self.mantissa / BPS_SCALE) * bps + (self.mantissa % BPS_SCALE) * bps / BPS_SCALE;
+        let low = (self.mantissa % BPS_SCALE) * bps % BPS_SCALE;
+        let result = if high < MIN_MANTISSA {
+            // Small enough to hold the whole product exactly
+            normalize(self.negative, high * BPS_SCALE + low, self.exponent - 4, false, rounding)
+        } else {
+            let rounded = high + round_div(low, BPS_SCALE, false, rounding);
+            normalize(self.negative, rounded, self.exponent, false, Rounding::Down)
+        };
+        // A share of a valid value can only shrink, so it cannot overflow
+        result.unwrap_or(IouValue::ZERO)
+    }
+}
+
+impl PartialOrd for IouValue {
+    fn partial_cmp(&self, other: &IouValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IouValue {
+    fn cmp(&self, other: &IouValue) -> Ordering {
+        let magnitude = match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return if other.negative { Ordering::Greater } else { Ordering::Less },
+            (false, true) => return if self.negative { Ordering::Less } else { Ordering::Greater },
+            (false, false) => self
+                .exponent
+                .cmp(&other.exponent)
+                .then(self.mantissa.cmp(&other.mantissa)),
+        };
+        match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (negative, _) => if negative { Ordering::Less } else { Ordering::Greater },
+        }
+    }
+}
+
+/// A decoded STAmount.
+#[derive(Clone, Copy, Debug, Eq)]
+pub enum Amount {
+    /// Native XRP, in drops.
+    Xrp(u64),
+    /// Issued currency.
+    Iou { value: IouValue, currency: [u8; 20], issuer: [u8; 20] },
+}
+
+// Spelled out so currencies and issuers compare without `memcmp`
+impl PartialEq for Amount {
+    fn eq(&self, other: &Amount) -> bool {
+        match (self, other) {
+            (Amount::Xrp(a), Amount::Xrp(b)) => a == b,
+            (
+                Amount::Iou { value, currency, issuer },
+                Amount::Iou { value: other_value, currency: other_currency, issuer: other_issuer },
+            ) => value == other_value && eq20(currency, other_currency) && eq20(issuer, other_issuer),
+            _ => false,
+        }
+    }
+}
+
+impl Amount {
+    /// Decodes a serialized STAmount: 8 bytes for XRP, 48 for an IOU.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Amount> {
+        let head: [u8; 8] = bytes.get(..8)?.try_into().ok()?;
+        let raw = u64::from_be_bytes(head);
+        if raw & NOT_XRP_BIT == 0 {
+            // Negative XRP never appears in a transaction amount
+            let drops = raw & !POSITIVE_BIT;
+            if bytes.len() != 8 || raw & POSITIVE_BIT == 0 || drops > MAX_DROPS {
+                return None;
+            }
+            return Some(Amount::Xrp(drops));
+        }
+        if bytes.len() != 48 {
+            return None;
+        }
+        Some(Amount::Iou {
+            value: IouValue::from_bytes(head)?,
+            currency: bytes[8..28].try_into().ok()?,
+            issuer: bytes[28..48].try_into().ok()?,
+        })
+    }
+
+    /// Serializes the amount into `buf`, returning the number of bytes used.
+    pub fn serialize(&self, buf: &mut [u8; 48]) -> usize {
+        match self {
+            Amount::Xrp(drops) => {
+                buf[..8].copy_from_slice(&(POSITIVE_BIT | drops).to_be_bytes());
+                8
+            }
+            Amount::Iou { value, currency, issuer } => {
+                buf[..8].copy_from_slice(&value.to_bytes());
+                buf[8..28].copy_from_slice(currency);
+                buf[28..48].copy_from_slice(issuer);
+                48
+            }
+        }
+    }
+
+    /// `bps` basis points of this amount, in the same currency.
+    pub fn bps(&self, bps: u64, rounding: Rounding) -> Amount {
+        match *self {
+            Amount::Xrp(drops) => Amount::Xrp(drops_bps(drops, bps, rounding)),
+            Amount::Iou { value, currency, issuer } => {
+                Amount::Iou { value: value.bps(bps, rounding), currency, issuer }
+            }
+        }
+    }
+}
+
+/// `bps` basis points of an XRP amount in drops (`bps` is capped at 100%).
+pub fn drops_bps(drops: u64, bps: u64, rounding: Rounding) -> u64 {
+    let bps = if bps > BPS_SCALE { BPS_SCALE } else { bps };
+    let whole = (drops / BPS_SCALE) * bps + (drops % BPS_SCALE) * bps / BPS_SCALE;
+    let rest = (drops % BPS_SCALE) * bps % BPS_SCALE;
+    whole + round_div(rest, BPS_SCALE, false, rounding)
+}
+
+/// `n / d` under `rounding`; `sticky` flags nonzero digits already dropped
+/// below `n`.
+const fn round_div(n: u64, d: u64, sticky: bool, rounding: Rounding) -> u64 {
+    let quotient = n / d;
+    let remainder = n % d;
+    let up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder != 0 || sticky,
+        Rounding::Nearest => remainder >= d - remainder,
+    };
+    if up { quotient + 1 } else { quotient }
+}
+
+/// Brings `mantissa * 10^exponent` into normalized form, rounding away any
+/// digits beyond the 16 significant ones.
+const fn normalize(
+    negative: bool,
+    mantissa: u64,
+    exponent: i32,
+    sticky: bool,
+    rounding: Rounding,
+) -> Option<IouValue> {
+    if mantissa == 0 {
+        return Some(IouValue::ZERO);
+    }
+    let digits = mantissa.ilog10() as i32 + 1;
+    let (mut mantissa, mut exponent) = if digits > 16 {
+        let shift = digits - 16;
+        (round_div(mantissa, POW10[shift as usize], sticky, rounding), exponent + shift)
+    } else {
+        let shift = 16 - digits;
+        (mantissa * POW10[shift as usize], exponent - shift)
+    };
+    if mantissa > MAX_MANTISSA {
+        // Rounding carried into a 17th digit
+        mantissa /= 10;
+        exponent += 1;
+    }
+    if exponent > MAX_EXPONENT {
+        return None;
+    }
+    if exponent < MIN_EXPONENT {
+        return Some(IouValue::ZERO);
+    }
+    Some(IouValue { negative, mantissa, exponent })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iou(mantissa: u64, exponent: i32) -> IouValue {
+        IouValue::new(mantissa, exponent).unwrap()
+    }
+
+    #[test]
+    fn decodes_known_iou_values() {
+        // 1 unit, as produced by rippled for {"value": "1"}
+        let one = IouValue::from_bytes(0xD4838D7EA4C68000u64.to_be_bytes()).unwrap();
+        assert_eq!(one, iou(1, 0));
+        assert_eq!((one.mantissa(), one.exponent()), (MIN_MANTISSA, -15));
+        assert_eq!(one.to_bytes(), 0xD4838D7EA4C68000u64.to_be_bytes());
+
+        let zero = IouValue::from_bytes(0x8000000000000000u64.to_be_bytes()).unwrap();
+        assert!(zero.is_zero());
+        assert_eq!(zero.to_bytes(), 0x8000000000000000u64.to_be_bytes());
+
+        // XRP bit pattern and unnormalized mantissas are rejected
+        assert_eq!(IouValue::from_bytes(0x4000000000000001u64.to_be_bytes()), None);
+        assert_eq!(IouValue::from_bytes(0xD480000000000001u64.to_be_bytes()), None);
+    }
+
+    #[test]
+    fn round_trips_negative_values() {
+        let value = IouValue { negative: true, ..iou(125, -2) };
+        assert_eq!(IouValue::from_bytes(value.to_bytes()), Some(value));
+        assert!(value < IouValue::ZERO);
+    }
+
+    #[test]
+    fn decodes_xrp_and_iou_amounts() {
+        assert_eq!(Amount::from_bytes(&0x40000000000F4240u64.to_be_bytes()), Some(Amount::Xrp(1_000_000)));
+        // Negative XRP and truncated IOUs are malformed
+        assert_eq!(Amount::from_bytes(&0x00000000000F4240u64.to_be_bytes()), None);
+        assert_eq!(Amount::from_bytes(&0xD4838D7EA4C68000u64.to_be_bytes()), None);
+
+        let mut buf = [0u8; 48];
+        let amount = Amount::Iou { value: iou(5, 0), currency: [1; 20], issuer: [2; 20] };
+        assert_eq!(amount.serialize(&mut buf), 48);
+        assert_eq!(Amount::from_bytes(&buf), Some(amount));
+    }
+
+    #[test]
+    fn normalizes_and_compares() {
+        assert_eq!(iou(1_000, 0), iou(1, 3));
+        assert!(iou(1, 0) > iou(9_999, -4));
+        assert!(iou(1, -81) > IouValue::ZERO);
+        assert_eq!(IouValue::new(1, -82), Some(IouValue::ZERO));
+        assert_eq!(IouValue::new(1, -200), Some(IouValue::ZERO));
+        assert_eq!(IouValue::new(1, 96), None);
+    }
+
+    #[test]
+    fn takes_exact_percentages_of_small_values() {
+        // Integer math would burn nothing below 100 units
+        assert_eq!(iou(1, 0).bps(100, Rounding::Down), iou(1, -2));
+        assert_eq!(iou(37, 0).bps(100, Rounding::Down), iou(37, -2));
+        assert_eq!(iou(1, -50).bps(1, Rounding::Down), iou(1, -54));
+    }
+
+    #[test]
+    fn rounds_discarded_digits_per_policy() {
+        // 1% of 1.234567890123457 keeps all 16 digits, so nothing is lost
+        let exact = iou(1_234_567_890_123_457, -15);
+        assert_eq!(exact.bps(100, Rounding::Up), iou(1_234_567_890_123_457, -17));
+
+        // 3.33% of 9.999999999999999 = 0.3329999999999999667
+        let value = iou(9_999_999_999_999_999, -15);
+        assert_eq!(value.bps(333, Rounding::Down), iou(3_329_999_999_999_999, -16));
+        assert_eq!(value.bps(333, Rounding::Up), iou(3_330_000_000_000_000, -16));
+        assert_eq!(value.bps(333, Rounding::Nearest), iou(3_330_000_000_000_000, -16));
+
+        // 99.99% of 9.999999999999999 = 9.9989999999999990001
+        assert_eq!(value.bps(9_999, Rounding::Down), iou(9_998_999_999_999_999, -15));
+        assert_eq!(value.bps(9_999, Rounding::Up), iou(9_999_000_000_000_000, -15));
+    }
+
+    #[test]
+    fn caps_rate_at_one_hundred_percent() {
+        assert_eq!(iou(42, 0).bps(20_000, Rounding::Down), iou(42, 0));
+        assert_eq!(iou(42, 0).bps(0, Rounding::Up), IouValue::ZERO);
+    }
+
+    #[test]
+    fn takes_percentages_of_drops() {
+        assert_eq!(drops_bps(1_000_000, 100, Rounding::Down), 10_000);
+        assert_eq!(drops_bps(99, 100, Rounding::Down), 0);
+        assert_eq!(drops_bps(99, 100, Rounding::Up), 1);
+        assert_eq!(drops_bps(150, 100, Rounding::Nearest), 2);
+        assert_eq!(drops_bps(MAX_DROPS, 10_000, Rounding::Down), MAX_DROPS);
+    }
+}
@@ -0,0 +1,418 @@
+//! Hook API surface: `HookCtx`, `ACCEPT`/`ROLLBACK` and the constants from
+//! `hookapi.h`.
+//!
+//! On-ledger the calls go to the host functions rippled imports into every
+//! hook; under `cfg(test)` or the `mock` feature they go to [`crate::mock`]
+//! instead, so hooks can run natively.
+#![allow(non_upper_case_globals)]
+
+#[cfg(any(test, feature = "mock"))]
+use crate::mock::host;
+
+// Host return codes
+pub const OUT_OF_BOUNDS: i64 = -1;
+pub const INTERNAL_ERROR: i64 = -2;
+pub const TOO_BIG: i64 = -3;
+pub const TOO_SMALL: i64 = -4;
+pub const DOESNT_EXIST: i64 = -5;
+pub const NO_FREE_SLOTS: i64 = -6;
+pub const INVALID_ARGUMENT: i64 = -7;
+pub const ALREADY_SET: i64 = -8;
+pub const PREREQUISITE_NOT_MET: i64 = -9;
+pub const TOO_MANY_EMITTED_TXN: i64 = -13;
+pub const GUARD_VIOLATION: i64 = -16;
+pub const NOT_AN_ARRAY: i64 = -22;
+pub const NOT_AN_OBJECT: i64 = -23;
+
+// Transaction types
+pub const ttPAYMENT: i64 = 0;
+pub const ttHOOK_SET: i64 = 22;
+pub const ttNFTOKEN_MINT: i64 = 25;
+pub const ttNFTOKEN_CREATE_OFFER: i64 = 27;
+pub const ttINVOKE: i64 = 99;
+
+// Transaction flags
+pub const tfPartialPayment: u32 = 0x0002_0000;
+pub const tfTransferable: u32 = 0x0000_0008;
+pub const tfSellNFToken: u32 = 0x0000_0001;
+
+// SetHook flags on a Hook object
+pub const hsfOVERRIDE: u32 = 0x0000_0001;
+
+// Field codes: type << 16 | field
+pub const sfLedgerEntryType: u32 = (1 << 16) + 1;
+pub const sfTransactionType: u32 = (1 << 16) + 2;
+pub const sfHookApiVersion: u32 = (1 << 16) + 20;
+pub const sfNetworkID: u32 = (2 << 16) + 1;
+pub const sfFlags: u32 = (2 << 16) + 2;
+pub const sfSequence: u32 = (2 << 16) + 4;
+pub const sfFirstLedgerSequence: u32 = (2 << 16) + 26;
+pub const sfLastLedgerSequence: u32 = (2 << 16) + 27;
+pub const sfNFTokenTaxon: u32 = (2 << 16) + 42;
+pub const sfEmitGeneration: u32 = (2 << 16) + 46;
+pub const sfEmitBurden: u32 = (3 << 16) + 12;
+pub const sfNFTokenID: u32 = (5 << 16) + 10;
+pub const sfEmitParentTxnID: u32 = (5 << 16) + 11;
+pub const sfEmitNonce: u32 = (5 << 16) + 12;
+pub const sfEmitHookHash: u32 = (5 << 16) + 13;
+pub const sfPreviousPageMin: u32 = (5 << 16) + 26;
+pub const sfNextPageMin: u32 = (5 << 16) + 27;
+pub const sfHookOn: u32 = (5 << 16) + 20;
+pub const sfHookHash: u32 = (5 << 16) + 31;
+pub const sfHookNamespace: u32 = (5 << 16) + 32;
+pub const sfAmount: u32 = (6 << 16) + 1;
+pub const sfFee: u32 = (6 << 16) + 8;
+pub const sfSigningPubKey: u32 = (7 << 16) + 3;
+pub const sfURI: u32 = (7 << 16) + 5;
+pub const sfCreateCode: u32 = (7 << 16) + 11;
+pub const sfMemoType: u32 = (7 << 16) + 12;
+pub const sfMemoData: u32 = (7 << 16) + 13;
+pub const sfMemoFormat: u32 = (7 << 16) + 14;
+pub const sfHookParameterName: u32 = (7 << 16) + 24;
+pub const sfHookParameterValue: u32 = (7 << 16) + 25;
+pub const sfAccount: u32 = (8 << 16) + 1;
+pub const sfOwner: u32 = (8 << 16) + 2;
+pub const sfDestination: u32 = (8 << 16) + 3;
+pub const sfAuthorize: u32 = (8 << 16) + 5;
+pub const sfEmitCallback: u32 = (8 << 16) + 10;
+pub const sfMemo: u32 = (14 << 16) + 10;
+pub const sfNFToken: u32 = (14 << 16) + 12;
+pub const sfEmitDetails: u32 = (14 << 16) + 13;
+pub const sfHook: u32 = (14 << 16) + 14;
+pub const sfHookParameter: u32 = (14 << 16) + 23;
+pub const sfHookGrant: u32 = (14 << 16) + 24;
+pub const sfMemos: u32 = (15 << 16) + 9;
+pub const sfNFTokens: u32 = (15 << 16) + 10;
+pub const sfHooks: u32 = (15 << 16) + 11;
+pub const sfHookParameters: u32 = (15 << 16) + 19;
+pub const sfHookGrants: u32 = (15 << 16) + 20;
+
+// Ledger entry types, the first two bytes of a 34-byte keylet
+pub const ltNFTOKEN_PAGE: u16 = 0x0050;
+
+/// Handle to the transaction that triggered the hook.
+pub struct HookCtx {
+    _private: (),
+}
+
+impl HookCtx {
+    /// Transaction type of the originating transaction.
+    pub fn otxn_type(&self) -> i64 {
+        host::otxn_type()
+    }
+
+    /// Copies serialized field `field` of the originating transaction into
+    /// `buf`, returning the number of bytes written or a negative error.
+    pub fn otxn_field(&self, field: u32, buf: &mut [u8]) -> i64 {
+        host::otxn_field(buf, field)
+    }
+
+    /// Hash of the originating transaction.
+    pub fn otxn_id(&self) -> [u8; 32] {
+        let mut id = [0u8; 32];
+        host::otxn_id(&mut id);
+        id
+    }
+
+    /// Account the hook is installed on.
+    pub fn hook_account(&self) -> [u8; 20] {
+        let mut account = [0u8; 20];
+        host::hook_account(&mut account);
+        account
+    }
+
+    /// Copies the value of hook parameter `name` into `buf`, returning its
+    /// length or `DOESNT_EXIST`.
+    pub fn hook_param(&self, name: &[u8], buf: &mut [u8]) -> i64 {
+        host::hook_param(buf, name)
+    }
+
+    /// Copies the value of the originating transaction's `HookParameters`
+    /// entry `name` into `buf`, returning its length or `DOESNT_EXIST`.
+    pub fn otxn_param(&self, name: &[u8], buf: &mut [u8]) -> i64 {
+        host::otxn_param(buf, name)
+    }
+
+    /// Copies the hook state entry under `key` into `buf`, returning its
+    /// length or `DOESNT_EXIST`.
+    pub fn state(&self, key: &[u8], buf: &mut [u8]) -> i64 {
+        host::state(buf, key)
+    }
+
+    /// Writes `data` to hook state under `key`; empty `data` deletes it.
+    pub fn state_set(&mut self, key: &[u8], data: &[u8]) -> i64 {
+        host::state_set(data, key)
+    }
+
+    /// Sequence of the ledger the hook is running in.
+    pub fn ledger_seq(&self) -> u32 {
+        host::ledger_seq() as u32
+    }
+
+    /// Reserves room for `count` emitted transactions. Must be called once,
+    /// before any `etxn_details` or `emit`.
+    pub fn etxn_reserve(&mut self, count: u32) -> i64 {
+        host::etxn_reserve(count)
+    }
+
+    /// Writes the `EmitDetails` object for the next emitted transaction into
+    /// `buf`, returning its length.
+    pub fn etxn_details(&self, buf: &mut [u8]) -> i64 {
+        host::etxn_details(buf)
+    }
+
+    /// Minimum fee in drops for emitting the serialized transaction `blob`.
+    pub fn etxn_fee_base(&self, blob: &[u8]) -> i64 {
+        host::etxn_fee_base(blob)
+    }
+
+    /// Emits the serialized transaction `blob`, returning its hash.
+    pub fn emit(&mut self, blob: &[u8]) -> Result<[u8; 32], i64> {
+        let mut hash = [0u8; 32];
+        match host::emit(&mut hash, blob) {
+            32 => Ok(hash),
+            error if error < 0 => Err(error),
+            _ => Err(INTERNAL_ERROR),
+        }
+    }
+
+    /// Checks `signature` over `data` against the 33-byte public key `key`
+    /// (ed25519 keys carry the 0xED prefix).
+    pub fn util_verify(&self, data: &[u8], signature: &[u8], key: &[u8]) -> bool {
+        host::util_verify(data, signature, key) == 1
+    }
+
+    /// SHA-512 of `data`, truncated to its first half.
+    pub fn util_sha512h(&self, data: &[u8]) -> [u8; 32] {
+        let mut hash = [0u8; 32];
+        host::util_sha512h(&mut hash, data);
+        hash
+    }
+
+    /// Loads the ledger object at the 34-byte `keylet` into a new slot,
+    /// returning the slot number or `DOESNT_EXIST`.
+    pub fn slot_set(&self, keylet: &[u8]) -> i64 {
+        host::slot_set(keylet, 0)
+    }
+
+    /// Loads field `field` of the object in `parent` into a new slot.
+    pub fn slot_subfield(&self, parent: i64, field: u32) -> i64 {
+        host::slot_subfield(parent as u32, field, 0)
+    }
+
+    /// Loads entry `index` of the array in `parent` into a new slot.
+    pub fn slot_subarray(&self, parent: i64, index: u32) -> i64 {
+        host::slot_subarray(parent as u32, index, 0)
+    }
+
+    /// Number of entries in the array in `slot`.
+    pub fn slot_count(&self, slot: i64) -> i64 {
+        host::slot_count(slot as u32)
+    }
+
+    /// Copies the serialized contents of `slot` into `buf`, returning the
+    /// number of bytes written.
+    pub fn slot(&self, slot: i64, buf: &mut [u8]) -> i64 {
+        host::slot(buf, slot as u32)
+    }
+
+    /// Writes `msg` and `data` to the node's trace log.
+    pub fn trace(&self, msg: &str, data: &[u8]) {
+        host::trace(msg.as_bytes(), data, true);
+    }
+}
+
+/// Ends the hook and lets the originating transaction through.
+#[allow(non_snake_case)]
+pub fn ACCEPT(msg: &str, code: i64) -> ! {
+    host::accept(msg.as_bytes(), code)
+}
+
+/// Ends the hook and rejects the originating transaction.
+#[allow(non_snake_case)]
+pub fn ROLLBACK(msg: &str, code: i64) -> ! {
+    host::rollback(msg.as_bytes(), code)
+}
+
+/// Marks a loop for the guard checker: the enclosing loop may run at most
+/// `maxiter` times. `id` must be unique per loop; `line!()` is customary.
+pub fn guard(id: u32, maxiter: u32) {
+    host::guard(id, maxiter);
+}
+
+/// Runs `hook` as the hook entry point. Returning from the hook accepts the
+/// transaction with the returned code.
+pub fn enter(hook: fn(&mut HookCtx) -> i32) -> ! {
+    guard(1, 1);
+    let code = hook(&mut HookCtx { _private: () });
+    ACCEPT("", code as i64)
+}
+
+/// Runs `cbak` as the callback entry point, called once a transaction this
+/// hook emitted leaves the queue. `what` is 0 if it was applied to a ledger
+/// and 1 if it failed or expired.
+pub fn enter_cbak(cbak: fn(&mut HookCtx, u32) -> i32, what: u32) -> ! {
+    guard(1, 1);
+    let code = cbak(&mut HookCtx { _private: () }, what);
+    ACCEPT("", code as i64)
+}
+
+#[cfg(not(any(test, feature = "mock")))]
+mod host {
+    mod ffi {
+        extern "C" {
+            pub fn _g(id: u32, maxiter: u32) -> i32;
+            pub fn accept(read_ptr: u32, read_len: u32, error_code: i64) -> i64;
+            pub fn rollback(read_ptr: u32, read_len: u32, error_code: i64) -> i64;
+            pub fn otxn_type() -> i64;
+            pub fn otxn_field(write_ptr: u32, write_len: u32, field_id: u32) -> i64;
+            pub fn otxn_id(write_ptr: u32, write_len: u32, flags: u32) -> i64;
+            pub fn hook_account(write_ptr: u32, write_len: u32) -> i64;
+            pub fn hook_param(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+            pub fn otxn_param(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+            pub fn state(write_ptr: u32, write_len: u32, kread_ptr: u32, kread_len: u32) -> i64;
+            pub fn state_set(read_ptr: u32, read_len: u32, kread_ptr: u32, kread_len: u32) -> i64;
+            pub fn ledger_seq() -> i64;
+            pub fn util_verify(
+                dread_ptr: u32,
+                dread_len: u32,
+                sread_ptr: u32,
+                sread_len: u32,
+                kread_ptr: u32,
+                kread_len: u32,
+            ) -> i64;
+            pub fn util_sha512h(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+            pub fn slot_set(read_ptr: u32, read_len: u32, slot_no: u32) -> i64;
+            pub fn slot_subfield(parent_slot: u32, field_id: u32, new_slot: u32) -> i64;
+            pub fn slot_subarray(parent_slot: u32, array_id: u32, new_slot: u32) -> i64;
+            pub fn slot_count(slot_no: u32) -> i64;
+            pub fn slot(write_ptr: u32, write_len: u32, slot_no: u32) -> i64;
+            pub fn etxn_reserve(count: u32) -> i64;
+            pub fn etxn_details(write_ptr: u32, write_len: u32) -> i64;
+            pub fn etxn_fee_base(read_ptr: u32, read_len: u32) -> i64;
+            pub fn emit(write_ptr: u32, write_len: u32, read_ptr: u32, read_len: u32) -> i64;
+            pub fn trace(
+                mread_ptr: u32,
+                mread_len: u32,
+                dread_ptr: u32,
+                dread_len: u32,
+                as_hex: u32,
+            ) -> i64;
+        }
+    }
+
+    // Hook memory is 32-bit, so pointers and lengths are passed as u32.
+    fn ptr(buf: &[u8]) -> u32 {
+        buf.as_ptr() as u32
+    }
+
+    fn len(buf: &[u8]) -> u32 {
+        buf.len() as u32
+    }
+
+    pub fn guard(id: u32, maxiter: u32) {
+        unsafe { ffi::_g(id, maxiter) };
+    }
+
+    pub fn accept(msg: &[u8], code: i64) -> ! {
+        unsafe {
+            ffi::accept(ptr(msg), len(msg), code);
+            // accept never returns control to the hook
+            core::hint::unreachable_unchecked()
+        }
+    }
+
+    pub fn rollback(msg: &[u8], code: i64) -> ! {
+        unsafe {
+            ffi::rollback(ptr(msg), len(msg), code);
+            // rollback never returns control to the hook
+            core::hint::unreachable_unchecked()
+        }
+    }
+
+    pub fn otxn_type() -> i64 {
+        unsafe { ffi::otxn_type() }
+    }
+
+    pub fn otxn_field(buf: &mut [u8], field: u32) -> i64 {
+        unsafe { ffi::otxn_field(ptr(buf), len(buf), field) }
+    }
+
+    pub fn otxn_id(buf: &mut [u8]) -> i64 {
+        unsafe { ffi::otxn_id(ptr(buf), len(buf), 0) }
+    }
+
+    pub fn hook_account(buf: &mut [u8]) -> i64 {
+        unsafe { ffi::hook_account(ptr(buf), len(buf)) }
+    }
+
+    pub fn hook_param(buf: &mut [u8], name: &[u8]) -> i64 {
+        unsafe { ffi::hook_param(ptr(buf), len(buf), ptr(name), len(name)) }
+    }
+
+    pub fn otxn_param(buf: &mut [u8], name: &[u8]) -> i64 {
+        unsafe { ffi::otxn_param(ptr(buf), len(buf), ptr(name), len(name)) }
+    }
+
+    pub fn state(buf: &mut [u8], key: &[u8]) -> i64 {
+        unsafe { ffi::state(ptr(buf), len(buf), ptr(key), len(key)) }
+    }
+
+    pub fn state_set(data: &[u8], key: &[u8]) -> i64 {
+        unsafe { ffi::state_set(ptr(data), len(data), ptr(key), len(key)) }
+    }
+
+    pub fn ledger_seq() -> i64 {
+        unsafe { ffi::ledger_seq() }
+    }
+
+    pub fn util_verify(data: &[u8], signature: &[u8], key: &[u8]) -> i64 {
+        unsafe {
+            ffi::util_verify(ptr(data), len(data), ptr(signature), len(signature), ptr(key), len(key))
+        }
+    }
+
+    pub fn util_sha512h(hash: &mut [u8], data: &[u8]) -> i64 {
+        unsafe { ffi::util_sha512h(ptr(hash), len(hash), ptr(data), len(data)) }
+    }
+
+    pub fn slot_set(keylet: &[u8], slot_no: u32) -> i64 {
+        unsafe { ffi::slot_set(ptr(keylet), len(keylet), slot_no) }
+    }
+
+    pub fn slot_subfield(parent: u32, field: u32, slot_no: u32) -> i64 {
+        unsafe { ffi::slot_subfield(parent, field, slot_no) }
+    }
+
+    pub fn slot_subarray(parent: u32, index: u32, slot_no: u32) -> i64 {
+        unsafe { ffi::slot_subarray(parent, index, slot_no) }
+    }
+
+    pub fn slot_count(slot_no: u32) -> i64 {
+        unsafe { ffi::slot_count(slot_no) }
+    }
+
+    pub fn slot(buf: &mut [u8], slot_no: u32) -> i64 {
+        unsafe { ffi::slot(ptr(buf), len(buf), slot_no) }
+    }
+
+    pub fn etxn_reserve(count: u32) -> i64 {
+        unsafe { ffi::etxn_reserve(count) }
+    }
+
+    pub fn etxn_details(buf: &mut [u8]) -> i64 {
+        unsafe { ffi::etxn_details(ptr(buf), len(buf)) }
+    }
+
+    pub fn etxn_fee_base(blob: &[u8]) -> i64 {
+        unsafe { ffi::etxn_fee_base(ptr(blob), len(blob)) }
+    }
+
+    pub fn emit(hash: &mut [u8], blob: &[u8]) -> i64 {
+        unsafe { ffi::emit(ptr(hash), len(hash), ptr(blob), len(blob)) }
+    }
+
+    pub fn trace(msg: &[u8], data: &[u8], as_hex: bool) {
+        unsafe { ffi::trace(ptr(msg), len(msg), ptr(data), len(data), as_hex as u32) };
+    }
+}
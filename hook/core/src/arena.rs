@@ -0,0 +1,137 @@
+//! Round-based combat, following `Battle` in the backend's `arena.go` with
+//! its `math/rand` calls replaced by a seeded generator, so anyone holding
+//! the seed can replay a fight.
+//!
+//! Both pets start at 100 + Endurance HP. Each round the challenger attacks
+//! first: damage is `max(1, Strength + rand(20) - (Speed / 2 + rand(10)))`
+//! against the defender's Speed. If the opponent survives it strikes back
+//! the same way, and the round counts. The fight ends when a pet drops to 0
+//! or after [`MAX_ROUNDS`]; the challenger wins if it has more HP left. A
+//! win pays `10 + (20 - rounds) + Intelligence / 10` Spark, a loss 10.
+//!
+//! `rand(n)` is `(splitmix64() >> 32) % n`, drawn in the order challenger
+//! attack, opponent defense, opponent attack, challenger defense.
+
+use crate::api::guard;
+use crate::dna::Stats;
+
+/// Rounds after which a fight is called on HP.
+pub const MAX_ROUNDS: u32 = 20;
+
+/// Spark every fighter earns; winners get a bonus on top.
+pub const BASE_REWARD: u32 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BattleResult {
+    /// Whether the challenger won.
+    pub victory: bool,
+    /// Completed rounds; a knockout in the challenger's attack does not
+    /// complete its round.
+    pub rounds: u32,
+    /// Spark reward for the challenger.
+    pub reward: u32,
+    pub challenger_hp: i32,
+    pub opponent_hp: i32,
+}
+
+/// SplitMix64, the generator behind every `rand(n)` of a fight.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-enough draw from `0..n`.
+    pub fn below(&mut self, n: u32) -> i32 {
+        ((self.next_u64() >> 32) as u32 % n) as i32
+    }
+}
+
+pub fn battle(challenger: Stats, opponent: Stats, seed: u64) -> BattleResult {
+    let mut rng = Rng::new(seed);
+    let mut challenger_hp = 100 + challenger.endurance as i32;
+    let mut opponent_hp = 100 + opponent.endurance as i32;
+
+    let mut rounds = 0;
+    loop {
+        guard(line!(), MAX_ROUNDS + 1);
+        if challenger_hp <= 0 || opponent_hp <= 0 || rounds == MAX_ROUNDS {
+            break;
+        }
+        opponent_hp -= strike(&mut rng, challenger, opponent);
+        if opponent_hp <= 0 {
+            break;
+        }
+        challenger_hp -= strike(&mut rng, opponent, challenger);
+        rounds += 1;
+    }
+
+    let victory = challenger_hp > opponent_hp;
+    let mut reward = BASE_REWARD;
+    if victory {
+        reward += (MAX_ROUNDS - rounds) + challenger.intelligence as u32 / 10;
+    }
+    BattleResult { victory, rounds, reward, challenger_hp, opponent_hp }
+}
+
+/// Damage `attacker` deals `defender` in one strike.
+fn strike(rng: &mut Rng, attacker: Stats, defender: Stats) -> i32 {
+    let attack = attacker.strength as i32 + rng.below(20);
+    let defense = defender.speed as i32 / 2 + rng.below(10);
+    (attack - defense).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(strength: u8, speed: u8, intelligence: u8, endurance: u8) -> Stats {
+        Stats { strength, speed, intelligence, endurance }
+    }
+
+    #[test]
+    fn generator_matches_splitmix64() {
+        // Reference outputs of SplitMix64 seeded with 1234567
+        let mut rng = Rng::new(1_234_567);
+        assert_eq!(rng.next_u64(), 6_457_827_717_110_365_317);
+        assert_eq!(rng.next_u64(), 3_203_168_211_198_807_973);
+    }
+
+    #[test]
+    fn same_seed_same_fight() {
+        let (a, b) = (stats(60, 40, 50, 30), stats(55, 60, 20, 40));
+        assert_eq!(battle(a, b, 42), battle(a, b, 42));
+        let outcomes = (0..64).map(|seed| battle(a, b, seed).victory);
+        let wins = outcomes.filter(|&won| won).count();
+        assert!(wins > 0 && wins < 64, "seeds should matter, got {wins} wins");
+    }
+
+    #[test]
+    fn stronger_pet_wins_by_knockout() {
+        let result = battle(stats(100, 100, 95, 100), stats(1, 1, 1, 1), 7);
+        assert!(result.victory);
+        assert!(result.opponent_hp <= 0);
+        assert_eq!(result.reward, BASE_REWARD + (MAX_ROUNDS - result.rounds) + 9);
+    }
+
+    #[test]
+    fn stalemates_end_after_max_rounds() {
+        // Minimum damage both ways: 1 HP per strike, 20 rounds
+        let result = battle(stats(1, 100, 50, 100), stats(1, 100, 50, 100), 3);
+        assert_eq!(result.rounds, MAX_ROUNDS);
+        assert_eq!((result.challenger_hp, result.opponent_hp), (180, 180));
+        assert!(!result.victory);
+        assert_eq!(result.reward, BASE_REWARD);
+    }
+}
@@ -0,0 +1,558 @@
+//! Battle hook, installed with the hatch hook (same account and namespace,
+//! so it sees the DNA hashes hatching recorded).
+//!
+//! A battle takes three Invokes, each carrying one memo, so that neither
+//! player can pick the seed [`crate::arena::battle`] fights with:
+//!
+//! 1. `pet-battle`, from the challenger's owner: the challenger's NFTokenID
+//!    and DNA, the opponent's, then a commitment `sha512half(secret)` to a
+//!    32-byte secret. Each DNA must hash to the value stored when the pet
+//!    hatched, which pins the stats [`crate::dna`] derives from it. A pet
+//!    has at most one open challenge, stored under
+//!    `sha512half("pet-battle" || challenger)`.
+//! 2. `pet-battle-accept`, from the opponent's owner: the challenger's
+//!    NFTokenID and 32 bytes of entropy of their own.
+//! 3. `pet-battle-reveal`, from anyone: the challenger's NFTokenID and the
+//!    secret. The seed is the first 8 bytes (big-endian) of
+//!    `sha512half(secret || entropy)`.
+//!
+//! The secret is fixed by the commitment before the opponent picks the
+//! entropy, and hidden from them until after, so neither side can steer the
+//! seed, and nothing about the ledger or the transactions feeds into it. The
+//! challenger's one remaining choice, not revealing a losing seed, costs
+//! them the fight: [`REVEAL_LEDGERS`] ledgers after the accept the
+//! opponent's owner can send `pet-battle-forfeit` with the challenger's
+//! NFTokenID, which records a loss with no reward.
+//!
+//! Until it is accepted, the challenger's owner can withdraw a challenge
+//! with `pet-battle-cancel` and the challenger's NFTokenID; after
+//! [`CHALLENGE_LEDGERS`] ledgers anyone can, so unanswered challenges do not
+//! hold the hook account's reserve for good.
+//!
+//! The open battle is
+//! `challenger account || challenger || opponent || challenger stats ||
+//! opponent stats || commitment || open ledger || accept ledger || entropy
+//! || challenge hash`
+//! (20 + 32 + 32 + 4 + 4 + 32 + 4 + 4 + 32 + 32 bytes; stats packed, accept
+//! ledger 0 until accepted). Settling deletes it and stores the result
+//! under the challenge's transaction hash as
+//! `challenger || opponent || seed || victory || rounds || reward`
+//! (32 + 32 + 8 + 1 + 1 + 2 bytes, integers big-endian), so anyone can look
+//! a fight up and replay it.
+
+use crate::admin::{self, WhenPaused};
+use crate::api::*;
+use crate::arena::{self, BattleResult};
+use crate::bytes::{array20, array32, eq20, eq32};
+use crate::dna::{Stats, DNA_LEN};
+use crate::error::HookError;
+use crate::hatch::owns_nftoken;
+use crate::memo::{self, MAX_MEMOS_LEN};
+use crate::triggers::{self, hook_on};
+
+/// MemoType of a battle challenge.
+pub const BATTLE_MEMO_TYPE: &[u8] = b"pet-battle";
+
+/// MemoType of the opponent accepting a challenge.
+pub const ACCEPT_MEMO_TYPE: &[u8] = b"pet-battle-accept";
+
+/// MemoType of the challenger revealing their secret.
+pub const REVEAL_MEMO_TYPE: &[u8] = b"pet-battle-reveal";
+
+/// MemoType of the opponent claiming a battle the challenger did not reveal.
+pub const FORFEIT_MEMO_TYPE: &[u8] = b"pet-battle-forfeit";
+
+/// MemoType of withdrawing a challenge nobody accepted.
+pub const CANCEL_MEMO_TYPE: &[u8] = b"pet-battle-cancel";
+
+/// Transaction types the battle hook fires on; deployment derives `HookOn` from these.
+pub const TRIGGERS: &[i64] = &[ttINVOKE];
+
+const HOOK_ON: [u8; 32] = hook_on(TRIGGERS);
+
+/// Paused, the hook rejects battles rather than let them through unhandled.
+const WHEN_PAUSED: WhenPaused = WhenPaused::Rollback;
+
+/// Ledgers after the accept in which only the challenger can settle the
+/// battle, by revealing.
+pub const REVEAL_LEDGERS: u32 = 64;
+
+/// Ledgers after the challenge in which only the challenger's owner can
+/// cancel it.
+pub const CHALLENGE_LEDGERS: u32 = 256;
+
+/// One fighter in the challenge memo: NFTokenID then hex DNA.
+const FIGHTER_LEN: usize = 32 + DNA_LEN;
+
+/// Length of a stored open battle.
+pub const OPEN_LEN: usize = 196;
+
+/// Length of the stored battle record.
+pub const RECORD_LEN: usize = 76;
+
+// Offsets into an open battle
+const CHALLENGER: usize = 20;
+const OPPONENT: usize = 52;
+const STATS: usize = 84;
+const COMMITMENT: usize = 92;
+const OPENED: usize = 124;
+const ACCEPTED: usize = 128;
+const ENTROPY: usize = 132;
+const CHALLENGE: usize = 164;
+
+pub fn battle(tx: &mut HookCtx) -> i32 {
+    if !triggers::declared(tx, &HOOK_ON) {
+        return 0;
+    }
+    admin::commands(tx);
+    admin::check_paused(tx, WHEN_PAUSED);
+    let mut sender = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut sender) != 20 || eq20(&sender, &tx.hook_account()) {
+        return 0;
+    }
+
+    let mut memos = [0u8; MAX_MEMOS_LEN];
+    let len = tx.otxn_field(sfMemos, &mut memos).max(0) as usize;
+    let memos = &memos[..len];
+    if let Some(request) = memo::find(memos, BATTLE_MEMO_TYPE) {
+        challenge(tx, &sender, request.data);
+    }
+    if let Some(request) = memo::find(memos, ACCEPT_MEMO_TYPE) {
+        accept(tx, &sender, request.data);
+    }
+    if let Some(request) = memo::find(memos, REVEAL_MEMO_TYPE) {
+        reveal(tx, request.data);
+    }
+    if let Some(request) = memo::find(memos, FORFEIT_MEMO_TYPE) {
+        forfeit(tx, &sender, request.data);
+    }
+    if let Some(request) = memo::find(memos, CANCEL_MEMO_TYPE) {
+        cancel(tx, &sender, request.data);
+    }
+    HookError::MissingBattleMemo.rollback();
+}
+
+/// Opens a battle under the challenger's key.
+fn challenge(tx: &mut HookCtx, sender: &[u8; 20], data: &[u8]) -> ! {
+    if data.len() != 2 * FIGHTER_LEN + 32 {
+        HookError::MalformedBattle.rollback();
+    }
+    let (challenger, rest) = data.split_at(FIGHTER_LEN);
+    let (opponent, commitment) = rest.split_at(FIGHTER_LEN);
+    if eq32(&array32(challenger), &array32(opponent)) {
+        HookError::SelfBattle.rollback();
+    }
+    let challenger_stats = fighter_stats(tx, challenger);
+    let opponent_stats = fighter_stats(tx, opponent);
+    if !owns_nftoken(tx, sender, &array32(challenger)) {
+        HookError::NotChallengerOwner.rollback();
+    }
+
+    let key = open_key(tx, &array32(challenger));
+    if tx.state(&key, &mut [0u8; OPEN_LEN]) >= 0 {
+        HookError::ChallengeOpen.rollback();
+    }
+
+    let mut open = [0u8; OPEN_LEN];
+    open[..CHALLENGER].copy_from_slice(sender);
+    open[CHALLENGER..OPPONENT].copy_from_slice(&challenger[..32]);
+    open[OPPONENT..STATS].copy_from_slice(&opponent[..32]);
+    open[STATS..STATS + 4].copy_from_slice(&challenger_stats.pack().to_be_bytes());
+    open[STATS + 4..COMMITMENT].copy_from_slice(&opponent_stats.pack().to_be_bytes());
+    open[COMMITMENT..OPENED].copy_from_slice(commitment);
+    open[OPENED..ACCEPTED].copy_from_slice(&tx.ledger_seq().to_be_bytes());
+    open[CHALLENGE..].copy_from_slice(&tx.otxn_id());
+    if tx.state_set(&key, &open) < 0 {
+        HookError::RecordBattleFailed.rollback();
+    }
+    ACCEPT("Battle open", 0);
+}
+
+/// Records the opponent's entropy and the ledger the battle was accepted in.
+fn accept(tx: &mut HookCtx, sender: &[u8; 20], data: &[u8]) -> ! {
+    if data.len() != 64 {
+        HookError::MalformedBattle.rollback();
+    }
+    let key = open_key(tx, &array32(data));
+    let mut open = open_battle(tx, &key);
+    if accepted_in(&open) != 0 {
+        HookError::AlreadyAccepted.rollback();
+    }
+    if eq20(sender, &array20(&open)) {
+        HookError::OwnChallenge.rollback();
+    }
+    if !owns_nftoken(tx, sender, &array32(&open[OPPONENT..])) {
+        HookError::NotOpponentOwner.rollback();
+    }
+
+    open[ACCEPTED..ENTROPY].copy_from_slice(&tx.ledger_seq().max(1).to_be_bytes());
+    open[ENTROPY..CHALLENGE].copy_from_slice(&data[32..]);
+    if tx.state_set(&key, &open) < 0 {
+        HookError::RecordBattleFailed.rollback();
+    }
+    ACCEPT("Battle accepted", 0);
+}
+
+/// Checks the secret against the commitment, then fights.
+fn reveal(tx: &mut HookCtx, data: &[u8]) -> ! {
+    if data.len() != 64 {
+        HookError::MalformedBattle.rollback();
+    }
+    let key = open_key(tx, &array32(data));
+    let open = open_battle(tx, &key);
+    if accepted_in(&open) == 0 {
+        HookError::NotAccepted.rollback();
+    }
+    let secret = &data[32..];
+    if !eq32(&tx.util_sha512h(secret), &array32(&open[COMMITMENT..])) {
+        HookError::WrongReveal.rollback();
+    }
+
+    let seed = battle_seed(tx, &array32(secret), &array32(&open[ENTROPY..]));
+    let stats = |at: usize| Stats::unpack(u32::from_be_bytes([open[at], open[at + 1], open[at + 2], open[at + 3]]));
+    let result = arena::battle(stats(STATS), stats(STATS + 4), seed);
+    settle(tx, &key, &open, seed, &result);
+    if result.victory {
+        ACCEPT("Challenger won", 0);
+    }
+    ACCEPT("Challenger lost", 0);
+}
+
+/// Records a loss for a challenger who let the reveal window pass.
+fn forfeit(tx: &mut HookCtx, sender: &[u8; 20], data: &[u8]) -> ! {
+    if data.len() != 32 {
+        HookError::MalformedBattle.rollback();
+    }
+    let key = open_key(tx, &array32(data));
+    let open = open_battle(tx, &key);
+    let accepted = accepted_in(&open);
+    if accepted == 0 {
+        HookError::NotAccepted.rollback();
+    }
+    if !owns_nftoken(tx, sender, &array32(&open[OPPONENT..])) {
+        HookError::NotOpponentOwner.rollback();
+    }
+    if tx.ledger_seq() <= accepted.saturating_add(REVEAL_LEDGERS) {
+        HookError::RevealPending.rollback();
+    }
+
+    let result = BattleResult { victory: false, rounds: 0, reward: 0, challenger_hp: 0, opponent_hp: 0 };
+    settle(tx, &key, &open, 0, &result);
+    ACCEPT("Challenger forfeited", 0);
+}
+
+/// Deletes a challenge nobody accepted: at any time for the challenger's
+/// owner, after [`CHALLENGE_LEDGERS`] for anyone else.
+fn cancel(tx: &mut HookCtx, sender: &[u8; 20], data: &[u8]) -> ! {
+    if data.len() != 32 {
+        HookError::MalformedBattle.rollback();
+    }
+    let key = open_key(tx, &array32(data));
+    let open = open_battle(tx, &key);
+    if accepted_in(&open) != 0 {
+        HookError::AlreadyAccepted.rollback();
+    }
+    let opened = u32::from_be_bytes([open[OPENED], open[OPENED + 1], open[OPENED + 2], open[OPENED + 3]]);
+    if !eq20(sender, &array20(&open)) && tx.ledger_seq() <= opened.saturating_add(CHALLENGE_LEDGERS) {
+        HookError::ChallengeNotExpired.rollback();
+    }
+    if tx.state_set(&key, &[]) < 0 {
+        HookError::RecordBattleFailed.rollback();
+    }
+    ACCEPT("Challenge cancelled", 0);
+}
+
+/// Stats of a fighter from the memo, rolling back unless it hatched with
+/// exactly this DNA.
+fn fighter_stats(tx: &HookCtx, fighter: &[u8]) -> Stats {
+    let (pet, dna) = fighter.split_at(32);
+    let mut hatched = [0u8; 32];
+    if tx.state(pet, &mut hatched) != 32 {
+        HookError::NotHatched.rollback();
+    }
+    match Stats::from_dna(dna) {
+        Some(stats) if eq32(&tx.util_sha512h(dna), &hatched) => stats,
+        _ => HookError::DnaMismatch.rollback(),
+    }
+}
+
+/// State key of the open battle of challenger `pet`.
+pub fn open_key(tx: &HookCtx, pet: &[u8; 32]) -> [u8; 32] {
+    let mut input = [0u8; BATTLE_MEMO_TYPE.len() + 32];
+    input[..BATTLE_MEMO_TYPE.len()].copy_from_slice(BATTLE_MEMO_TYPE);
+    input[BATTLE_MEMO_TYPE.len()..].copy_from_slice(pet);
+    tx.util_sha512h(&input)
+}
+
+/// The battle open under `key`; settled, cancelled and unknown battles roll
+/// back.
+fn open_battle(tx: &HookCtx, key: &[u8; 32]) -> [u8; OPEN_LEN] {
+    let mut open = [0u8; OPEN_LEN];
+    if tx.state(key, &mut open) != OPEN_LEN as i64 {
+        HookError::UnknownBattle.rollback();
+    }
+    open
+}
+
+/// Ledger an open battle was accepted in, 0 if it was not.
+fn accepted_in(open: &[u8; OPEN_LEN]) -> u32 {
+    u32::from_be_bytes([open[ACCEPTED], open[ACCEPTED + 1], open[ACCEPTED + 2], open[ACCEPTED + 3]])
+}
+
+/// Battle seed from the challenger's revealed secret and the opponent's
+/// entropy.
+pub fn battle_seed(tx: &HookCtx, secret: &[u8; 32], entropy: &[u8; 32]) -> u64 {
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(secret);
+    input[32..].copy_from_slice(entropy);
+    let hash = tx.util_sha512h(&input);
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(seed)
+}
+
+/// Replaces the open battle under `key` with its record under the
+/// challenge's hash.
+fn settle(tx: &mut HookCtx, key: &[u8; 32], open: &[u8; OPEN_LEN], seed: u64, result: &BattleResult) {
+    if tx.state_set(&array32(&open[CHALLENGE..]), &record(open, seed, result)) < 0 || tx.state_set(key, &[]) < 0 {
+        HookError::RecordBattleFailed.rollback();
+    }
+}
+
+fn record(open: &[u8; OPEN_LEN], seed: u64, result: &BattleResult) -> [u8; RECORD_LEN] {
+    let mut record = [0u8; RECORD_LEN];
+    record[..64].copy_from_slice(&open[CHALLENGER..STATS]);
+    record[64..72].copy_from_slice(&seed.to_be_bytes());
+    record[72] = result.victory as u8;
+    record[73] = result.rounds as u8;
+    record[74..].copy_from_slice(&(result.reward as u16).to_be_bytes());
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hatch::nftoken_page_keylet;
+    use crate::mock::{nftoken_page, Exit, Mock, Outcome, Tx};
+    use sha2::{Digest, Sha512};
+
+    const SHOP: [u8; 20] = [0xE6; 20];
+    const PLAYER: [u8; 20] = [0x01; 20];
+    const RIVAL: [u8; 20] = [0x02; 20];
+    const STRONG: &[u8; 32] = b"0123456789abcdef0123456789abcdef";
+    const WEAK: &[u8; 32] = b"fedcba9876543210fedcba9876543210";
+    const BATTLE_ID: [u8; 32] = [0xBA; 32];
+    const SECRET: [u8; 32] = [0x5E; 32];
+    const ENTROPY: [u8; 32] = [0xE7; 32];
+
+    fn pet(n: u8) -> [u8; 32] {
+        let mut id = [0u8; 32];
+        id[4..24].copy_from_slice(&SHOP);
+        id[31] = n;
+        id
+    }
+
+    fn sha512h(data: &[u8]) -> Vec<u8> {
+        Sha512::digest(data)[..32].to_vec()
+    }
+
+    fn owner_page(owner: &[u8; 20]) -> [u8; 34] {
+        let mut last_page = [0xFF; 32];
+        last_page[..20].copy_from_slice(owner);
+        nftoken_page_keylet(&last_page)
+    }
+
+    /// The shop with pets 1 (owned by `PLAYER`) and 2 (owned by `RIVAL`)
+    /// hatched.
+    fn arena() -> Mock {
+        Mock::new(SHOP)
+            .object(owner_page(&PLAYER), &nftoken_page(None, &[pet(1)]))
+            .object(owner_page(&RIVAL), &nftoken_page(None, &[pet(2)]))
+            .state(&pet(1), &sha512h(STRONG))
+            .state(&pet(2), &sha512h(WEAK))
+    }
+
+    /// `mock` with the state `outcome` left behind.
+    fn after(mock: Mock, outcome: &Outcome) -> Mock {
+        outcome.state.iter().fold(mock, |mock, (key, value)| mock.state(key, value))
+    }
+
+    /// Key of pet 1's open battle.
+    fn open_key() -> [u8; 32] {
+        sha512h(&[BATTLE_MEMO_TYPE, &pet(1)].concat()).try_into().unwrap()
+    }
+
+    fn challenge_tx(challenger: ([u8; 32], &[u8]), opponent: ([u8; 32], &[u8])) -> Tx {
+        let mut data = challenger.0.to_vec();
+        data.extend(challenger.1);
+        data.extend(opponent.0);
+        data.extend(opponent.1);
+        data.extend(sha512h(&SECRET));
+        Tx::new(ttINVOKE).id(BATTLE_ID).field(sfAccount, &PLAYER).memo(BATTLE_MEMO_TYPE, &data)
+    }
+
+    fn step_tx(sender: [u8; 20], memo_type: &[u8], value: Option<[u8; 32]>) -> Tx {
+        let mut data = pet(1).to_vec();
+        data.extend(value.iter().flatten());
+        Tx::new(ttINVOKE).id([0xCC; 32]).field(sfAccount, &sender).memo(memo_type, &data)
+    }
+
+    /// The battle challenged and accepted in ledger 1000.
+    fn accepted() -> Mock {
+        let open = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), WEAK)), battle);
+        assert_eq!(open.exit, Exit::Accept, "{}", open.message);
+        let accepted = after(arena(), &open).run(&step_tx(RIVAL, ACCEPT_MEMO_TYPE, Some(ENTROPY)), battle);
+        assert_eq!(accepted.exit, Exit::Accept, "{}", accepted.message);
+        after(arena(), &accepted)
+    }
+
+    fn reveal_tx(secret: [u8; 32]) -> Tx {
+        step_tx(PLAYER, REVEAL_MEMO_TYPE, Some(secret))
+    }
+
+    #[test]
+    fn records_a_replayable_fight() {
+        let outcome = accepted().run(&reveal_tx(SECRET), battle);
+        assert_eq!(outcome.exit, Exit::Accept, "{}", outcome.message);
+        assert!(!outcome.state.contains_key(&open_key()));
+        let record = &outcome.state[&BATTLE_ID];
+        assert_eq!(record.len(), RECORD_LEN);
+        assert_eq!((&record[..32], &record[32..64]), (&pet(1)[..], &pet(2)[..]));
+
+        let seed = u64::from_be_bytes(sha512h(&[SECRET, ENTROPY].concat())[..8].try_into().unwrap());
+        assert_eq!(record[64..72], seed.to_be_bytes());
+        let stats = |dna: &[u8]| Stats::from_dna(dna).unwrap();
+        let result = arena::battle(stats(STRONG), stats(WEAK), seed);
+        assert_eq!(record[72..], [result.victory as u8, result.rounds as u8, 0, result.reward as u8]);
+        let message = if result.victory { "Challenger won" } else { "Challenger lost" };
+        assert_eq!(outcome.message, message);
+    }
+
+    #[test]
+    fn challenger_cannot_choose_the_seed() {
+        // Once the opponent's entropy is known, only the committed secret
+        // settles the battle...
+        for secret in [[0u8; 32], ENTROPY, [0x5F; 32]] {
+            let outcome = accepted().run(&reveal_tx(secret), battle);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::WrongReveal.code()));
+        }
+        // ...and nothing the revealer controls or the ledger offers changes
+        // the seed it gives
+        let seed = |mock: Mock, tx: &Tx| mock.run(tx, battle).state[&BATTLE_ID][64..72].to_vec();
+        let first = seed(accepted(), &reveal_tx(SECRET));
+        assert_eq!(first, seed(accepted().ledger_seq(2_000), &reveal_tx(SECRET)));
+        assert_eq!(first, seed(accepted(), &reveal_tx(SECRET).id([0xDD; 32]).field(sfAccount, &RIVAL)));
+    }
+
+    #[test]
+    fn opponent_entropy_changes_the_seed() {
+        let open = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), WEAK)), battle);
+        let other_entropy = after(arena(), &open).run(&step_tx(RIVAL, ACCEPT_MEMO_TYPE, Some([0xE8; 32])), battle);
+        let other = after(arena(), &other_entropy).run(&reveal_tx(SECRET), battle);
+        let outcome = accepted().run(&reveal_tx(SECRET), battle);
+        assert_ne!(outcome.state[&BATTLE_ID][64..72], other.state[&BATTLE_ID][64..72]);
+    }
+
+    #[test]
+    fn allows_one_open_challenge_per_pet() {
+        let open = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), WEAK)), battle);
+        assert_eq!(open.state[&open_key()].len(), OPEN_LEN);
+        assert!(!open.state.contains_key(&BATTLE_ID));
+        let again = challenge_tx((pet(1), STRONG), (pet(2), WEAK)).id([0xBB; 32]);
+        let outcome = after(arena(), &open).run(&again, battle);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::ChallengeOpen.code()));
+        let outcome = accepted().run(&again, battle);
+        assert_eq!(outcome.code, HookError::ChallengeOpen.code());
+
+        // Settled, the pet can challenge again
+        let settled = after(arena(), &accepted().run(&reveal_tx(SECRET), battle));
+        assert_eq!(settled.run(&again, battle).exit, Exit::Accept);
+    }
+
+    #[test]
+    fn cancels_unaccepted_challenges() {
+        let open = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), WEAK)), battle);
+        let cancel = |sender| step_tx(sender, CANCEL_MEMO_TYPE, None);
+        let outcome = after(arena(), &open).run(&cancel(PLAYER), battle);
+        assert_eq!((outcome.exit, outcome.message.as_str()), (Exit::Accept, "Challenge cancelled"));
+        assert!(!outcome.state.contains_key(&open_key()));
+
+        // Anyone else only once the challenge expires
+        let outcome = after(arena(), &open).ledger_seq(1_000 + CHALLENGE_LEDGERS).run(&cancel(RIVAL), battle);
+        assert_eq!(outcome.code, HookError::ChallengeNotExpired.code());
+        let outcome = after(arena(), &open).ledger_seq(1_001 + CHALLENGE_LEDGERS).run(&cancel([0x03; 20]), battle);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert!(!outcome.state.contains_key(&open_key()));
+
+        // Accepted battles are settled by reveal or forfeit instead
+        let outcome = accepted().ledger_seq(1_001 + CHALLENGE_LEDGERS).run(&cancel(PLAYER), battle);
+        assert_eq!(outcome.code, HookError::AlreadyAccepted.code());
+        let cancelled = after(arena(), &after(arena(), &open).run(&cancel(PLAYER), battle));
+        assert_eq!(cancelled.run(&step_tx(RIVAL, ACCEPT_MEMO_TYPE, Some(ENTROPY)), battle).code, HookError::UnknownBattle.code());
+    }
+
+    #[test]
+    fn only_the_opponent_accepts_once() {
+        let open = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), WEAK)), battle);
+        let outcome = after(arena(), &open).run(&step_tx(PLAYER, ACCEPT_MEMO_TYPE, Some(ENTROPY)), battle);
+        assert_eq!(outcome.code, HookError::OwnChallenge.code());
+        let outcome = after(arena(), &open).run(&step_tx([0x03; 20], ACCEPT_MEMO_TYPE, Some(ENTROPY)), battle);
+        assert_eq!(outcome.code, HookError::NotOpponentOwner.code());
+        let outcome = accepted().run(&step_tx(RIVAL, ACCEPT_MEMO_TYPE, Some([0; 32])), battle);
+        assert_eq!(outcome.code, HookError::AlreadyAccepted.code());
+        let outcome = after(arena(), &open).run(&reveal_tx(SECRET), battle);
+        assert_eq!(outcome.code, HookError::NotAccepted.code());
+    }
+
+    #[test]
+    fn opponent_wins_a_battle_left_unrevealed() {
+        let forfeit = step_tx(RIVAL, FORFEIT_MEMO_TYPE, None);
+        let outcome = accepted().ledger_seq(1_000 + REVEAL_LEDGERS).run(&forfeit, battle);
+        assert_eq!(outcome.code, HookError::RevealPending.code());
+        let outcome = accepted().ledger_seq(1_001 + REVEAL_LEDGERS).run(&step_tx(PLAYER, FORFEIT_MEMO_TYPE, None), battle);
+        assert_eq!(outcome.code, HookError::NotOpponentOwner.code());
+
+        let outcome = accepted().ledger_seq(1_001 + REVEAL_LEDGERS).run(&forfeit, battle);
+        assert_eq!((outcome.exit, outcome.message.as_str()), (Exit::Accept, "Challenger forfeited"));
+        let record = &outcome.state[&BATTLE_ID];
+        assert_eq!(record[..64], [pet(1), pet(2)].concat());
+        assert_eq!(record[64..], [0; 12]);
+
+        // Settled, the battle can no longer be revealed
+        let settled = after(arena(), &outcome).run(&reveal_tx(SECRET), battle);
+        assert_eq!(settled.code, HookError::UnknownBattle.code());
+    }
+
+    #[test]
+    fn rolls_back_unhatched_pets_and_wrong_dna() {
+        let outcome = arena().run(&challenge_tx((pet(1), STRONG), (pet(3), WEAK)), battle);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::NotHatched.code()));
+        let outcome = arena().run(&challenge_tx((pet(1), STRONG), (pet(2), STRONG)), battle);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::DnaMismatch.code()));
+        assert!(!outcome.state.contains_key(&open_key()));
+    }
+
+    #[test]
+    fn challenger_must_be_owned_and_distinct() {
+        let outcome = arena().run(&challenge_tx((pet(2), WEAK), (pet(1), STRONG)), battle);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::NotChallengerOwner.code()));
+        let outcome = arena().run(&challenge_tx((pet(1), STRONG), (pet(1), STRONG)), battle);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::SelfBattle.code()));
+    }
+
+    #[test]
+    fn rolls_back_malformed_requests() {
+        for memo_type in [BATTLE_MEMO_TYPE, ACCEPT_MEMO_TYPE, REVEAL_MEMO_TYPE, FORFEIT_MEMO_TYPE, CANCEL_MEMO_TYPE] {
+            let tx = Tx::new(ttINVOKE).field(sfAccount, &PLAYER).memo(memo_type, &[0; 10]);
+            assert_eq!(arena().run(&tx, battle).code, HookError::MalformedBattle.code());
+        }
+        assert_eq!(arena().run(&reveal_tx(SECRET), battle).code, HookError::UnknownBattle.code());
+        assert_eq!(arena().run(&Tx::new(ttINVOKE).field(sfAccount, &PLAYER), battle).code, HookError::MissingBattleMemo.code());
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "undeclared transaction type"))]
+    fn ignores_other_transactions() {
+        assert_eq!(arena().run(&Tx::new(ttPAYMENT), battle).exit, Exit::Accept);
+    }
+}
@@ -0,0 +1,101 @@
+//! Byte helpers that keep `memcmp` and `memcpy` out of hook wasm.
+//!
+//! `==` on byte arrays compiles to a call to `memcmp`, whose loop carries no
+//! `_g` guard, so SetHook rejects any hook that links it. These compare
+//! 8-byte words instead, OR-ing the differences together so LLVM does not
+//! merge them back into a `memcmp` call. [`copy`] does the same for copies
+//! whose length is only known at run time.
+
+use crate::api::guard;
+
+/// Most bytes [`copy`] moves in one hook run, across all calls.
+pub const MAX_COPIED: u32 = 256;
+
+fn word(bytes: &[u8], at: usize) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_be_bytes(word)
+}
+
+/// Whether two 20-byte values (accounts, currencies) are equal.
+pub fn eq20(a: &[u8; 20], b: &[u8; 20]) -> bool {
+    let tail = |bytes: &[u8; 20]| u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    ((word(a, 0) ^ word(b, 0)) | (word(a, 8) ^ word(b, 8)) | (tail(a) ^ tail(b)) as u64) == 0
+}
+
+/// Whether two 32-byte values (hashes, NFTokenIDs) are equal.
+pub fn eq32(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    ((word(a, 0) ^ word(b, 0)) | (word(a, 8) ^ word(b, 8)) | (word(a, 16) ^ word(b, 16)) | (word(a, 24) ^ word(b, 24)))
+        == 0
+}
+
+/// Whether `a` sorts at or after `b`, comparing 32-byte keys bytewise.
+pub fn ge32(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let key = |bytes: &[u8; 32]| (word(bytes, 0), word(bytes, 8), word(bytes, 16), word(bytes, 24));
+    key(a) >= key(b)
+}
+
+/// First 20 bytes of `bytes` as an array; `bytes` must be at least that long.
+pub fn array20(bytes: &[u8]) -> [u8; 20] {
+    let mut array = [0u8; 20];
+    array.copy_from_slice(&bytes[..20]);
+    array
+}
+
+/// First 32 bytes of `bytes` as an array; `bytes` must be at least that long.
+pub fn array32(bytes: &[u8]) -> [u8; 32] {
+    let mut array = [0u8; 32];
+    array.copy_from_slice(&bytes[..32]);
+    array
+}
+
+/// Copies `src` to the front of `dst`, which must be at least as long, a
+/// byte at a time under a guard.
+pub fn copy(dst: &mut [u8], src: &[u8]) {
+    let mut i = 0;
+    loop {
+        guard(line!(), MAX_COPIED + 1);
+        if i == src.len() {
+            return;
+        }
+        dst[i] = src[i];
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_every_byte() {
+        let a = [7u8; 32];
+        for i in 0..32 {
+            let mut b = a;
+            b[i] ^= 1;
+            assert!(!eq32(&a, &b));
+            if i < 20 {
+                assert!(!eq20(&array20(&a), &array20(&b)));
+            }
+        }
+        assert!(eq32(&a, &a) && eq20(&array20(&a), &array20(&a)));
+    }
+
+    #[test]
+    fn orders_keys_bytewise() {
+        let mut low = [0u8; 32];
+        let mut high = [0u8; 32];
+        low[31] = 0xFF;
+        high[30] = 1;
+        assert!(ge32(&high, &low) && !ge32(&low, &high) && ge32(&low, &low));
+    }
+
+    #[test]
+    fn copies_to_the_front() {
+        let mut dst = [0u8; 6];
+        copy(&mut dst, b"abc");
+        assert_eq!(&dst, b"abc\0\0\0");
+        copy(&mut dst, b"");
+        assert_eq!(&dst[..3], b"abc");
+    }
+}
@@ -0,0 +1,160 @@
+//! Pet stats derived from DNA, the same way in hooks, the backend and the SDK.
+//!
+//! DNA is the 32-character hex string the oracle generates (16 bytes, either
+//! case). Each stat comes from one 4-byte quarter of it, in the order
+//! Strength, Speed, Intelligence, Endurance, like `NewPetFromDNA`:
+//!
+//! ```text
+//! word = big-endian u32 of bytes 4i..4i+4
+//! h    = fmix32(word ^ (i * 0x9E3779B9))    // wrapping multiply
+//! stat = 1 + h % 100                        // 1..=100
+//! ```
+//!
+//! `fmix32` is the MurmurHash3 finalizer (`h ^= h >> 16; h *= 0x85EBCA6B;
+//! h ^= h >> 13; h *= 0xC2B2AE35; h ^= h >> 16`, multiplies wrapping). It
+//! spreads every input bit over the whole word, so stats are close to
+//! uniform even for low-entropy DNA, and XORing in the stat index keeps
+//! repeated quarters from producing repeated stats. The bias of `% 100` on
+//! a 32-bit value is below one in 40 million.
+//!
+//! Nothing here loops, so it needs no guards and runs unchanged outside a
+//! hook; the `dna-abi` crate exports it over the C ABI.
+//! `vectors/dna_stats.json` holds reference values; the Go port
+//! (`StatsFromDNA` in backend/internal/game/pet.go) and the SDK port
+//! (`statsFromDNA` in sdk/dna.ts) are tested against them, and the SDK port
+//! also against the `dna-abi` wasm.
+
+/// Length of the hex DNA string.
+pub const DNA_LEN: usize = 32;
+
+/// Per-stat salt: the 32-bit golden ratio.
+const STAT_SALT: u32 = 0x9E37_79B9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub strength: u8,
+    pub speed: u8,
+    pub intelligence: u8,
+    pub endurance: u8,
+}
+
+impl Stats {
+    /// Derives stats from hex `dna`. `None` unless it is exactly
+    /// [`DNA_LEN`] hex characters.
+    pub fn from_dna(dna: &[u8]) -> Option<Stats> {
+        let dna: &[u8; DNA_LEN] = dna.try_into().ok()?;
+        Some(Stats {
+            strength: stat(word(dna, 0)?, 0),
+            speed: stat(word(dna, 1)?, 1),
+            intelligence: stat(word(dna, 2)?, 2),
+            endurance: stat(word(dna, 3)?, 3),
+        })
+    }
+
+    /// Stats as one word, Strength in the top byte and Endurance in the
+    /// bottom one.
+    pub fn pack(self) -> u32 {
+        u32::from_be_bytes([self.strength, self.speed, self.intelligence, self.endurance])
+    }
+
+    /// Stats from a word [`Stats::pack`] made.
+    pub fn unpack(word: u32) -> Stats {
+        let [strength, speed, intelligence, endurance] = word.to_be_bytes();
+        Stats { strength, speed, intelligence, endurance }
+    }
+}
+
+fn stat(word: u32, index: u32) -> u8 {
+    (1 + fmix32(word ^ index.wrapping_mul(STAT_SALT)) % 100) as u8
+}
+
+fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+/// Quarter `index` of `dna` as a big-endian word, decoded from its 8 hex
+/// characters.
+fn word(dna: &[u8; DNA_LEN], index: usize) -> Option<u32> {
+    let hex = &dna[index * 8..index * 8 + 8];
+    Some(
+        nibble(hex[0])? << 28
+            | nibble(hex[1])? << 24
+            | nibble(hex[2])? << 20
+            | nibble(hex[3])? << 16
+            | nibble(hex[4])? << 12
+            | nibble(hex[5])? << 8
+            | nibble(hex[6])? << 4
+            | nibble(hex[7])?,
+    )
+}
+
+fn nibble(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'a'..=b'f' => Some((c - b'a' + 10) as u32),
+        b'A'..=b'F' => Some((c - b'A' + 10) as u32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_stats_in_range() {
+        let stats = Stats::from_dna(b"00000000ffffffff0123456789abcdef").unwrap();
+        for stat in [stats.strength, stats.speed, stats.intelligence, stats.endurance] {
+            assert!((1..=100).contains(&stat));
+        }
+        assert_eq!(Stats::from_dna(b"0123456789ABCDEF0123456789abcdef"), Stats::from_dna(b"0123456789abcdef0123456789ABCDEF"));
+    }
+
+    #[test]
+    fn rejects_malformed_dna() {
+        assert_eq!(Stats::from_dna(b"0123456789abcdef0123456789abcde"), None);
+        assert_eq!(Stats::from_dna(b"0123456789abcdef0123456789abcdeg"), None);
+        assert_eq!(Stats::from_dna(b"0123456789abcdef0123456789abcdef0"), None);
+    }
+
+    #[test]
+    fn repeated_quarters_give_different_stats() {
+        let stats = Stats::from_dna(&[b'0'; DNA_LEN]).unwrap();
+        assert_ne!(stats.strength, stats.speed);
+        assert_ne!(stats.intelligence, stats.endurance);
+    }
+
+    #[test]
+    fn is_close_to_uniform() {
+        let mut counts = [0u32; 100];
+        for seed in 0..100_000u32 {
+            let mut dna = [0u8; DNA_LEN];
+            for (i, c) in dna.iter_mut().enumerate() {
+                *c = b"0123456789abcdef"[(fmix32(seed.wrapping_add(i as u32 * 100_003)) & 15) as usize];
+            }
+            counts[Stats::from_dna(&dna).unwrap().strength as usize - 1] += 1;
+        }
+        assert!(counts.iter().all(|&n| (850..1150).contains(&n)), "{counts:?}");
+    }
+
+    #[test]
+    fn matches_reference_vectors() {
+        let vectors: serde_json::Value = serde_json::from_str(include_str!("../vectors/dna_stats.json")).unwrap();
+        let vectors = vectors.as_array().unwrap();
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let dna = vector["dna"].as_str().unwrap();
+            let stats = Stats::from_dna(dna.as_bytes()).unwrap();
+            let expected = |name: &str| vector[name].as_u64().unwrap() as u8;
+            assert_eq!(stats.strength, expected("strength"), "{dna}");
+            assert_eq!(stats.speed, expected("speed"), "{dna}");
+            assert_eq!(stats.intelligence, expected("intelligence"), "{dna}");
+            assert_eq!(stats.endurance, expected("endurance"), "{dna}");
+            assert_eq!(stats.pack() as u64, vector["packed"].as_u64().unwrap(), "{dna}");
+        }
+    }
+}
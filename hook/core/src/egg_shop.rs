@@ -0,0 +1,344 @@
+//! Egg shop hook, installed on the egg-shop account.
+//!
+//! Incoming payments must be exactly the egg price, set with the `EGG_PRICE`
+//! hook parameter as a serialized STAmount (10 XRP when unset); anything else
+//! is rolled back. A valid purchase bumps the buyer's pending-egg count in
+//! hook state and emits an NFTokenMint of the egg, offered to the buyer for
+//! free, in the same transaction. The callback clears the pending egg once
+//! the mint lands. A mint that fails moves it to the buyer's failed-mint
+//! count instead, since they have paid for it: an Invoke from the buyer
+//! with an `egg-remint` memo emits the mint again for one failed egg.
+
+use crate::admin::{self, WhenPaused};
+use crate::amount::Amount;
+use crate::api::*;
+use crate::bytes::eq20;
+use crate::error::HookError;
+use crate::etxn::{self, MAX_URI_LEN};
+use crate::memo::{self, MAX_MEMOS_LEN};
+use crate::triggers::{self, hook_on};
+
+/// Transaction types the egg shop fires on; deployment derives `HookOn` from these.
+pub const TRIGGERS: &[i64] = &[ttPAYMENT, ttINVOKE];
+
+const HOOK_ON: [u8; 32] = hook_on(TRIGGERS);
+
+/// Paused, the hook rejects purchases rather than let them through unhandled.
+const WHEN_PAUSED: WhenPaused = WhenPaused::Rollback;
+
+/// Egg price when the `EGG_PRICE` hook parameter is not set.
+const DEFAULT_EGG_PRICE: Amount = Amount::Xrp(10_000_000);
+
+/// NFTokenTaxon of egg NFTs.
+const EGG_TAXON: u32 = 0;
+
+/// MemoType of a buyer retrying their failed egg mints.
+pub const REMINT_MEMO_TYPE: &[u8] = b"egg-remint";
+
+/// State key of `buyer`'s pending-egg count: "EGG" followed by the account.
+pub fn pending_egg_key(buyer: &[u8; 20]) -> [u8; 23] {
+    let mut key = [0u8; 23];
+    key[..3].copy_from_slice(b"EGG");
+    key[3..].copy_from_slice(buyer);
+    key
+}
+
+/// State key of `buyer`'s count of eggs whose mint failed: "EGGFAIL"
+/// followed by the account.
+pub fn failed_egg_key(buyer: &[u8; 20]) -> [u8; 27] {
+    let mut key = [0u8; 27];
+    key[..7].copy_from_slice(b"EGGFAIL");
+    key[7..].copy_from_slice(buyer);
+    key
+}
+
+pub fn egg_shop(tx: &mut HookCtx) -> i32 {
+    if !triggers::declared(tx, &HOOK_ON) {
+        return 0;
+    }
+    admin::commands(tx);
+    admin::check_paused(tx, WHEN_PAUSED);
+    if tx.otxn_type() == ttINVOKE {
+        let mut memos = [0u8; MAX_MEMOS_LEN];
+        let len = tx.otxn_field(sfMemos, &mut memos).max(0) as usize;
+        if memo::find(&memos[..len], REMINT_MEMO_TYPE).is_some() {
+            remint(tx);
+        }
+        return 0;
+    }
+    if tx.otxn_type() != ttPAYMENT {
+        return 0;
+    }
+    // Payments the shop itself sends are not purchases
+    let mut destination = [0u8; 20];
+    if tx.otxn_field(sfDestination, &mut destination) != 20 || !eq20(&destination, &tx.hook_account()) {
+        return 0;
+    }
+    let Some(price) = egg_price(tx) else {
+        HookError::InvalidEggPrice.rollback();
+    };
+    let mut flags = [0u8; 4];
+    if tx.otxn_field(sfFlags, &mut flags) == 4 && u32::from_be_bytes(flags) & tfPartialPayment != 0 {
+        HookError::PartialPayment.rollback();
+    }
+    let mut amount = [0u8; 48];
+    let len = tx.otxn_field(sfAmount, &mut amount);
+    if len < 0 || Amount::from_bytes(&amount[..len as usize]) != Some(price) {
+        HookError::WrongEggPrice.rollback();
+    }
+
+    let mut buyer = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut buyer) != 20 {
+        HookError::UnreadableBuyer.rollback();
+    }
+    let key = pending_egg_key(&buyer);
+    if set_count(tx, &key, count(tx, &key).saturating_add(1)) < 0 {
+        HookError::RecordEggFailed.rollback();
+    }
+    mint(tx, &buyer);
+    ACCEPT("Egg purchased", 0);
+}
+
+/// Moves one of the sender's failed eggs back to pending and mints it again.
+fn remint(tx: &mut HookCtx) -> ! {
+    let mut buyer = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut buyer) != 20 {
+        HookError::UnreadableBuyer.rollback();
+    }
+    let failed_key = failed_egg_key(&buyer);
+    let failed = count(tx, &failed_key);
+    if failed == 0 {
+        HookError::NoFailedEgg.rollback();
+    }
+    let pending_key = pending_egg_key(&buyer);
+    let pending = count(tx, &pending_key);
+    if set_count(tx, &failed_key, failed - 1) < 0 || set_count(tx, &pending_key, pending.saturating_add(1)) < 0 {
+        HookError::RecordEggFailed.rollback();
+    }
+    mint(tx, &buyer);
+    ACCEPT("Egg mint retried", 0);
+}
+
+/// Emits the mint of one egg, offered to `buyer`.
+fn mint(tx: &mut HookCtx, buyer: &[u8; 20]) {
+    let mut uri = [0u8; MAX_URI_LEN];
+    let uri_len = admin::param(tx, b"EGG_URI", &mut uri).max(0) as usize;
+    if tx.etxn_reserve(1) < 0 {
+        HookError::ReserveMintFailed.rollback();
+    }
+    if etxn::emit_nftoken_mint(tx, EGG_TAXON, tfTransferable, &uri[..uri_len], buyer).is_err() {
+        HookError::EmitMintFailed.rollback();
+    }
+}
+
+/// Callback for emitted egg mints: settles one of the buyer's pending eggs,
+/// clearing it once a mint has been applied and counting it as failed, to
+/// be reminted, if the mint was not.
+pub fn egg_shop_cbak(tx: &mut HookCtx, what: u32) -> i32 {
+    if tx.otxn_type() != ttNFTOKEN_MINT {
+        return 0;
+    }
+    let mut buyer = [0u8; 20];
+    if tx.otxn_field(sfDestination, &mut buyer) != 20 {
+        return 0;
+    }
+    let key = pending_egg_key(&buyer);
+    let pending = count(tx, &key);
+    if pending == 0 {
+        return 0;
+    }
+    set_count(tx, &key, pending - 1);
+    if what != 0 {
+        let failed_key = failed_egg_key(&buyer);
+        set_count(tx, &failed_key, count(tx, &failed_key).saturating_add(1));
+    }
+    0
+}
+
+/// The egg count under `key`, 0 if there is none.
+fn count(tx: &HookCtx, key: &[u8]) -> u32 {
+    let mut count = [0u8; 4];
+    if tx.state(key, &mut count) == 4 { u32::from_be_bytes(count) } else { 0 }
+}
+
+/// Stores `count` under `key`, deleting it at 0. Returns the `state_set`
+/// result.
+fn set_count(tx: &mut HookCtx, key: &[u8], count: u32) -> i64 {
+    if count == 0 {
+        return tx.state_set(key, &[]);
+    }
+    tx.state_set(key, &count.to_be_bytes())
+}
+
+/// Egg price from the `EGG_PRICE` hook parameter, falling back to the
+/// default when it is absent. `None` if it is not a valid amount.
+fn egg_price(tx: &HookCtx) -> Option<Amount> {
+    let mut param = [0u8; 48];
+    match admin::param(tx, b"EGG_PRICE", &mut param) {
+        DOESNT_EXIST => Some(DEFAULT_EGG_PRICE),
+        len if len > 0 => Amount::from_bytes(&param[..len as usize]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::IouValue;
+    use crate::mock::{field_of, state_key, Exit, Mock, Tx};
+
+    const SHOP: [u8; 20] = [0xE6; 20];
+    const BUYER: [u8; 20] = [0x01; 20];
+
+    fn pending(count: u32) -> Vec<u8> {
+        count.to_be_bytes().to_vec()
+    }
+
+    #[test]
+    fn records_pending_egg_for_exact_payment() {
+        let tx = Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000));
+        let outcome = Mock::new(SHOP).run(&tx, egg_shop);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert_eq!(outcome.state.get(&state_key(&pending_egg_key(&BUYER))), Some(&pending(1)));
+    }
+
+    #[test]
+    fn emits_egg_mint_offered_to_buyer() {
+        let mock = Mock::new(SHOP).ledger_seq(500).param(b"EGG_URI", b"ipfs://egg");
+        let outcome = mock.run(&Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)), egg_shop);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert_eq!(outcome.emitted.len(), 1);
+
+        let mut expected = vec![0x12, 0x00, 0x19]; // NFTokenMint
+        expected.extend([0x22, 0, 0, 0, 8]); // tfTransferable
+        expected.extend([0x24, 0, 0, 0, 0]); // Sequence
+        expected.extend([0x20, 0x1A, 0, 0, 0x01, 0xF5]); // FirstLedgerSequence 501
+        expected.extend([0x20, 0x1B, 0, 0, 0x01, 0xF9]); // LastLedgerSequence 505
+        expected.extend([0x20, 0x2A, 0, 0, 0, 0]); // NFTokenTaxon
+        expected.extend([0x61, 0x40, 0, 0, 0, 0, 0, 0, 0]); // Amount 0
+        expected.extend([0x68, 0x40, 0, 0, 0, 0, 0, 0, 10]); // Fee
+        expected.extend([0x73, 0x00]); // SigningPubKey
+        expected.extend([0x75, 10]); // URI
+        expected.extend(b"ipfs://egg");
+        expected.extend([0x81, 0x14]);
+        expected.extend(SHOP);
+        expected.extend([0x83, 0x14]);
+        expected.extend(BUYER);
+        let blob = &outcome.emitted[0];
+        assert_eq!(blob[..expected.len()], expected[..]);
+        assert_eq!(blob.len(), expected.len() + etxn::EMIT_DETAILS_LEN);
+    }
+
+    #[test]
+    fn callback_clears_pending_egg_once_minted() {
+        let key = pending_egg_key(&BUYER);
+        let mint = Tx::new(ttNFTOKEN_MINT).field(sfAccount, &SHOP).field(sfDestination, &BUYER);
+
+        let mock = Mock::new(SHOP).state(&key, &pending(2));
+        let outcome = mock.callback(&mint, egg_shop_cbak, 0);
+        assert_eq!(outcome.state.get(&state_key(&key)), Some(&pending(1)));
+
+        let mock = Mock::new(SHOP).state(&key, &pending(1));
+        assert!(mock.callback(&mint, egg_shop_cbak, 0).state.is_empty());
+        assert!(Mock::new(SHOP).callback(&mint, egg_shop_cbak, 0).state_writes.is_empty());
+    }
+
+    #[test]
+    fn callback_keeps_a_failed_mint_for_the_buyer() {
+        let mint = Tx::new(ttNFTOKEN_MINT).field(sfAccount, &SHOP).field(sfDestination, &BUYER);
+        let mock = Mock::new(SHOP).state(&pending_egg_key(&BUYER), &pending(2)).state(&failed_egg_key(&BUYER), &pending(1));
+        let outcome = mock.callback(&mint, egg_shop_cbak, 1);
+        assert_eq!(outcome.state.get(&state_key(&pending_egg_key(&BUYER))), Some(&pending(1)));
+        assert_eq!(outcome.state.get(&state_key(&failed_egg_key(&BUYER))), Some(&pending(2)));
+    }
+
+    #[test]
+    fn remints_a_failed_egg_for_its_buyer() {
+        let remint = Tx::new(ttINVOKE).field(sfAccount, &BUYER).memo(REMINT_MEMO_TYPE, &[]);
+        let mock = Mock::new(SHOP).state(&failed_egg_key(&BUYER), &pending(1));
+        let outcome = mock.run(&remint, egg_shop);
+        assert_eq!((outcome.exit, outcome.message.as_str()), (Exit::Accept, "Egg mint retried"));
+        assert_eq!(outcome.state.get(&state_key(&pending_egg_key(&BUYER))), Some(&pending(1)));
+        assert!(!outcome.state.contains_key(&state_key(&failed_egg_key(&BUYER))));
+        assert_eq!(outcome.emitted.len(), 1);
+        assert_eq!(field_of(&outcome.emitted[0], sfDestination).unwrap(), BUYER);
+
+        // Only eggs that failed, and only for their own buyer
+        let outcome = Mock::new(SHOP).run(&remint, egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::NoFailedEgg.code()));
+        let outcome = mock.run(&remint.field(sfAccount, &[0x02; 20]), egg_shop);
+        assert_eq!(outcome.code, HookError::NoFailedEgg.code());
+
+        // Other Invokes pass through
+        let outcome = mock.run(&Tx::new(ttINVOKE).field(sfAccount, &BUYER), egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Accept, 0));
+        assert!(outcome.state_writes.is_empty());
+    }
+
+    #[test]
+    fn counts_repeat_purchases() {
+        let mock = Mock::new(SHOP).state(&pending_egg_key(&BUYER), &pending(2));
+        let outcome = mock.run(&Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)), egg_shop);
+        assert_eq!(outcome.state.get(&state_key(&pending_egg_key(&BUYER))), Some(&pending(3)));
+    }
+
+    #[test]
+    fn rolls_back_wrong_price_or_currency() {
+        let usd = Amount::Iou { value: IouValue::new(10, 0).unwrap(), currency: [0x55; 20], issuer: SHOP };
+        for amount in [Amount::Xrp(9_999_999), Amount::Xrp(10_000_001), usd] {
+            let outcome = Mock::new(SHOP).run(&Tx::payment(BUYER, SHOP, &amount), egg_shop);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::WrongEggPrice.code()));
+            assert!(outcome.state.is_empty());
+        }
+    }
+
+    #[test]
+    fn reads_price_from_hook_parameter() {
+        let price = Amount::Iou { value: IouValue::new(25, 0).unwrap(), currency: [0x55; 20], issuer: SHOP };
+        let mut buf = [0u8; 48];
+        let len = price.serialize(&mut buf);
+        let mock = Mock::new(SHOP).param(b"EGG_PRICE", &buf[..len]);
+
+        let outcome = mock.run(&Tx::payment(BUYER, SHOP, &price), egg_shop);
+        assert_eq!(outcome.exit, Exit::Accept);
+        let outcome = mock.run(&Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)), egg_shop);
+        assert_eq!(outcome.exit, Exit::Rollback);
+    }
+
+    #[test]
+    fn rolls_back_on_invalid_price_parameter() {
+        let mock = Mock::new(SHOP).param(b"EGG_PRICE", &[0x40, 0x00]);
+        let outcome = mock.run(&Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)), egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::InvalidEggPrice.code()));
+    }
+
+    #[test]
+    fn rolls_back_partial_payments() {
+        let tx = Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)).field(sfFlags, &tfPartialPayment.to_be_bytes());
+        let outcome = Mock::new(SHOP).run(&tx, egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::PartialPayment.code()));
+    }
+
+    #[test]
+    fn rolls_back_when_the_buyer_cannot_be_read() {
+        let tx = Tx::payment(BUYER, SHOP, &Amount::Xrp(10_000_000)).field(sfAccount, &BUYER[..19]);
+        let outcome = Mock::new(SHOP).run(&tx, egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::UnreadableBuyer.code()));
+        assert!(outcome.state.is_empty() && outcome.emitted.is_empty());
+    }
+
+    #[test]
+    fn ignores_outgoing_payments() {
+        let outgoing = Tx::payment(SHOP, BUYER, &Amount::Xrp(1));
+        let outcome = Mock::new(SHOP).run(&outgoing, egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Accept, 0));
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "undeclared transaction type"))]
+    fn ignores_other_transactions() {
+        let outcome = Mock::new(SHOP).run(&Tx::new(3), egg_shop);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Accept, 0));
+        assert!(outcome.state_writes.is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+//! Why a hook rolled back a transaction, or let it through without acting
+//! on it.
+//!
+//! Every reason has a stable code, passed as the error code of
+//! `ROLLBACK`/`ACCEPT` and so recorded as the `HookReturnCode` in the
+//! transaction metadata, and a short message, recorded as the
+//! `HookReturnString`. Codes are grouped by hook:
+//!
+//! ```text
+//!  -1      panic in any hook
+//!   0      success
+//! 100-199  admin commands and pausing (every hook)
+//! 200-299  Spark burn
+//! 300-399  egg shop
+//! 400-499  hatch
+//! 500-599  battle
+//! 600-699  reward treasury
+//! ```
+//!
+//! Codes are never reused or renumbered. `vectors/hook_errors.json`, the Go
+//! backend's `internal/hookerr` and the SDK's `hookErrors.ts` decode them;
+//! a test keeps all three in step with [`HookError::ALL`].
+
+use crate::api::{ACCEPT, ROLLBACK};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookError {
+    Panic = -1,
+
+    InvalidAdmin = 100,
+    NotAdmin = 101,
+    NotMultisigned = 102,
+    MalformedCommand = 103,
+    CommandFailed = 104,
+    Paused = 105,
+
+    InvalidBurnBps = 201,
+    InvalidBurnOn = 202,
+    InvalidBurnTo = 203,
+    NotSpark = 210,
+    IssuerTransfer = 211,
+    DirectionNotBurned = 212,
+    ExemptAccount = 213,
+    BelowMinimumBurn = 214,
+    BurnPayment = 215,
+    PartialBurnPayment = 216,
+    ReserveBurnFailed = 220,
+    EmitBurnFailed = 221,
+    RecordBurnFailed = 222,
+
+    InvalidEggPrice = 301,
+    PartialPayment = 302,
+    WrongEggPrice = 303,
+    RecordEggFailed = 304,
+    ReserveMintFailed = 305,
+    EmitMintFailed = 306,
+    UnreadableBuyer = 307,
+    NoFailedEgg = 308,
+
+    MissingHatchMemo = 401,
+    MalformedHatch = 402,
+    ForeignEgg = 403,
+    AlreadyHatched = 404,
+    NotEggOwner = 405,
+    RecordDnaFailed = 406,
+
+    MissingBattleMemo = 501,
+    MalformedBattle = 502,
+    SelfBattle = 503,
+    NotHatched = 504,
+    DnaMismatch = 505,
+    NotChallengerOwner = 506,
+    RecordBattleFailed = 507,
+    NotOpponentOwner = 508,
+    UnknownBattle = 509,
+    AlreadyAccepted = 510,
+    NotAccepted = 511,
+    WrongReveal = 512,
+    OwnChallenge = 513,
+    RevealPending = 514,
+    ChallengeOpen = 515,
+    ChallengeNotExpired = 516,
+
+    MissingClaimMemo = 601,
+    MalformedClaim = 602,
+    ClaimForOther = 603,
+    MissingMmKey = 604,
+    InvalidClaimSignature = 605,
+    NothingToClaim = 606,
+    RecordClaimFailed = 607,
+    ClaimTooLarge = 608,
+    ReservePayoutFailed = 609,
+    EmitPayoutFailed = 610,
+    RecordPayoutFailed = 611,
+}
+
+impl HookError {
+    /// Every reason, in code order.
+    pub const ALL: &'static [HookError] = &[
+        HookError::Panic,
+        HookError::InvalidAdmin,
+        HookError::NotAdmin,
+        HookError::NotMultisigned,
+        HookError::MalformedCommand,
+        HookError::CommandFailed,
+        HookError::Paused,
+        HookError::InvalidBurnBps,
+        HookError::InvalidBurnOn,
+        HookError::InvalidBurnTo,
+        HookError::NotSpark,
+        HookError::IssuerTransfer,
+        HookError::DirectionNotBurned,
+        HookError::ExemptAccount,
+        HookError::BelowMinimumBurn,
+        HookError::BurnPayment,
+        HookError::PartialBurnPayment,
+        HookError::ReserveBurnFailed,
+        HookError::EmitBurnFailed,
+        HookError::RecordBurnFailed,
+        HookError::InvalidEggPrice,
+        HookError::PartialPayment,
+        HookError::WrongEggPrice,
+        HookError::RecordEggFailed,
+        HookError::ReserveMintFailed,
+        HookError::EmitMintFailed,
+        HookError::UnreadableBuyer,
+        HookError::NoFailedEgg,
+        HookError::MissingHatchMemo,
+        HookError::MalformedHatch,
+        HookError::ForeignEgg,
+        HookError::AlreadyHatched,
+        HookError::NotEggOwner,
+        HookError::RecordDnaFailed,
+        HookError::MissingBattleMemo,
+        HookError::MalformedBattle,
+        HookError::SelfBattle,
+        HookError::NotHatched,
+        HookError::DnaMismatch,
+        HookError::NotChallengerOwner,
+        HookError::RecordBattleFailed,
+        HookError::NotOpponentOwner,
+        HookError::UnknownBattle,
+        HookError::AlreadyAccepted,
+        HookError::NotAccepted,
+        HookError::WrongReveal,
+        HookError::OwnChallenge,
+        HookError::RevealPending,
+        HookError::ChallengeOpen,
+        HookError::ChallengeNotExpired,
+        HookError::MissingClaimMemo,
+        HookError::MalformedClaim,
+        HookError::ClaimForOther,
+        HookError::MissingMmKey,
+        HookError::InvalidClaimSignature,
+        HookError::NothingToClaim,
+        HookError::RecordClaimFailed,
+        HookError::ClaimTooLarge,
+        HookError::ReservePayoutFailed,
+        HookError::EmitPayoutFailed,
+        HookError::RecordPayoutFailed,
+    ];
+
+    pub const fn code(self) -> i64 {
+        self as i64
+    }
+
+    pub const fn message(self) -> &'static str {
+        match self {
+            HookError::Panic => "panic",
+
+            HookError::InvalidAdmin => "Invalid ADMIN parameter",
+            HookError::NotAdmin => "Only the admin can send admin commands",
+            HookError::NotMultisigned => "Admin commands must be multi-signed",
+            HookError::MalformedCommand => "Malformed admin command",
+            HookError::CommandFailed => "Could not apply admin command",
+            HookError::Paused => "Hooks are paused",
+
+            HookError::InvalidBurnBps => "Invalid BURN_BPS parameter",
+            HookError::InvalidBurnOn => "Invalid BURN_ON parameter",
+            HookError::InvalidBurnTo => "Invalid BURN_TO parameter",
+            HookError::NotSpark => "Not a Spark payment",
+            HookError::IssuerTransfer => "Spark issuance or redemption",
+            HookError::DirectionNotBurned => "Payment direction not burned",
+            HookError::ExemptAccount => "Exempt account",
+            HookError::BelowMinimumBurn => "Burn below minimum",
+            HookError::BurnPayment => "Payment to the burn account",
+            HookError::PartialBurnPayment => "Partial Spark payment not burned",
+            HookError::ReserveBurnFailed => "Could not reserve burn",
+            HookError::EmitBurnFailed => "Could not emit burn",
+            HookError::RecordBurnFailed => "Could not record burn",
+
+            HookError::InvalidEggPrice => "Invalid EGG_PRICE parameter",
+            HookError::PartialPayment => "Partial payments cannot buy eggs",
+            HookError::WrongEggPrice => "Payment does not match egg price",
+            HookError::RecordEggFailed => "Could not record pending egg",
+            HookError::ReserveMintFailed => "Could not reserve egg mint",
+            HookError::EmitMintFailed => "Could not emit egg mint",
+            HookError::UnreadableBuyer => "Could not read buyer account",
+            HookError::NoFailedEgg => "No failed egg mint to retry",
+
+            HookError::MissingHatchMemo => "Missing pet-hatch memo",
+            HookError::MalformedHatch => "Malformed hatch request",
+            HookError::ForeignEgg => "Not an egg from this shop",
+            HookError::AlreadyHatched => "Egg already hatched",
+            HookError::NotEggOwner => "Sender does not own the egg",
+            HookError::RecordDnaFailed => "Could not record DNA",
+
+            HookError::MissingBattleMemo => "Missing pet-battle memo",
+            HookError::MalformedBattle => "Malformed battle request",
+            HookError::SelfBattle => "A pet cannot fight itself",
+            HookError::NotHatched => "Pet has not hatched",
+            HookError::DnaMismatch => "DNA does not match the hatched pet",
+            HookError::NotChallengerOwner => "Sender does not own the challenger",
+            HookError::RecordBattleFailed => "Could not record battle",
+            HookError::NotOpponentOwner => "Sender does not own the opponent",
+            HookError::UnknownBattle => "No such open battle",
+            HookError::AlreadyAccepted => "Battle already accepted",
+            HookError::NotAccepted => "Battle not accepted yet",
+            HookError::WrongReveal => "Reveal does not match the commitment",
+            HookError::OwnChallenge => "Challenger cannot accept their own battle",
+            HookError::RevealPending => "Challenger can still reveal",
+            HookError::ChallengeOpen => "Pet already has an open challenge",
+            HookError::ChallengeNotExpired => "Only the challenger can cancel before the challenge expires",
+
+            HookError::MissingClaimMemo => "Missing spark-claim memo",
+            HookError::MalformedClaim => "Malformed claim",
+            HookError::ClaimForOther => "Claim is for another account",
+            HookError::MissingMmKey => "MM_KEY parameter not set",
+            HookError::InvalidClaimSignature => "Invalid claim signature",
+            HookError::NothingToClaim => "Nothing left to claim",
+            HookError::RecordClaimFailed => "Could not record claim",
+            HookError::ClaimTooLarge => "Claim too large",
+            HookError::ReservePayoutFailed => "Could not reserve payout",
+            HookError::EmitPayoutFailed => "Could not emit payout",
+            HookError::RecordPayoutFailed => "Could not record payout",
+        }
+    }
+
+    /// The reason with `code`. For off-chain decoding: the search has no
+    /// guard, so hooks must not call this.
+    pub fn from_code(code: i64) -> Option<HookError> {
+        HookError::ALL.iter().copied().find(|e| e.code() == code)
+    }
+
+    /// Ends the hook and rejects the originating transaction.
+    pub fn rollback(self) -> ! {
+        ROLLBACK(self.message(), self.code())
+    }
+
+    /// Ends the hook and lets the originating transaction through without
+    /// acting on it.
+    pub fn accept(self) -> ! {
+        ACCEPT(self.message(), self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entries of the decoder table that opens on the line containing
+    /// `start`, one `key: "message",` per line up to the closing brace.
+    /// `key` reads a key the way the table's language does; any line that
+    /// does not parse fails the test, so the table cannot hold something
+    /// the compiler would reject.
+    fn table_entries(source: &str, start: &str, key: fn(&str) -> Option<i64>) -> Vec<(i64, String)> {
+        let mut lines = source.lines().skip_while(|line| !line.contains(start));
+        assert!(lines.next().is_some_and(|line| line.trim_end().ends_with('{')), "no table opening with {start}");
+        lines
+            .take_while(|line| !line.trim_start().starts_with('}'))
+            .map(|line| {
+                let entry = line.trim().split_once(':').and_then(|(code, message)| {
+                    let message = message.trim().strip_prefix('"')?.strip_suffix("\",")?;
+                    Some((key(code)?, message.to_string()))
+                });
+                entry.unwrap_or_else(|| panic!("bad table line {line:?}"))
+            })
+            .collect()
+    }
+
+    /// A Go map key: any integer literal.
+    fn go_key(key: &str) -> Option<i64> {
+        key.parse().ok()
+    }
+
+    /// A TypeScript property name: a plain non-negative integer, or any
+    /// integer in double quotes. `-1` unquoted is a syntax error.
+    fn ts_key(key: &str) -> Option<i64> {
+        if let Some(quoted) = key.strip_prefix('"').and_then(|key| key.strip_suffix('"')) {
+            return quoted.parse().ok();
+        }
+        let plain = !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit()) && (key == "0" || !key.starts_with('0'));
+        plain.then(|| key.parse().ok())?
+    }
+
+    fn expected() -> Vec<(i64, String)> {
+        HookError::ALL.iter().map(|e| (e.code(), e.message().to_string())).collect()
+    }
+
+    #[test]
+    fn codes_are_unique_and_ordered() {
+        assert!(HookError::ALL.windows(2).all(|pair| pair[0].code() < pair[1].code()));
+        for &error in HookError::ALL {
+            assert_eq!(HookError::from_code(error.code()), Some(error));
+        }
+        assert_eq!(HookError::from_code(0), None);
+    }
+
+    #[test]
+    fn matches_reference_table() {
+        let table: serde_json::Value = serde_json::from_str(include_str!("../vectors/hook_errors.json")).unwrap();
+        let table: Vec<_> = table
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| (entry["code"].as_i64().unwrap(), entry["message"].as_str().unwrap().to_string()))
+            .collect();
+        assert_eq!(table, expected());
+    }
+
+    #[test]
+    fn matches_backend_and_sdk_decoders() {
+        let go = include_str!("../../../backend/internal/hookerr/hookerr.go");
+        assert_eq!(table_entries(go, "var Messages", go_key), expected(), "backend/internal/hookerr/hookerr.go");
+        let ts = include_str!("../../../sdk/hookErrors.ts");
+        assert_eq!(table_entries(ts, "export const HOOK_ERRORS", ts_key), expected(), "sdk/hookErrors.ts");
+    }
+
+    #[test]
+    fn reads_table_keys_as_their_languages_do() {
+        assert_eq!(go_key("-1"), Some(-1));
+        assert_eq!(ts_key("\"-1\""), Some(-1));
+        assert_eq!(ts_key("501"), Some(501));
+        for bad in ["-1", "+1", "0501", "", "\"-1"] {
+            assert_eq!(ts_key(bad), None, "{bad}");
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Emitted transactions: serializing a transaction in a stack buffer and
+//! handing it to `emit`.
+//!
+//! Fields go in canonical order (type code, then field code). Every emitted
+//! transaction carries `Sequence` 0, an empty `SigningPubKey`, a short
+//! `FirstLedgerSequence`..`LastLedgerSequence` window and the `EmitDetails`
+//! object from `etxn_details`. The `Fee` is filled in last, once
+//! `etxn_fee_base` can price the finished blob. Callers must `etxn_reserve`
+//! before emitting.
+//!
+//! [`payment`], [`nftoken_mint`] and [`nftoken_create_offer`] build the
+//! transaction up to `EmitDetails` from an [`Origin`] rather than the hook
+//! context, so they can be checked natively; the `emit_*` functions wrap
+//! them.
+
+use crate::amount::Amount;
+use crate::api::*;
+use crate::stobject::Writer;
+
+/// Emitted transactions expire if not applied within this many ledgers.
+const LEDGER_WINDOW: u32 = 4;
+
+/// Largest `EmitDetails` object `etxn_details` writes (with a callback).
+pub const EMIT_DETAILS_LEN: usize = 138;
+
+/// Longest NFToken URI the ledger accepts.
+pub const MAX_URI_LEN: usize = 256;
+
+/// Buffer size that fits any NFTokenMint built by [`emit_nftoken_mint`].
+pub const NFTOKEN_MINT_LEN: usize = 512;
+
+/// Buffer size that fits any NFTokenCreateOffer built by
+/// [`emit_nftoken_create_offer`].
+pub const NFTOKEN_OFFER_LEN: usize = 384;
+
+/// Buffer size that fits any Payment built by [`emit_payment`].
+pub const PAYMENT_LEN: usize = 320;
+
+/// Who emits a transaction and when: the hook account, and the ledger the
+/// hook runs in, which the transaction's ledger window follows.
+#[derive(Clone, Copy, Debug)]
+pub struct Origin {
+    pub account: [u8; 20],
+    pub ledger_seq: u32,
+}
+
+impl Origin {
+    pub fn of(tx: &HookCtx) -> Origin {
+        let ledger_seq = tx.ledger_seq();
+        Origin { account: tx.hook_account(), ledger_seq }
+    }
+}
+
+/// Writes the fields every emitted transaction shares and that sort before
+/// any type-specific UInt32: type, flags, sequence and the ledger window.
+fn common_fields(w: &mut Writer, origin: &Origin, tt: i64, flags: u32) {
+    w.u16(sfTransactionType, tt as u16);
+    w.u32(sfFlags, flags);
+    w.u32(sfSequence, 0);
+    w.u32(sfFirstLedgerSequence, origin.ledger_seq + 1);
+    w.u32(sfLastLedgerSequence, origin.ledger_seq + 1 + LEDGER_WINDOW);
+}
+
+/// Writes the zero `Fee`, to be patched once the blob is priced, and the
+/// empty `SigningPubKey` that follows it. Returns the offset of the fee.
+fn fee_and_key(w: &mut Writer) -> usize {
+    let fee_offset = w.amount(sfFee, &Amount::Xrp(0));
+    w.vl(sfSigningPubKey, &[]);
+    fee_offset
+}
+
+/// Appends `EmitDetails`, prices the blob, patches the fee at `fee_offset`
+/// and emits it. Returns the emitted transaction's hash.
+fn finish(tx: &mut HookCtx, w: &mut Writer, fee_offset: usize) -> Result<[u8; 32], i64> {
+    let start = w.len;
+    let details = tx.etxn_details(&mut w.buf[start..]);
+    if details < 0 {
+        return Err(details);
+    }
+    w.len += details as usize;
+    let fee = tx.etxn_fee_base(w.as_bytes());
+    if fee < 0 {
+        return Err(fee);
+    }
+    let mut serialized = [0u8; 48];
+    Amount::Xrp(fee as u64).serialize(&mut serialized);
+    w.buf[fee_offset..fee_offset + 8].copy_from_slice(&serialized[..8]);
+    tx.emit(w.as_bytes())
+}
+
+/// Writes a Payment of `amount` from `origin` to `destination`. Returns the
+/// offset of the fee.
+pub fn payment(w: &mut Writer, origin: &Origin, destination: &[u8; 20], amount: &Amount) -> usize {
+    common_fields(w, origin, ttPAYMENT, 0);
+    w.amount(sfAmount, amount);
+    let fee_offset = fee_and_key(w);
+    w.account(sfAccount, &origin.account);
+    w.account(sfDestination, destination);
+    fee_offset
+}
+
+/// Writes an NFTokenMint that also creates a zero-priced sell offer to
+/// `destination`. Returns the offset of the fee, or `TOO_BIG` if `uri` is
+/// longer than the ledger accepts.
+pub fn nftoken_mint(
+    w: &mut Writer,
+    origin: &Origin,
+    taxon: u32,
+    flags: u32,
+    uri: &[u8],
+    destination: &[u8; 20],
+) -> Result<usize, i64> {
+    if uri.len() > MAX_URI_LEN {
+        return Err(TOO_BIG);
+    }
+    common_fields(w, origin, ttNFTOKEN_MINT, flags);
+    w.u32(sfNFTokenTaxon, taxon);
+    w.amount(sfAmount, &Amount::Xrp(0));
+    let fee_offset = fee_and_key(w);
+    if !uri.is_empty() {
+        w.vl(sfURI, uri);
+    }
+    w.account(sfAccount, &origin.account);
+    w.account(sfDestination, destination);
+    Ok(fee_offset)
+}
+
+/// Writes an NFTokenCreateOffer for `nftoken_id` at `amount`: a sell offer
+/// if `flags` has `tfSellNFToken`, otherwise a buy offer from the token's
+/// `owner`. A `destination` limits who may accept it. Returns the offset of
+/// the fee.
+pub fn nftoken_create_offer(
+    w: &mut Writer,
+    origin: &Origin,
+    nftoken_id: &[u8; 32],
+    amount: &Amount,
+    flags: u32,
+    owner: Option<&[u8; 20]>,
+    destination: Option<&[u8; 20]>,
+) -> usize {
+    common_fields(w, origin, ttNFTOKEN_CREATE_OFFER, flags);
+    w.hash256(sfNFTokenID, nftoken_id);
+    w.amount(sfAmount, amount);
+    let fee_offset = fee_and_key(w);
+    w.account(sfAccount, &origin.account);
+    if let Some(owner) = owner {
+        w.account(sfOwner, owner);
+    }
+    if let Some(destination) = destination {
+        w.account(sfDestination, destination);
+    }
+    fee_offset
+}
+
+/// Emits a Payment of `amount` from the hook account to `destination`.
+pub fn emit_payment(tx: &mut HookCtx, destination: &[u8; 20], amount: &Amount) -> Result<[u8; 32], i64> {
+    let mut buf = [0u8; PAYMENT_LEN];
+    let mut w = Writer::new(&mut buf);
+    let fee_offset = payment(&mut w, &Origin::of(tx), destination, amount);
+    finish(tx, &mut w, fee_offset)
+}
+
+/// Emits an NFTokenMint from the hook account that also creates a
+/// zero-priced sell offer to `destination`, so the buyer can accept the
+/// token without another round trip through the issuer.
+///
+/// Minting with `Amount`/`Destination` needs the NFTokenMintOffer amendment.
+pub fn emit_nftoken_mint(
+    tx: &mut HookCtx,
+    taxon: u32,
+    flags: u32,
+    uri: &[u8],
+    destination: &[u8; 20],
+) -> Result<[u8; 32], i64> {
+    let mut buf = [0u8; NFTOKEN_MINT_LEN];
+    let mut w = Writer::new(&mut buf);
+    let fee_offset = nftoken_mint(&mut w, &Origin::of(tx), taxon, flags, uri, destination)?;
+    finish(tx, &mut w, fee_offset)
+}
+
+/// Emits an NFTokenCreateOffer from the hook account; see
+/// [`nftoken_create_offer`].
+pub fn emit_nftoken_create_offer(
+    tx: &mut HookCtx,
+    nftoken_id: &[u8; 32],
+    amount: &Amount,
+    flags: u32,
+    owner: Option<&[u8; 20]>,
+    destination: Option<&[u8; 20]>,
+) -> Result<[u8; 32], i64> {
+    let mut buf = [0u8; NFTOKEN_OFFER_LEN];
+    let mut w = Writer::new(&mut buf);
+    let fee_offset = nftoken_create_offer(&mut w, &Origin::of(tx), nftoken_id, amount, flags, owner, destination);
+    finish(tx, &mut w, fee_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::IouValue;
+    use crate::{SPARK_CURRENCY, SPARK_ISSUER};
+
+    const ORIGIN: Origin = Origin { account: [0xAA; 20], ledger_seq: 1_000 };
+    const BUYER: [u8; 20] = [0xBB; 20];
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    /// Builds a transaction, checking the fee offset points at the fee.
+    fn built(f: impl FnOnce(&mut Writer) -> usize) -> Vec<u8> {
+        let mut buf = [0u8; 512];
+        let mut w = Writer::new(&mut buf);
+        let fee_offset = f(&mut w);
+        let bytes = w.as_bytes().to_vec();
+        assert_eq!(bytes[fee_offset - 1..fee_offset + 8], hex("684000000000000000"));
+        bytes
+    }
+
+    // Shared by every builder: TransactionType, then Flags, Sequence 0 and
+    // the ledger window 1001..1005
+    const WINDOW: &str = "2400000000201A000003E9201B000003ED";
+    const FEE_AND_KEY: &str = "6840000000000000007300";
+    const ACCOUNT: &str = "8114AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+    const DESTINATION: &str = "8314BBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB";
+
+    #[test]
+    fn builds_payments() {
+        let half_spark = Amount::Iou { value: IouValue::new(5, -1).unwrap(), currency: SPARK_CURRENCY, issuer: SPARK_ISSUER };
+        let bytes = built(|w| payment(w, &ORIGIN, &BUYER, &half_spark));
+        let amount = "61D451C37937E08000535041524B000000000000000000000000000000B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+        let expected = ["1200002200000000", WINDOW, amount, FEE_AND_KEY, ACCOUNT, DESTINATION].concat();
+        assert_eq!(bytes, hex(&expected));
+    }
+
+    #[test]
+    fn builds_nftoken_mints() {
+        let bytes = built(|w| nftoken_mint(w, &ORIGIN, 7, tfTransferable, b"ipfs://egg", &BUYER).unwrap());
+        // NFTokenTaxon 7, a zero Amount, then after the fee the URI
+        let (taxon, amount, uri) = ("202A00000007", "614000000000000000", "750A697066733A2F2F656767");
+        let expected = ["1200192200000008", WINDOW, taxon, amount, FEE_AND_KEY, uri, ACCOUNT, DESTINATION].concat();
+        assert_eq!(bytes, hex(&expected));
+
+        let mut buf = [0u8; 512];
+        let too_long = [b'a'; MAX_URI_LEN + 1];
+        assert_eq!(nftoken_mint(&mut Writer::new(&mut buf), &ORIGIN, 7, 0, &too_long, &BUYER), Err(TOO_BIG));
+    }
+
+    #[test]
+    fn builds_nftoken_offers() {
+        let id = [0x0C; 32];
+        let sell = built(|w| nftoken_create_offer(w, &ORIGIN, &id, &Amount::Xrp(1_000_000), tfSellNFToken, None, Some(&BUYER)));
+        let (nftoken_id, amount) = (["5A", &"0C".repeat(32)].concat(), "6140000000000F4240");
+        let expected = ["12001B2200000001", WINDOW, &nftoken_id, amount, FEE_AND_KEY, ACCOUNT, DESTINATION].concat();
+        assert_eq!(sell, hex(&expected));
+
+        let buy = built(|w| nftoken_create_offer(w, &ORIGIN, &id, &Amount::Xrp(1_000_000), 0, Some(&BUYER), None));
+        let owner = "8214BBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB";
+        let expected = ["12001B2200000000", WINDOW, &nftoken_id, amount, FEE_AND_KEY, ACCOUNT, owner].concat();
+        assert_eq!(buy, hex(&expected));
+    }
+}
@@ -0,0 +1,70 @@
+//! Accounts exempt from the Spark burn, kept in the burn hook's state.
+//!
+//! Payments from or to an exempt account (the treasury, the egg shop, AMM
+//! accounts) pass through [`crate::burn_one_percent`] untouched. The admin
+//! edits the list with the `ADD_EXEMPTION` and `REMOVE_EXEMPTION` commands
+//! of [`crate::admin`].
+
+use crate::api::*;
+
+/// State key of `account`'s exemption: "EXM" followed by the account.
+pub fn exemption_key(account: &[u8; 20]) -> [u8; 23] {
+    let mut key = [0u8; 23];
+    key[..3].copy_from_slice(b"EXM");
+    key[3..].copy_from_slice(account);
+    key
+}
+
+pub fn is_exempt(tx: &HookCtx, account: &[u8; 20]) -> bool {
+    let mut flag = [0u8; 1];
+    tx.state(&exemption_key(account), &mut flag) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::{ADD_EXEMPTION, ADMIN_MEMO_TYPE, REMOVE_EXEMPTION};
+    use crate::amount::{Amount, IouValue};
+    use crate::burn_one_percent;
+    use crate::error::HookError;
+    use crate::mock::{state_key, Exit, Mock, Outcome, Tx};
+    use crate::{SPARK_CURRENCY, SPARK_ISSUER};
+
+    const HOOK_ACCOUNT: [u8; 20] = [0xAA; 20];
+    const SHOP: [u8; 20] = [0x05; 20];
+    const ALICE: [u8; 20] = [0x01; 20];
+
+    fn pay(mock: &Mock, from: [u8; 20], to: [u8; 20]) -> Outcome {
+        let spark = Amount::Iou { value: IouValue::new(50, 0).unwrap(), currency: SPARK_CURRENCY, issuer: SPARK_ISSUER };
+        mock.run(&Tx::payment(from, to, &spark), burn_one_percent)
+    }
+
+    fn command(op: u8, account: [u8; 20]) -> Tx {
+        let mut data = vec![op];
+        data.extend(account);
+        Tx::new(ttINVOKE).field(sfAccount, &HOOK_ACCOUNT).memo(ADMIN_MEMO_TYPE, &data)
+    }
+
+    #[test]
+    fn admin_adds_and_removes_exemptions() {
+        let outcome = Mock::new(HOOK_ACCOUNT).run(&command(ADD_EXEMPTION, SHOP), burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert_eq!(outcome.state.get(&state_key(&exemption_key(&SHOP))), Some(&vec![1]));
+
+        let exempt = Mock::new(HOOK_ACCOUNT).state(&exemption_key(&SHOP), &[1]);
+        let outcome = exempt.run(&command(REMOVE_EXEMPTION, SHOP), burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert!(outcome.state.is_empty());
+    }
+
+    #[test]
+    fn exempt_transfers_pass_untouched() {
+        let exempt = Mock::new(HOOK_ACCOUNT).state(&exemption_key(&SHOP), &[1]);
+        for (from, to) in [(SHOP, HOOK_ACCOUNT), (HOOK_ACCOUNT, SHOP)] {
+            let outcome = pay(&exempt, from, to);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Accept, HookError::ExemptAccount.code()));
+            assert!(outcome.emitted.is_empty() && outcome.state_writes.is_empty());
+        }
+        assert_eq!(pay(&exempt, ALICE, HOOK_ACCOUNT).emitted.len(), 1);
+    }
+}
@@ -0,0 +1,228 @@
+//! Hatch hook, installed next to the egg shop on the account that mints eggs.
+//!
+//! The owner of an egg hatches it with an Invoke carrying a `pet-hatch` memo:
+//! the egg's NFTokenID followed by the 32-character hex DNA the oracle's
+//! `/hatch` generated. The hook checks the egg was minted by the hook account
+//! and is held by the sender, then records `NFTokenID -> sha512half(DNA)` in
+//! hook state. The record is written once; stats derived from the DNA (see
+//! `NewPetFromDNA` in the backend) can be checked against it at any time.
+
+use crate::admin::{self, WhenPaused};
+use crate::api::*;
+use crate::bytes::{array20, array32, eq20, eq32, ge32};
+use crate::dna::{Stats, DNA_LEN};
+use crate::error::HookError;
+use crate::memo::{self, MAX_MEMOS_LEN};
+use crate::triggers::{self, hook_on};
+
+/// MemoType of a hatch request.
+pub const HATCH_MEMO_TYPE: &[u8] = b"pet-hatch";
+
+/// Transaction types the hatch hook fires on; deployment derives `HookOn` from these.
+pub const TRIGGERS: &[i64] = &[ttINVOKE];
+
+const HOOK_ON: [u8; 32] = hook_on(TRIGGERS);
+
+/// Paused, the hook rejects hatches rather than let them through unhandled.
+const WHEN_PAUSED: WhenPaused = WhenPaused::Rollback;
+
+/// Most NFToken pages walked when looking for the egg; owners with more
+/// than `MAX_PAGES * 32` tokens may not be able to hatch their lowest eggs.
+const MAX_PAGES: u32 = 16;
+
+/// Tokens an NFToken page holds at most.
+const PAGE_SIZE: u32 = 32;
+
+pub fn hatch(tx: &mut HookCtx) -> i32 {
+    if !triggers::declared(tx, &HOOK_ON) {
+        return 0;
+    }
+    admin::commands(tx);
+    admin::check_paused(tx, WHEN_PAUSED);
+    let mut owner = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut owner) != 20 || eq20(&owner, &tx.hook_account()) {
+        return 0;
+    }
+
+    let mut memos = [0u8; MAX_MEMOS_LEN];
+    let len = tx.otxn_field(sfMemos, &mut memos).max(0) as usize;
+    let Some(request) = memo::find(&memos[..len], HATCH_MEMO_TYPE) else {
+        HookError::MissingHatchMemo.rollback();
+    };
+    if request.data.len() != 32 + DNA_LEN || Stats::from_dna(&request.data[32..]).is_none() {
+        HookError::MalformedHatch.rollback();
+    }
+    let egg = array32(request.data);
+    let dna = &request.data[32..];
+
+    // NFTokenID: flags (2), transfer fee (2), issuer (20), taxon, sequence
+    if !eq20(&array20(&egg[4..]), &tx.hook_account()) {
+        HookError::ForeignEgg.rollback();
+    }
+    let mut existing = [0u8; 32];
+    if tx.state(&egg, &mut existing) != DOESNT_EXIST {
+        HookError::AlreadyHatched.rollback();
+    }
+    if !owns_nftoken(tx, &owner, &egg) {
+        HookError::NotEggOwner.rollback();
+    }
+    if tx.state_set(&egg, &tx.util_sha512h(dna)) < 0 {
+        HookError::RecordDnaFailed.rollback();
+    }
+    ACCEPT("Egg hatched", 0);
+}
+
+/// Keylet of the NFToken page with `key`.
+pub fn nftoken_page_keylet(key: &[u8; 32]) -> [u8; 34] {
+    let mut keylet = [0u8; 34];
+    keylet[..2].copy_from_slice(&ltNFTOKEN_PAGE.to_be_bytes());
+    keylet[2..].copy_from_slice(key);
+    keylet
+}
+
+/// Whether `owner` holds `token`.
+///
+/// An owner's pages are keyed by the account followed by the low 96 bits of
+/// the highest token a page may hold, and the ledger keeps each token on the
+/// lowest page whose key is not below `owner || low 96 bits of token`. So
+/// walk down from the last page (all ones) while the page below it still
+/// qualifies, then search the page we stop on.
+pub fn owns_nftoken(tx: &HookCtx, owner: &[u8; 20], token: &[u8; 32]) -> bool {
+    let mut wanted = [0u8; 32];
+    wanted[..20].copy_from_slice(owner);
+    wanted[20..].copy_from_slice(&token[20..]);
+    let mut key = [0xFF; 32];
+    key[..20].copy_from_slice(owner);
+
+    let mut pages = 0;
+    loop {
+        guard(line!(), MAX_PAGES + 1);
+        if pages == MAX_PAGES {
+            return false;
+        }
+        pages += 1;
+        let page = tx.slot_set(&nftoken_page_keylet(&key));
+        if page < 0 {
+            return false;
+        }
+        let previous = tx.slot_subfield(page, sfPreviousPageMin);
+        let mut previous_key = [0u8; 32];
+        if previous >= 0 && tx.slot(previous, &mut previous_key) == 32 && ge32(&previous_key, &wanted) {
+            key = previous_key;
+            continue;
+        }
+        return page_holds(tx, page, token);
+    }
+}
+
+fn page_holds(tx: &HookCtx, page: i64, token: &[u8; 32]) -> bool {
+    let tokens = tx.slot_subfield(page, sfNFTokens);
+    let count = tx.slot_count(tokens).clamp(0, PAGE_SIZE as i64) as u32;
+    let mut index = 0;
+    loop {
+        guard(line!(), PAGE_SIZE + 1);
+        if index == count {
+            return false;
+        }
+        let entry = tx.slot_subarray(tokens, index);
+        let id = tx.slot_subfield(entry, sfNFTokenID);
+        let mut found = [0u8; 32];
+        if tx.slot(id, &mut found) == 32 && eq32(&found, token) {
+            return true;
+        }
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{nftoken_page, state_key, Exit, Mock, Tx};
+    use sha2::{Digest, Sha512};
+
+    const SHOP: [u8; 20] = [0xE6; 20];
+    const OWNER: [u8; 20] = [0x01; 20];
+    const DNA: &[u8; 32] = b"0123456789abcdef0123456789ABCDEF";
+
+    /// NFTokenID of an egg issued by `issuer`, with `low` as its last byte.
+    fn egg_id(issuer: [u8; 20], low: u8) -> [u8; 32] {
+        let mut id = [0u8; 32];
+        id[..4].copy_from_slice(&[0, 8, 0, 0]);
+        id[4..24].copy_from_slice(&issuer);
+        id[31] = low;
+        id
+    }
+
+    /// Key of `OWNER`'s page that ends at `token`, or of the last page.
+    fn page_key(token: Option<[u8; 32]>) -> [u8; 32] {
+        let mut key = token.unwrap_or([0xFF; 32]);
+        key[..20].copy_from_slice(&OWNER);
+        key
+    }
+
+    fn hatch_tx(egg: [u8; 32], dna: &[u8]) -> Tx {
+        let mut data = egg.to_vec();
+        data.extend(dna);
+        Tx::new(ttINVOKE).field(sfAccount, &OWNER).memo(HATCH_MEMO_TYPE, &data)
+    }
+
+    fn owning(tokens: &[[u8; 32]]) -> Mock {
+        Mock::new(SHOP).object(nftoken_page_keylet(&page_key(None)), &nftoken_page(None, tokens))
+    }
+
+    #[test]
+    fn records_dna_hash_for_owned_egg() {
+        let egg = egg_id(SHOP, 7);
+        let outcome = owning(&[egg_id(SHOP, 3), egg]).run(&hatch_tx(egg, DNA), hatch);
+        assert_eq!(outcome.exit, Exit::Accept, "{}", outcome.message);
+        assert_eq!(outcome.state.get(&egg).map(Vec::as_slice), Some(&Sha512::digest(DNA)[..32]));
+    }
+
+    #[test]
+    fn finds_egg_on_a_lower_page() {
+        let egg = egg_id(SHOP, 0x20);
+        let (middle, low) = (page_key(Some(egg_id(SHOP, 0x40))), page_key(Some(egg_id(SHOP, 0x10))));
+        let mock = Mock::new(SHOP)
+            .object(nftoken_page_keylet(&page_key(None)), &nftoken_page(Some(middle), &[]))
+            .object(nftoken_page_keylet(&middle), &nftoken_page(Some(low), &[egg]))
+            .object(nftoken_page_keylet(&low), &nftoken_page(None, &[]));
+        assert_eq!(mock.run(&hatch_tx(egg, DNA), hatch).exit, Exit::Accept);
+    }
+
+    #[test]
+    fn rolls_back_eggs_the_sender_does_not_own() {
+        let egg = egg_id(SHOP, 7);
+        let outcome = owning(&[egg_id(SHOP, 8)]).run(&hatch_tx(egg, DNA), hatch);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::NotEggOwner.code()));
+        let outcome = Mock::new(SHOP).run(&hatch_tx(egg, DNA), hatch);
+        assert_eq!(outcome.code, HookError::NotEggOwner.code());
+    }
+
+    #[test]
+    fn rolls_back_foreign_or_hatched_eggs() {
+        let foreign = egg_id([0x02; 20], 7);
+        let outcome = owning(&[foreign]).run(&hatch_tx(foreign, DNA), hatch);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::ForeignEgg.code()));
+
+        let egg = egg_id(SHOP, 7);
+        let outcome = owning(&[egg]).state(&egg, &[0xAB; 32]).run(&hatch_tx(egg, DNA), hatch);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::AlreadyHatched.code()));
+        assert_eq!(outcome.state.get(&state_key(&egg)), Some(&vec![0xAB; 32]));
+    }
+
+    #[test]
+    fn rolls_back_malformed_requests() {
+        let egg = egg_id(SHOP, 7);
+        let mock = owning(&[egg]);
+        assert_eq!(mock.run(&hatch_tx(egg, &DNA[..31]), hatch).code, HookError::MalformedHatch.code());
+        assert_eq!(mock.run(&hatch_tx(egg, b"0123456789abcdef0123456789abcdeg"), hatch).code, HookError::MalformedHatch.code());
+        assert_eq!(mock.run(&Tx::new(ttINVOKE).field(sfAccount, &OWNER), hatch).code, HookError::MissingHatchMemo.code());
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "undeclared transaction type"))]
+    fn ignores_other_transactions() {
+        let outcome = Mock::new(SHOP).run(&Tx::new(ttPAYMENT), hatch);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Accept, 0));
+    }
+}
@@ -0,0 +1,497 @@
+//! Shared hook code: the hook API bindings, amount and transaction
+//! serialization, and the logic of every hook. The crates under `hooks/`
+//! each wrap one hook function in the `hook`/`cbak` exports of their own
+//! wasm.
+#![cfg_attr(not(any(test, feature = "mock")), no_std)]
+
+pub mod admin;
+pub mod amount;
+pub mod api;
+pub mod arena;
+pub mod battle;
+pub mod bytes;
+pub mod dna;
+pub mod egg_shop;
+pub mod error;
+pub mod etxn;
+pub mod exemptions;
+pub mod hatch;
+pub mod memo;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod stobject;
+pub mod treasury;
+pub mod triggers;
+
+use admin::WhenPaused;
+use amount::{Amount, IouValue, Rounding};
+use api::*;
+use bytes::eq20;
+use error::HookError;
+use triggers::hook_on;
+
+// Shared by every hook wasm. Native builds link std, which brings its own.
+#[cfg(all(target_arch = "wasm32", not(any(test, feature = "mock"))))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    HookError::Panic.rollback()
+}
+
+/// Spark currency code: "SPARK" as a 160-bit non-standard currency.
+pub(crate) const SPARK_CURRENCY: [u8; 20] = *b"SPARK\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+
+/// Spark issuer (genesis account rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh on standalone).
+pub(crate) const SPARK_ISSUER: [u8; 20] = [
+    0xB5, 0xF7, 0x62, 0x79, 0x8A, 0x53, 0xD5, 0x43, 0xA0, 0x14,
+    0xCA, 0xF8, 0xB2, 0x97, 0xCF, 0xF8, 0xF2, 0xF9, 0x37, 0xE8,
+];
+
+/// Transaction types the burn hook fires on: payments to burn, and Invokes
+/// carrying [`admin`] commands.
+pub const BURN_TRIGGERS: &[i64] = &[ttPAYMENT, ttINVOKE];
+
+const BURN_HOOK_ON: [u8; 32] = hook_on(BURN_TRIGGERS);
+
+/// Paused, the burn hook lets payments through unburned: Spark stays
+/// transferable while the burn logic is in question.
+const BURN_WHEN_PAUSED: WhenPaused = WhenPaused::PassThrough;
+
+/// A hook built from this crate, named after its wasm (`<name>_hook.wasm`),
+/// and the transaction types it fires on.
+pub struct HookInfo {
+    pub name: &'static str,
+    pub triggers: &'static [i64],
+}
+
+/// Every hook in `hooks/`, for deployment tooling.
+pub const HOOKS: &[HookInfo] = &[
+    HookInfo { name: "burn", triggers: BURN_TRIGGERS },
+    HookInfo { name: "egg_shop", triggers: egg_shop::TRIGGERS },
+    HookInfo { name: "treasury", triggers: treasury::TRIGGERS },
+    HookInfo { name: "hatch", triggers: hatch::TRIGGERS },
+    HookInfo { name: "battle", triggers: battle::TRIGGERS },
+];
+
+/// Burn rate applied when the `BURN_BPS` hook parameter is not set (1%).
+const DEFAULT_BURN_BPS: u64 = 100;
+
+/// Highest burn rate the `BURN_BPS` hook parameter may configure (10%).
+const MAX_BURN_BPS: u64 = 1_000;
+
+/// Burns round down, so a payer never loses more than the configured rate.
+const BURN_ROUNDING: Rounding = Rounding::Down;
+
+/// Burns smaller than this (0.000001 Spark) are skipped rather than emitted.
+const MIN_BURN: IouValue = IouValue::new(1, -6).unwrap();
+
+/// Length of a pending or failed burn's state record, kept under the hash of
+/// the emitted Payment: the serialized amount, then a `BURN_*` status.
+pub const BURN_RECORD_LEN: usize = 49;
+/// Burn status: emitted, callback not yet run.
+pub const BURN_PENDING: u8 = 0;
+/// Burn status: the emitted Payment failed.
+pub const BURN_FAILED: u8 = 1;
+
+/// State key of the count of burns that landed.
+pub const BURNED_KEY: &[u8] = b"BURNED";
+
+/// `BURN_ON` bit: burn payments the hook account sends.
+pub const BURN_ON_SEND: u8 = 1;
+/// `BURN_ON` bit: burn payments the hook account receives.
+pub const BURN_ON_RECEIVE: u8 = 2;
+
+/// Payments burned when the `BURN_ON` hook parameter is not set.
+const DEFAULT_BURN_ON: u8 = BURN_ON_SEND | BURN_ON_RECEIVE;
+
+/// Which way a payment moves relative to the hook account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// The hook account sends it.
+    Outgoing,
+    /// The hook account receives it.
+    Incoming,
+    /// The hook account pays itself, converting another currency to Spark.
+    /// Counts as receiving: the sent side is not Spark.
+    SelfPayment,
+    /// The hook account is neither sender nor destination.
+    Unrelated,
+}
+
+impl Direction {
+    pub fn of(hook_account: &[u8; 20], account: &[u8; 20], destination: &[u8; 20]) -> Direction {
+        match (eq20(account, hook_account), eq20(destination, hook_account)) {
+            (true, true) => Direction::SelfPayment,
+            (true, false) => Direction::Outgoing,
+            (false, true) => Direction::Incoming,
+            (false, false) => Direction::Unrelated,
+        }
+    }
+
+    /// Whether a `BURN_ON` policy burns payments going this way.
+    pub fn burned_under(self, burn_on: u8) -> bool {
+        match self {
+            Direction::Outgoing => burn_on & BURN_ON_SEND != 0,
+            Direction::Incoming | Direction::SelfPayment => burn_on & BURN_ON_RECEIVE != 0,
+            Direction::Unrelated => false,
+        }
+    }
+}
+
+// Burns a share of Spark token transfers to or from the hook account: 1%
+// unless the `BURN_BPS` hook parameter (2-byte big-endian basis points) says
+// otherwise, both ways unless `BURN_ON` (one byte of `BURN_ON_*` bits)
+// picks one. Issuing and redeeming Spark moves no tokens between holders,
+// so payments from or to the issuer are never burned, and neither are
+// payments from or to an exempt account.
+//
+// A hook cannot change the amount of the payment it runs on, so the burn is
+// a separate Payment the hook account emits to the Spark issuer, which
+// destroys the tokens it receives, or to the blackholed account set with
+// `BURN_TO`, which must hold a Spark trust line. It leaves the hook
+// account's balance once it lands, in a later ledger; [`burn_cbak`]
+// records whether it did.
+pub fn burn_one_percent(tx: &mut HookCtx) -> i32 {
+    if !triggers::declared(tx, &BURN_HOOK_ON) {
+        return 0;
+    }
+    admin::commands(tx);
+    admin::check_paused(tx, BURN_WHEN_PAUSED);
+    if tx.otxn_type() != ttPAYMENT {
+        return 0;
+    }
+    // XRP amounts serialize to 8 bytes, IOUs to 48: value, currency, issuer
+    let mut amount = [0u8; 48];
+    if tx.otxn_field(sfAmount, &mut amount) != 48 {
+        HookError::NotSpark.accept();
+    }
+    let Some(Amount::Iou { value, currency, issuer }) = Amount::from_bytes(&amount) else {
+        HookError::NotSpark.accept();
+    };
+    if !eq20(&currency, &SPARK_CURRENCY) || !eq20(&issuer, &SPARK_ISSUER) {
+        HookError::NotSpark.accept();
+    }
+    let Some(bps) = burn_rate_bps(tx) else {
+        HookError::InvalidBurnBps.rollback();
+    };
+    let Some(burn_on) = burn_on(tx) else {
+        HookError::InvalidBurnOn.rollback();
+    };
+    let Some(burn_to) = burn_destination(tx) else {
+        HookError::InvalidBurnTo.rollback();
+    };
+
+    let mut account = [0u8; 20];
+    let mut destination = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut account) != 20 || tx.otxn_field(sfDestination, &mut destination) != 20 {
+        return 0;
+    }
+    if eq20(&account, &SPARK_ISSUER) || eq20(&destination, &SPARK_ISSUER) {
+        HookError::IssuerTransfer.accept();
+    }
+    // Including the burns this hook emits, which would otherwise be burned
+    // in turn
+    if eq20(&destination, &burn_to) {
+        HookError::BurnPayment.accept();
+    }
+    if !Direction::of(&tx.hook_account(), &account, &destination).burned_under(burn_on) {
+        HookError::DirectionNotBurned.accept();
+    }
+    if exemptions::is_exempt(tx, &account) || exemptions::is_exempt(tx, &destination) {
+        HookError::ExemptAccount.accept();
+    }
+    // A partial payment may deliver far less than its Amount, and the hook
+    // cannot see how much until it has run, so a burn of its Amount could
+    // be paid out of the hook account's own Spark. It goes through unburned
+    // rather than blocking cross-currency payments.
+    let mut flags = [0u8; 4];
+    if tx.otxn_field(sfFlags, &mut flags) == 4 && u32::from_be_bytes(flags) & tfPartialPayment != 0 {
+        HookError::PartialBurnPayment.accept();
+    }
+    let burn = value.bps(bps, BURN_ROUNDING);
+    if burn < MIN_BURN {
+        HookError::BelowMinimumBurn.accept();
+    }
+
+    let burn = Amount::Iou { value: burn, currency, issuer };
+    if tx.etxn_reserve(1) < 0 {
+        HookError::ReserveBurnFailed.rollback();
+    }
+    let Ok(hash) = etxn::emit_payment(tx, &burn_to, &burn) else {
+        HookError::EmitBurnFailed.rollback();
+    };
+    // Remember the burn under its hash so the callback can settle it
+    let mut record = [0u8; BURN_RECORD_LEN];
+    burn.serialize((&mut record[..48]).try_into().unwrap());
+    record[48] = BURN_PENDING;
+    if tx.state_set(&hash, &record) < 0 {
+        HookError::RecordBurnFailed.rollback();
+    }
+    ACCEPT("Spark burned", 0);
+}
+
+/// Callback for emitted burns. A burn that landed has its record dropped
+/// and is counted under [`BURNED_KEY`]; one that failed keeps its record,
+/// marked [`BURN_FAILED`], so ops can see what went unburned.
+pub fn burn_cbak(tx: &mut HookCtx, what: u32) -> i32 {
+    let hash = tx.otxn_id();
+    let mut record = [0u8; BURN_RECORD_LEN];
+    if tx.state(&hash, &mut record) != BURN_RECORD_LEN as i64 {
+        return 0;
+    }
+    if what != 0 {
+        record[48] = BURN_FAILED;
+        tx.state_set(&hash, &record);
+        return 0;
+    }
+    tx.state_set(&hash, &[]);
+    let mut count = [0u8; 8];
+    let burned = if tx.state(BURNED_KEY, &mut count) == 8 { u64::from_be_bytes(count) } else { 0 };
+    tx.state_set(BURNED_KEY, &burned.saturating_add(1).to_be_bytes());
+    0
+}
+
+/// Where burns are paid from the `BURN_TO` hook parameter, falling back to
+/// the Spark issuer when it is absent. `None` if it is not an account.
+fn burn_destination(tx: &HookCtx) -> Option<[u8; 20]> {
+    let mut account = [0u8; 20];
+    match admin::param(tx, b"BURN_TO", &mut account) {
+        DOESNT_EXIST => Some(SPARK_ISSUER),
+        20 => Some(account),
+        _ => None,
+    }
+}
+
+/// Burn rate in basis points from the `BURN_BPS` hook parameter, falling back
+/// to the default when it is absent. `None` if it is malformed or too high.
+fn burn_rate_bps(tx: &mut HookCtx) -> Option<u64> {
+    let mut param = [0u8; 2];
+    match admin::param(tx, b"BURN_BPS", &mut param) {
+        DOESNT_EXIST => Some(DEFAULT_BURN_BPS),
+        2 => {
+            let bps = u16::from_be_bytes(param) as u64;
+            (bps <= MAX_BURN_BPS).then_some(bps)
+        }
+        _ => None,
+    }
+}
+
+/// Directions to burn from the `BURN_ON` hook parameter, falling back to
+/// both when it is absent. `None` if it is not one byte of `BURN_ON_*` bits.
+fn burn_on(tx: &mut HookCtx) -> Option<u8> {
+    let mut param = [0u8; 1];
+    match admin::param(tx, b"BURN_ON", &mut param) {
+        DOESNT_EXIST => Some(DEFAULT_BURN_ON),
+        1 if param[0] != 0 && param[0] & !(BURN_ON_SEND | BURN_ON_RECEIVE) == 0 => Some(param[0]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{field_of, state_key, Exit, Mock, Outcome, Tx};
+
+    const HOOK_ACCOUNT: [u8; 20] = [0xAA; 20];
+    const ALICE: [u8; 20] = [0x01; 20];
+    const BOB: [u8; 20] = [0x02; 20];
+
+    fn spark(mantissa: u64, exponent: i32) -> Amount {
+        let value = IouValue::new(mantissa, exponent).unwrap();
+        Amount::Iou { value, currency: SPARK_CURRENCY, issuer: SPARK_ISSUER }
+    }
+
+    fn serialized(amount: &Amount) -> Vec<u8> {
+        let mut buf = [0u8; 48];
+        let len = amount.serialize(&mut buf);
+        buf[..len].to_vec()
+    }
+
+    /// Amounts of the burn payments `outcome` emitted, checking they go to
+    /// `to`.
+    fn burns_to(outcome: &Outcome, to: [u8; 20]) -> Vec<Vec<u8>> {
+        let burns = outcome.emitted.iter().map(|blob| {
+            assert_eq!(field_of(blob, sfTransactionType), Some(vec![0, ttPAYMENT as u8]));
+            assert_eq!(field_of(blob, sfAccount), Some(HOOK_ACCOUNT.to_vec()));
+            assert_eq!(field_of(blob, sfDestination), Some(to.to_vec()));
+            field_of(blob, sfAmount).unwrap()
+        });
+        burns.collect()
+    }
+
+    #[test]
+    fn burns_one_percent_of_spark_payments() {
+        let tx = Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0));
+        let outcome = Mock::new(HOOK_ACCOUNT).run(&tx, burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert_eq!(outcome.message, "Spark burned");
+        assert_eq!(burns_to(&outcome, SPARK_ISSUER), vec![serialized(&spark(5, -1))]);
+    }
+
+    #[test]
+    fn passes_partial_payments_through_without_burning_their_amount() {
+        // Amount claims a million Spark; a partial payment could deliver a
+        // fraction of that, leaving the hook account to pay the burn
+        let partial = tfPartialPayment.to_be_bytes();
+        for (from, to) in [(ALICE, HOOK_ACCOUNT), (HOOK_ACCOUNT, ALICE)] {
+            let tx = Tx::payment(from, to, &spark(1, 6)).field(sfFlags, &partial);
+            let outcome = Mock::new(HOOK_ACCOUNT).run(&tx, burn_one_percent);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Accept, HookError::PartialBurnPayment.code()));
+            assert!(outcome.emitted.is_empty());
+            assert!(outcome.state.is_empty());
+        }
+        // tfNoRippleDirect, which changes nothing about the amount delivered
+        let other_flags = Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)).field(sfFlags, &0x0001_0000u32.to_be_bytes());
+        assert_eq!(burns_to(&Mock::new(HOOK_ACCOUNT).run(&other_flags, burn_one_percent), SPARK_ISSUER).len(), 1);
+    }
+
+    #[test]
+    fn passes_xrp_and_other_currencies_through() {
+        let usd = Amount::Iou {
+            value: IouValue::new(50, 0).unwrap(),
+            currency: *b"\0\0\0\0\0\0\0\0\0\0\0\0USD\0\0\0\0\0",
+            issuer: SPARK_ISSUER,
+        };
+        let fake_spark = Amount::Iou {
+            value: IouValue::new(50, 0).unwrap(),
+            currency: SPARK_CURRENCY,
+            issuer: BOB,
+        };
+        for amount in [Amount::Xrp(1_000_000), usd, fake_spark] {
+            let outcome = Mock::new(HOOK_ACCOUNT).run(&Tx::payment(ALICE, HOOK_ACCOUNT, &amount), burn_one_percent);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Accept, HookError::NotSpark.code()));
+            assert!(outcome.emitted.is_empty());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "undeclared transaction type"))]
+    fn ignores_other_transaction_types() {
+        let tx = Tx::new(3).field(sfAmount, &serialized(&spark(50, 0)));
+        let outcome = Mock::new(HOOK_ACCOUNT).run(&tx, burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert!(outcome.emitted.is_empty());
+    }
+
+    #[test]
+    fn reads_burn_rate_from_hook_parameter() {
+        let mock = Mock::new(HOOK_ACCOUNT).param(b"BURN_BPS", &250u16.to_be_bytes());
+        let outcome = mock.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+        assert_eq!(burns_to(&outcome, SPARK_ISSUER), vec![serialized(&spark(125, -2))]);
+    }
+
+    #[test]
+    fn rolls_back_on_invalid_burn_rate() {
+        for value in [&1_001u16.to_be_bytes()[..], &[1]] {
+            let mock = Mock::new(HOOK_ACCOUNT).param(b"BURN_BPS", value);
+            let outcome = mock.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+            assert_eq!(outcome.exit, Exit::Rollback);
+            assert!(outcome.emitted.is_empty());
+        }
+    }
+
+    #[test]
+    fn skips_burns_below_minimum() {
+        let outcome = Mock::new(HOOK_ACCOUNT).run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(99, -6)), burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        assert!(outcome.emitted.is_empty());
+    }
+
+    fn burned(mock: &Mock, from: [u8; 20], to: [u8; 20]) -> bool {
+        let outcome = mock.run(&Tx::payment(from, to, &spark(50, 0)), burn_one_percent);
+        assert_eq!(outcome.exit, Exit::Accept);
+        !outcome.emitted.is_empty()
+    }
+
+    #[test]
+    fn burns_in_the_directions_burn_on_selects() {
+        let default = Mock::new(HOOK_ACCOUNT);
+        assert!(burned(&default, HOOK_ACCOUNT, ALICE) && burned(&default, ALICE, HOOK_ACCOUNT));
+        for (burn_on, send, receive) in [(BURN_ON_SEND, true, false), (BURN_ON_RECEIVE, false, true), (3, true, true)] {
+            let mock = Mock::new(HOOK_ACCOUNT).param(b"BURN_ON", &[burn_on]);
+            assert_eq!(burned(&mock, HOOK_ACCOUNT, ALICE), send, "BURN_ON {burn_on}");
+            assert_eq!(burned(&mock, ALICE, HOOK_ACCOUNT), receive, "BURN_ON {burn_on}");
+        }
+    }
+
+    #[test]
+    fn ignores_payments_between_other_accounts() {
+        assert!(!burned(&Mock::new(HOOK_ACCOUNT), ALICE, BOB));
+    }
+
+    #[test]
+    fn self_payments_count_as_receiving() {
+        assert_eq!(Direction::of(&HOOK_ACCOUNT, &HOOK_ACCOUNT, &HOOK_ACCOUNT), Direction::SelfPayment);
+        assert!(burned(&Mock::new(HOOK_ACCOUNT), HOOK_ACCOUNT, HOOK_ACCOUNT));
+        let receive = Mock::new(HOOK_ACCOUNT).param(b"BURN_ON", &[BURN_ON_RECEIVE]);
+        assert!(burned(&receive, HOOK_ACCOUNT, HOOK_ACCOUNT));
+        let send = Mock::new(HOOK_ACCOUNT).param(b"BURN_ON", &[BURN_ON_SEND]);
+        assert!(!burned(&send, HOOK_ACCOUNT, HOOK_ACCOUNT));
+    }
+
+    #[test]
+    fn never_burns_issuance_or_redemption() {
+        // Installed on a holder
+        let holder = Mock::new(HOOK_ACCOUNT);
+        assert!(!burned(&holder, SPARK_ISSUER, HOOK_ACCOUNT));
+        assert!(!burned(&holder, HOOK_ACCOUNT, SPARK_ISSUER));
+        // Installed on the issuer itself
+        let issuer = Mock::new(SPARK_ISSUER);
+        assert!(!burned(&issuer, SPARK_ISSUER, ALICE));
+        assert!(!burned(&issuer, ALICE, SPARK_ISSUER));
+    }
+
+    #[test]
+    fn rolls_back_on_invalid_burn_on() {
+        for value in [&[0][..], &[4], &[1, 2]] {
+            let mock = Mock::new(HOOK_ACCOUNT).param(b"BURN_ON", value);
+            let outcome = mock.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+            assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::InvalidBurnOn.code()));
+        }
+    }
+
+    #[test]
+    fn paused_burn_passes_payments_through() {
+        let paused = Mock::new(HOOK_ACCOUNT).state(admin::PAUSED_KEY, &[1]);
+        let outcome = paused.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+        assert_eq!((outcome.exit, outcome.code, outcome.message.as_str()), (Exit::Accept, HookError::Paused.code(), "Hooks are paused"));
+        assert!(outcome.emitted.is_empty());
+    }
+
+    #[test]
+    fn burns_to_a_blackhole_and_never_burns_burns() {
+        const BLACKHOLE: [u8; 20] = [0x0B; 20];
+        let mock = Mock::new(HOOK_ACCOUNT).param(b"BURN_TO", &BLACKHOLE);
+        let outcome = mock.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+        assert_eq!(burns_to(&outcome, BLACKHOLE), vec![serialized(&spark(5, -1))]);
+
+        // The emitted burn runs the hook again on its way out
+        let burn = mock.run(&Tx::payment(HOOK_ACCOUNT, BLACKHOLE, &spark(5, -1)), burn_one_percent);
+        assert_eq!((burn.exit, burn.code), (Exit::Accept, HookError::BurnPayment.code()));
+        assert!(burn.emitted.is_empty());
+
+        let invalid = Mock::new(HOOK_ACCOUNT).param(b"BURN_TO", &[1; 19]);
+        let outcome = invalid.run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::InvalidBurnTo.code()));
+    }
+
+    #[test]
+    fn callback_records_whether_the_burn_landed() {
+        let outcome = Mock::new(HOOK_ACCOUNT).run(&Tx::payment(ALICE, HOOK_ACCOUNT, &spark(50, 0)), burn_one_percent);
+        let (hash, record) = outcome.state.iter().next().unwrap();
+        assert_eq!(record[..48], serialized(&spark(5, -1))[..]);
+        assert_eq!(record[48], BURN_PENDING);
+        let mut after = Mock::new(HOOK_ACCOUNT);
+        for (key, value) in &outcome.state {
+            after = after.state(key, value);
+        }
+        let burn = Tx::new(ttPAYMENT).id(*hash);
+
+        let landed = after.callback(&burn, burn_cbak, 0);
+        assert_eq!(landed.state.get(hash), None);
+        assert_eq!(landed.state.get(&state_key(BURNED_KEY)), Some(&1u64.to_be_bytes().to_vec()));
+
+        let failed = after.callback(&burn, burn_cbak, 1);
+        assert_eq!(failed.state.get(hash).map(|record| record[48]), Some(BURN_FAILED));
+        assert_eq!(failed.state.get(&state_key(BURNED_KEY)), None);
+    }
+}
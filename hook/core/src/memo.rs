@@ -0,0 +1,153 @@
+//! Reading the `Memos` array of the originating transaction.
+//!
+//! `otxn_field(sfMemos)` yields the array body: each element is a `Memo`
+//! object holding optional `MemoType`, `MemoData` and `MemoFormat` blobs,
+//! read with [`Reader`].
+
+use crate::api::{guard, sfMemo, sfMemoData, sfMemoFormat, sfMemoType};
+use crate::stobject::Reader;
+
+/// Buffer size for reading `sfMemos`; larger memo arrays are not inspected.
+pub const MAX_MEMOS_LEN: usize = 1024;
+
+/// Most memos inspected per transaction.
+const MAX_MEMOS: u32 = 8;
+
+/// Most [`find`] calls one hook run makes: the battle hook's admin command
+/// and one per battle step. Guards count across calls, so they scale by it.
+const MAX_FINDS: u32 = 6;
+
+/// Longest MemoType [`find`] can match.
+const MAX_TYPE_LEN: u32 = 32;
+
+/// One decoded memo; absent fields are empty.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Memo<'a> {
+    pub memo_type: &'a [u8],
+    pub data: &'a [u8],
+    pub format: &'a [u8],
+}
+
+/// First memo in `memos` whose `MemoType` is `memo_type`. `None` if there is
+/// none or the array is malformed.
+pub fn find<'a>(memos: &'a [u8], memo_type: &[u8]) -> Option<Memo<'a>> {
+    let mut reader = Reader::new(memos);
+    let mut seen = 0;
+    loop {
+        guard(line!(), MAX_FINDS * (MAX_MEMOS + 1));
+        if seen == MAX_MEMOS {
+            return None;
+        }
+        seen += 1;
+        let memo = reader.next_field().filter(|field| field.code == sfMemo)?;
+        let memo = read_memo(memo.value)?;
+        if type_matches(memo.memo_type, memo_type) {
+            return Some(memo);
+        }
+    }
+}
+
+/// Bytewise comparison with a guard per byte; `==` on slices would call
+/// `memcmp`, which has none.
+#[inline(never)]
+fn type_matches(memo_type: &[u8], wanted: &[u8]) -> bool {
+    if memo_type.len() != wanted.len() || wanted.len() > MAX_TYPE_LEN as usize {
+        return false;
+    }
+    let mut i = 0;
+    loop {
+        // Runs once per byte of every same-length type compared
+        guard(line!(), MAX_FINDS * MAX_MEMOS * (MAX_TYPE_LEN + 1));
+        if i == wanted.len() {
+            return true;
+        }
+        if memo_type[i] != wanted[i] {
+            return false;
+        }
+        i += 1;
+    }
+}
+
+/// Reads the fields of one memo.
+#[inline(never)]
+fn read_memo(fields: &[u8]) -> Option<Memo<'_>> {
+    let mut reader = Reader::new(fields);
+    let mut memo = Memo::default();
+    let mut read = 0;
+    loop {
+        // Runs for up to three fields and the end of every memo
+        guard(line!(), MAX_FINDS * MAX_MEMOS * 4);
+        let Some(field) = reader.next_field() else {
+            return reader.is_done().then_some(memo);
+        };
+        if read == 3 || !set_field(&mut memo, field.code, field.value) {
+            return None;
+        }
+        read += 1;
+    }
+}
+
+/// Stores `blob` in the field of `memo` that `field` names. Kept out of
+/// line so `memo` stays in memory instead of in locals that the compiler
+/// would shuffle ahead of the guard in [`read_memo`]'s loop.
+#[inline(never)]
+fn set_field<'a>(memo: &mut Memo<'a>, field: u32, blob: &'a [u8]) -> bool {
+    if field == sfMemoType {
+        memo.memo_type = blob;
+    } else if field == sfMemoData {
+        memo.data = blob;
+    } else if field == sfMemoFormat {
+        memo.format = blob;
+    } else {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stobject::Writer;
+
+    /// Memos as `otxn_field(sfMemos)` returns them.
+    fn memos(memos: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut buf = [0u8; 256];
+        let mut w = Writer::new(&mut buf);
+        for (memo_type, data) in memos {
+            w.begin(sfMemo);
+            w.vl(sfMemoType, memo_type);
+            w.vl(sfMemoData, data);
+            w.end_object();
+        }
+        w.end_array();
+        w.as_bytes().to_vec()
+    }
+
+    #[test]
+    fn finds_memo_by_type() {
+        let memos = memos(&[(b"note", b"hello"), (b"claim", b"\x01\x02")]);
+        let found = find(&memos, b"claim").unwrap();
+        assert_eq!((found.memo_type, found.data, found.format), (&b"claim"[..], &b"\x01\x02"[..], &b""[..]));
+        assert_eq!(find(&memos, b"missing"), None);
+    }
+
+    #[test]
+    fn rejects_truncated_memos() {
+        let mut memos = memos(&[(b"claim", b"data")]);
+        memos.truncate(memos.len() - 3);
+        assert_eq!(find(&memos, b"claim"), None);
+        assert_eq!(find(&[], b"claim"), None);
+    }
+
+    #[test]
+    fn rejects_fields_memos_cannot_hold() {
+        let mut buf = [0u8; 64];
+        let mut w = Writer::new(&mut buf);
+        w.begin(sfMemo);
+        w.vl(sfMemoType, b"claim");
+        w.u32(crate::api::sfFlags, 0);
+        w.end_object();
+        w.end_array();
+        assert_eq!(find(w.as_bytes(), b"claim"), None);
+    }
+}
@@ -0,0 +1,612 @@
+//! Native stand-in for the hook host API, used by `cargo test` and by
+//! downstream crates through the `mock` feature.
+//!
+//! [`Mock`] holds what the ledger would provide (hook account, parameters,
+//! state, ledger objects); [`Mock::run`] executes a hook against a fake
+//! originating [`Tx`] and reports the [`Outcome`]. `ACCEPT` and `ROLLBACK`
+//! end the run by unwinding back into `run`, just as they end execution
+//! on-ledger.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::string::String;
+use std::vec::Vec;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha512};
+
+use crate::amount::Amount;
+use crate::api::{self, HookCtx, DOESNT_EXIST, GUARD_VIOLATION, TOO_BIG, TOO_SMALL};
+use crate::api::{sfAccount, sfAmount, sfDestination, sfMemo, sfMemoData, sfMemoType, sfMemos, ttPAYMENT};
+use crate::api::{sfEmitBurden, sfEmitCallback, sfEmitDetails, sfEmitGeneration};
+use crate::api::{sfEmitHookHash, sfEmitNonce, sfEmitParentTxnID, sfLedgerEntryType};
+use crate::api::{ALREADY_SET, PREREQUISITE_NOT_MET, TOO_MANY_EMITTED_TXN};
+use crate::api::{sfNFToken, sfNFTokenID, sfNFTokens, sfPreviousPageMin, ltNFTOKEN_PAGE};
+use crate::api::{INVALID_ARGUMENT, NOT_AN_ARRAY, NOT_AN_OBJECT, NO_FREE_SLOTS};
+use crate::stobject::{Reader, TypeCode, Writer};
+
+/// Flat fee `etxn_fee_base` charges for any emitted transaction, in drops.
+pub const EMIT_FEE: i64 = 10;
+
+/// Slots a hook may hold at once.
+const MAX_SLOTS: usize = 255;
+
+/// Fake originating transaction: a type, a hash, serialized fields and
+/// `HookParameters`.
+#[derive(Clone, Debug)]
+pub struct Tx {
+    tt: i64,
+    id: [u8; 32],
+    fields: BTreeMap<u32, Vec<u8>>,
+    params: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Tx {
+    pub fn new(tt: i64) -> Tx {
+        Tx { tt, id: [0; 32], fields: BTreeMap::new(), params: BTreeMap::new() }
+    }
+
+    /// Payment of `amount` from `account` to `destination`.
+    pub fn payment(account: [u8; 20], destination: [u8; 20], amount: &Amount) -> Tx {
+        let mut buf = [0u8; 48];
+        let len = amount.serialize(&mut buf);
+        Tx::new(ttPAYMENT)
+            .field(sfAccount, &account)
+            .field(sfDestination, &destination)
+            .field(sfAmount, &buf[..len])
+    }
+
+    pub fn id(mut self, id: [u8; 32]) -> Tx {
+        self.id = id;
+        self
+    }
+
+    /// Sets `field` to `value`, already serialized the way `otxn_field`
+    /// returns it.
+    pub fn field(mut self, field: u32, value: &[u8]) -> Tx {
+        self.fields.insert(field, value.to_vec());
+        self
+    }
+
+    /// Sets the transaction's `HookParameters` entry `name`, read with
+    /// `otxn_param`.
+    pub fn param(mut self, name: &[u8], value: &[u8]) -> Tx {
+        self.params.insert(name.to_vec(), value.to_vec());
+        self
+    }
+
+    /// Appends a memo with `memo_type` and `data` to `Memos`.
+    pub fn memo(mut self, memo_type: &[u8], data: &[u8]) -> Tx {
+        let memos = self.fields.entry(sfMemos).or_default();
+        memos.pop(); // array end marker
+        let mut buf = std::vec![0u8; memo_type.len() + data.len() + 16];
+        let mut w = Writer::new(&mut buf);
+        w.begin(sfMemo);
+        w.vl(sfMemoType, memo_type);
+        w.vl(sfMemoData, data);
+        w.end_object();
+        w.end_array();
+        memos.extend_from_slice(w.as_bytes());
+        self
+    }
+}
+
+/// How the hook ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Accept,
+    Rollback,
+}
+
+/// Everything a hook run produced.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub exit: Exit,
+    pub code: i64,
+    pub message: String,
+    /// Hook state after the run; unchanged if the hook rolled back.
+    pub state: BTreeMap<[u8; 32], Vec<u8>>,
+    /// Every `state_set` call, in order.
+    pub state_writes: Vec<([u8; 32], Vec<u8>)>,
+    /// Serialized transactions passed to `emit`, in order.
+    pub emitted: Vec<Vec<u8>>,
+    pub traces: Vec<(String, Vec<u8>)>,
+}
+
+/// Ledger context a hook runs in.
+#[derive(Clone, Debug, Default)]
+pub struct Mock {
+    hook_account: [u8; 20],
+    params: BTreeMap<Vec<u8>, Vec<u8>>,
+    state: BTreeMap<[u8; 32], Vec<u8>>,
+    objects: BTreeMap<[u8; 34], Vec<u8>>,
+    ledger_seq: u32,
+}
+
+impl Mock {
+    pub fn new(hook_account: [u8; 20]) -> Mock {
+        Mock { hook_account, ledger_seq: 1_000, ..Mock::default() }
+    }
+
+    /// Sets the sequence of the ledger the hook runs in.
+    pub fn ledger_seq(mut self, seq: u32) -> Mock {
+        self.ledger_seq = seq;
+        self
+    }
+
+    /// Sets hook parameter `name`, as installed by SetHook.
+    pub fn param(mut self, name: &[u8], value: &[u8]) -> Mock {
+        self.params.insert(name.to_vec(), value.to_vec());
+        self
+    }
+
+    /// Seeds hook state under `key`.
+    pub fn state(mut self, key: &[u8], value: &[u8]) -> Mock {
+        self.state.insert(state_key(key), value.to_vec());
+        self
+    }
+
+    /// Adds the ledger object at `keylet` (entry type then key), given as
+    /// its serialized fields.
+    pub fn object(mut self, keylet: [u8; 34], fields: &[u8]) -> Mock {
+        self.objects.insert(keylet, fields.to_vec());
+        self
+    }
+
+    /// Runs `hook` on `tx` through the same entry point the wasm export uses.
+    pub fn run(&self, tx: &Tx, hook: fn(&mut HookCtx) -> i32) -> Outcome {
+        self.execute(tx, || api::enter(hook))
+    }
+
+    /// Runs `cbak` for the emitted transaction `tx`; `what` is 0 if it was
+    /// applied and 1 if it failed.
+    pub fn callback(&self, tx: &Tx, cbak: fn(&mut HookCtx, u32) -> i32, what: u32) -> Outcome {
+        self.execute(tx, || api::enter_cbak(cbak, what))
+    }
+
+    fn execute(&self, tx: &Tx, entry: impl FnOnce()) -> Outcome {
+        SESSION.with(|current| *current.borrow_mut() = Some(Session::new(self, tx)));
+        let result = panic::catch_unwind(AssertUnwindSafe(entry));
+        let session = SESSION.with(|current| current.borrow_mut().take()).unwrap();
+        let halt = match result {
+            Ok(()) => unreachable!("hook entry returned without accept or rollback"),
+            Err(payload) => match payload.downcast::<Halt>() {
+                Ok(halt) => *halt,
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        };
+        session.finish(halt.exit, &halt.message, halt.code)
+    }
+}
+
+/// Pads a state key to 32 bytes the way the ledger does.
+pub fn state_key(key: &[u8]) -> [u8; 32] {
+    assert!(key.len() <= 32, "state keys are at most 32 bytes");
+    let mut padded = [0u8; 32];
+    padded[32 - key.len()..].copy_from_slice(key);
+    padded
+}
+
+/// One hook run in progress: the ledger it sees and everything it has done
+/// so far. Its methods are the host API; [`Mock::run`] reaches them through
+/// [`api`], and the `hook-sim` crate from the imports of compiled wasm.
+/// Pointers become slices, and functions that end the run are left to the
+/// caller: [`Session::guard`] only counts, and [`Session::finish`] takes
+/// the result of `accept` or `rollback`.
+pub struct Session {
+    mock: Mock,
+    tx: Tx,
+    state: BTreeMap<[u8; 32], Vec<u8>>,
+    state_writes: Vec<([u8; 32], Vec<u8>)>,
+    reserved: Option<u32>,
+    emitted: Vec<Vec<u8>>,
+    traces: Vec<(String, Vec<u8>)>,
+    guards: BTreeMap<u32, u32>,
+    slots: Vec<Slot>,
+}
+
+/// A slotted object, array or field value.
+#[derive(Clone)]
+struct Slot {
+    type_code: TypeCode,
+    bytes: Vec<u8>,
+}
+
+/// Unwind payload carrying the result of `accept` or `rollback`.
+struct Halt {
+    exit: Exit,
+    code: i64,
+    message: Vec<u8>,
+}
+
+std::thread_local! {
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+fn with_session<R>(f: impl FnOnce(&mut Session) -> R) -> R {
+    SESSION.with(|current| {
+        let mut current = current.borrow_mut();
+        f(current.as_mut().expect("hook API called outside Mock::run"))
+    })
+}
+
+fn halt(exit: Exit, message: &[u8], code: i64) -> ! {
+    panic::resume_unwind(std::boxed::Box::new(Halt { exit, code, message: message.to_vec() }))
+}
+
+fn write(buf: &mut [u8], data: &[u8]) -> i64 {
+    if buf.len() < data.len() {
+        return TOO_SMALL;
+    }
+    buf[..data.len()].copy_from_slice(data);
+    data.len() as i64
+}
+
+/// Serialized fields of an NFToken page holding `tokens`, linked to the
+/// page below it if there is one.
+pub fn nftoken_page(previous: Option<[u8; 32]>, tokens: &[[u8; 32]]) -> Vec<u8> {
+    let mut buf = std::vec![0u8; 64 + tokens.len() * 40];
+    let mut w = Writer::new(&mut buf);
+    w.u16(sfLedgerEntryType, ltNFTOKEN_PAGE);
+    if let Some(previous) = previous {
+        w.hash256(sfPreviousPageMin, &previous);
+    }
+    w.begin(sfNFTokens);
+    for token in tokens {
+        w.begin(sfNFToken);
+        w.hash256(sfNFTokenID, token);
+        w.end_object();
+    }
+    w.end_array();
+    w.as_bytes().to_vec()
+}
+
+/// Value of `field` in a serialized object such as an emitted transaction,
+/// without its length prefix or end marker.
+pub fn field_of(bytes: &[u8], field: u32) -> Option<Vec<u8>> {
+    Reader::new(bytes).find(|found| found.code == field).map(|found| found.value.to_vec())
+}
+
+impl Session {
+    pub fn new(mock: &Mock, tx: &Tx) -> Session {
+        Session {
+            mock: mock.clone(),
+            tx: tx.clone(),
+            state: mock.state.clone(),
+            state_writes: Vec::new(),
+            reserved: None,
+            emitted: Vec::new(),
+            traces: Vec::new(),
+            guards: BTreeMap::new(),
+            slots: Vec::new(),
+        }
+    }
+
+    /// Ends the run the way `accept` or `rollback` with `message` and `code`
+    /// would. A rollback discards the state changes.
+    pub fn finish(self, exit: Exit, message: &[u8], code: i64) -> Outcome {
+        let state = match exit {
+            Exit::Accept => self.state,
+            Exit::Rollback => self.mock.state,
+        };
+        Outcome {
+            exit,
+            code,
+            message: String::from_utf8_lossy(message).into_owned(),
+            state,
+            state_writes: self.state_writes,
+            emitted: self.emitted,
+            traces: self.traces,
+        }
+    }
+
+    /// Counts an iteration of the loop guarded by `id`; `false` once it has
+    /// run more than `maxiter` times, when the host rolls back with
+    /// `GUARD_VIOLATION`.
+    pub fn guard(&mut self, id: u32, maxiter: u32) -> bool {
+        let count = self.guards.entry(id).or_insert(0);
+        *count += 1;
+        *count <= maxiter
+    }
+
+    pub fn otxn_type(&self) -> i64 {
+        self.tx.tt
+    }
+
+    pub fn otxn_field(&self, buf: &mut [u8], field: u32) -> i64 {
+        match self.tx.fields.get(&field) {
+            Some(value) => write(buf, value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn otxn_id(&self, buf: &mut [u8]) -> i64 {
+        write(buf, &self.tx.id)
+    }
+
+    pub fn hook_account(&self, buf: &mut [u8]) -> i64 {
+        write(buf, &self.mock.hook_account)
+    }
+
+    pub fn hook_param(&self, buf: &mut [u8], name: &[u8]) -> i64 {
+        match self.mock.params.get(name) {
+            Some(value) => write(buf, value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn otxn_param(&self, buf: &mut [u8], name: &[u8]) -> i64 {
+        match self.tx.params.get(name) {
+            Some(value) => write(buf, value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn state(&self, buf: &mut [u8], key: &[u8]) -> i64 {
+        if key.len() > 32 {
+            return TOO_BIG;
+        }
+        match self.state.get(&state_key(key)) {
+            Some(value) => write(buf, value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn state_set(&mut self, data: &[u8], key: &[u8]) -> i64 {
+        if key.len() > 32 {
+            return TOO_BIG;
+        }
+        let key = state_key(key);
+        self.state_writes.push((key, data.to_vec()));
+        if data.is_empty() {
+            self.state.remove(&key);
+        } else {
+            self.state.insert(key, data.to_vec());
+        }
+        data.len() as i64
+    }
+
+    pub fn ledger_seq(&self) -> i64 {
+        self.mock.ledger_seq as i64
+    }
+
+    /// Verifies ed25519 signatures; other key types never verify.
+    pub fn util_verify(&self, data: &[u8], signature: &[u8], key: &[u8]) -> i64 {
+        let (Some((&0xED, key)), Ok(signature)) = (key.split_first(), Signature::from_slice(signature)) else {
+            return 0;
+        };
+        let Ok(key) = key.try_into().map(VerifyingKey::from_bytes) else {
+            return 0;
+        };
+        key.is_ok_and(|key| key.verify_strict(data, &signature).is_ok()) as i64
+    }
+
+    pub fn util_sha512h(&self, hash: &mut [u8], data: &[u8]) -> i64 {
+        write(hash, &Sha512::digest(data)[..32])
+    }
+
+    /// Only allocates fresh slots; `slot_no` must be 0.
+    pub fn slot_set(&mut self, keylet: &[u8], slot_no: u32) -> i64 {
+        if keylet.len() != 34 || slot_no != 0 {
+            return INVALID_ARGUMENT;
+        }
+        match self.mock.objects.get(keylet).cloned() {
+            Some(fields) => self.add_slot(TypeCode::STObject, &fields),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn slot_subfield(&mut self, parent: u32, field: u32, slot_no: u32) -> i64 {
+        let Some(parent) = self.get_slot(parent) else {
+            return DOESNT_EXIST;
+        };
+        if parent.type_code != TypeCode::STObject || slot_no != 0 {
+            return NOT_AN_OBJECT;
+        }
+        match Reader::new(&parent.bytes).find(|found| found.code == field) {
+            Some(found) => self.add_slot(found.type_code, found.value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn slot_subarray(&mut self, parent: u32, index: u32, slot_no: u32) -> i64 {
+        let Some(parent) = self.get_slot(parent) else {
+            return DOESNT_EXIST;
+        };
+        if parent.type_code != TypeCode::STArray || slot_no != 0 {
+            return NOT_AN_ARRAY;
+        }
+        match Reader::new(&parent.bytes).nth(index as usize) {
+            Some(entry) => self.add_slot(entry.type_code, entry.value),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn slot_count(&self, slot_no: u32) -> i64 {
+        match self.get_slot(slot_no) {
+            Some(slot) if slot.type_code == TypeCode::STArray => Reader::new(&slot.bytes).count() as i64,
+            Some(_) => NOT_AN_ARRAY,
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn slot(&self, buf: &mut [u8], slot_no: u32) -> i64 {
+        match self.get_slot(slot_no) {
+            Some(slot) => write(buf, &slot.bytes),
+            None => DOESNT_EXIST,
+        }
+    }
+
+    pub fn etxn_reserve(&mut self, count: u32) -> i64 {
+        if self.reserved.is_some() {
+            return ALREADY_SET;
+        }
+        self.reserved = Some(count);
+        count as i64
+    }
+
+    /// Writes `EmitDetails` with a callback to the hook account, the
+    /// originating transaction as parent and a per-emission nonce.
+    pub fn etxn_details(&self, buf: &mut [u8]) -> i64 {
+        if self.reserved.is_none() {
+            return PREREQUISITE_NOT_MET;
+        }
+        let mut details = [0u8; crate::etxn::EMIT_DETAILS_LEN];
+        let mut w = Writer::new(&mut details);
+        w.begin(sfEmitDetails);
+        w.u32(sfEmitGeneration, 1);
+        w.u64(sfEmitBurden, 1);
+        w.hash256(sfEmitParentTxnID, &self.tx.id);
+        w.hash256(sfEmitNonce, &[self.emitted.len() as u8 + 1; 32]);
+        w.hash256(sfEmitHookHash, &[0; 32]);
+        w.account(sfEmitCallback, &self.mock.hook_account);
+        w.end_object();
+        write(buf, w.as_bytes())
+    }
+
+    pub fn etxn_fee_base(&self, _blob: &[u8]) -> i64 {
+        EMIT_FEE
+    }
+
+    pub fn emit(&mut self, hash: &mut [u8], blob: &[u8]) -> i64 {
+        let Some(reserved) = self.reserved else {
+            return PREREQUISITE_NOT_MET;
+        };
+        if self.emitted.len() as u32 >= reserved {
+            return TOO_MANY_EMITTED_TXN;
+        }
+        self.emitted.push(blob.to_vec());
+        write(hash, &[self.emitted.len() as u8; 32])
+    }
+
+    pub fn trace(&mut self, msg: &[u8], data: &[u8], _as_hex: bool) {
+        self.traces.push((String::from_utf8_lossy(msg).into_owned(), data.to_vec()));
+    }
+
+    fn add_slot(&mut self, type_code: TypeCode, bytes: &[u8]) -> i64 {
+        if self.slots.len() >= MAX_SLOTS {
+            return NO_FREE_SLOTS;
+        }
+        self.slots.push(Slot { type_code, bytes: bytes.to_vec() });
+        self.slots.len() as i64
+    }
+
+    fn get_slot(&self, slot_no: u32) -> Option<Slot> {
+        self.slots.get((slot_no as usize).checked_sub(1)?).cloned()
+    }
+}
+
+/// The native hook API `api` calls: the [`Session`] of the current
+/// [`Mock::run`], with `accept`, `rollback` and guard violations unwinding
+/// back into it.
+pub(crate) mod host {
+    use super::*;
+
+    /// Outside a run there is no budget to count against, so loops in pure
+    /// helpers can be tested directly. Nor is there for the host's own
+    /// walks through serialized fields, made while it holds the session.
+    pub fn guard(id: u32, maxiter: u32) {
+        let exceeded = SESSION.with(|current| {
+            let Ok(mut current) = current.try_borrow_mut() else {
+                return false;
+            };
+            current.as_mut().is_some_and(|session| !session.guard(id, maxiter))
+        });
+        if exceeded {
+            halt(Exit::Rollback, b"guard violation", GUARD_VIOLATION);
+        }
+    }
+
+    pub fn accept(msg: &[u8], code: i64) -> ! {
+        halt(Exit::Accept, msg, code)
+    }
+
+    pub fn rollback(msg: &[u8], code: i64) -> ! {
+        halt(Exit::Rollback, msg, code)
+    }
+
+    pub fn otxn_type() -> i64 {
+        with_session(|session| session.otxn_type())
+    }
+
+    pub fn otxn_field(buf: &mut [u8], field: u32) -> i64 {
+        with_session(|session| session.otxn_field(buf, field))
+    }
+
+    pub fn otxn_id(buf: &mut [u8]) -> i64 {
+        with_session(|session| session.otxn_id(buf))
+    }
+
+    pub fn hook_account(buf: &mut [u8]) -> i64 {
+        with_session(|session| session.hook_account(buf))
+    }
+
+    pub fn hook_param(buf: &mut [u8], name: &[u8]) -> i64 {
+        with_session(|session| session.hook_param(buf, name))
+    }
+
+    pub fn otxn_param(buf: &mut [u8], name: &[u8]) -> i64 {
+        with_session(|session| session.otxn_param(buf, name))
+    }
+
+    pub fn state(buf: &mut [u8], key: &[u8]) -> i64 {
+        with_session(|session| session.state(buf, key))
+    }
+
+    pub fn state_set(data: &[u8], key: &[u8]) -> i64 {
+        with_session(|session| session.state_set(data, key))
+    }
+
+    pub fn ledger_seq() -> i64 {
+        with_session(|session| session.ledger_seq())
+    }
+
+    pub fn util_verify(data: &[u8], signature: &[u8], key: &[u8]) -> i64 {
+        with_session(|session| session.util_verify(data, signature, key))
+    }
+
+    pub fn util_sha512h(hash: &mut [u8], data: &[u8]) -> i64 {
+        with_session(|session| session.util_sha512h(hash, data))
+    }
+
+    pub fn slot_set(keylet: &[u8], slot_no: u32) -> i64 {
+        with_session(|session| session.slot_set(keylet, slot_no))
+    }
+
+    pub fn slot_subfield(parent: u32, field: u32, slot_no: u32) -> i64 {
+        with_session(|session| session.slot_subfield(parent, field, slot_no))
+    }
+
+    pub fn slot_subarray(parent: u32, index: u32, slot_no: u32) -> i64 {
+        with_session(|session| session.slot_subarray(parent, index, slot_no))
+    }
+
+    pub fn slot_count(slot_no: u32) -> i64 {
+        with_session(|session| session.slot_count(slot_no))
+    }
+
+    pub fn slot(buf: &mut [u8], slot_no: u32) -> i64 {
+        with_session(|session| session.slot(buf, slot_no))
+    }
+
+    pub fn etxn_reserve(count: u32) -> i64 {
+        with_session(|session| session.etxn_reserve(count))
+    }
+
+    pub fn etxn_details(buf: &mut [u8]) -> i64 {
+        with_session(|session| session.etxn_details(buf))
+    }
+
+    pub fn etxn_fee_base(blob: &[u8]) -> i64 {
+        with_session(|session| session.etxn_fee_base(blob))
+    }
+
+    pub fn emit(hash: &mut [u8], blob: &[u8]) -> i64 {
+        with_session(|session| session.emit(hash, blob))
+    }
+
+    pub fn trace(msg: &[u8], data: &[u8], as_hex: bool) {
+        with_session(|session| session.trace(msg, data, as_hex));
+    }
+}
@@ -0,0 +1,587 @@
+//! XRPL binary serialization, read and written in place: the STObject
+//! format of transactions and ledger objects, and of the values
+//! `otxn_field` and `slot` return.
+//!
+//! A field is a header naming its type and field code, then its value. The
+//! type fixes the value's length, except that blobs, accounts and
+//! Vector256s carry a length prefix (see [`read_vl`]), amounts are 8 bytes
+//! for XRP and 48 for tokens, and inner objects and arrays run to an end
+//! marker. An object lists its fields in canonical order, by type code and
+//! then field code; [`Writer`] leaves that order to its callers.
+//!
+//! [`Reader`] walks an object or array a field at a time without copying.
+//! Stepping over an inner object or array is a guarded loop, so it can run
+//! in hooks, within a budget of [`MAX_FIELDS`] for the whole run.
+
+use crate::amount::Amount;
+use crate::api::guard;
+
+/// Most fields the reader steps over inside inner objects and arrays in
+/// one hook run, across all calls: enough for the two reads of `Memos` a
+/// hook makes (an admin command, then its own request), each over up to 8
+/// memos of three fields and an end marker.
+pub const MAX_FIELDS: u32 = 64;
+
+/// Ends an inner object.
+pub const OBJECT_END: u8 = 0xE1;
+/// Ends an array.
+pub const ARRAY_END: u8 = 0xF1;
+
+/// Serialized type of a field, the upper half of its field code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TypeCode {
+    UInt16 = 1,
+    UInt32 = 2,
+    UInt64 = 3,
+    Hash128 = 4,
+    Hash256 = 5,
+    Amount = 6,
+    Blob = 7,
+    AccountID = 8,
+    STObject = 14,
+    STArray = 15,
+    UInt8 = 16,
+    Hash160 = 17,
+    PathSet = 18,
+    Vector256 = 19,
+}
+
+impl TypeCode {
+    /// Type of the field with code `field` (type << 16 | field).
+    pub const fn of(field: u32) -> Option<TypeCode> {
+        TypeCode::from_u8((field >> 16) as u8)
+    }
+
+    const fn from_u8(code: u8) -> Option<TypeCode> {
+        Some(match code {
+            1 => TypeCode::UInt16,
+            2 => TypeCode::UInt32,
+            3 => TypeCode::UInt64,
+            4 => TypeCode::Hash128,
+            5 => TypeCode::Hash256,
+            6 => TypeCode::Amount,
+            7 => TypeCode::Blob,
+            8 => TypeCode::AccountID,
+            14 => TypeCode::STObject,
+            15 => TypeCode::STArray,
+            16 => TypeCode::UInt8,
+            17 => TypeCode::Hash160,
+            18 => TypeCode::PathSet,
+            19 => TypeCode::Vector256,
+            _ => return None,
+        })
+    }
+
+    /// Length of every value of this type, `None` if it varies.
+    const fn fixed_len(self) -> Option<usize> {
+        Some(match self {
+            TypeCode::UInt8 => 1,
+            TypeCode::UInt16 => 2,
+            TypeCode::UInt32 => 4,
+            TypeCode::UInt64 => 8,
+            TypeCode::Hash128 => 16,
+            TypeCode::Hash160 => 20,
+            TypeCode::Hash256 => 32,
+            _ => return None,
+        })
+    }
+
+    /// Whether values of this type carry a length prefix.
+    const fn is_vl(self) -> bool {
+        matches!(self, TypeCode::Blob | TypeCode::AccountID | TypeCode::Vector256)
+    }
+}
+
+/// One field of a serialized object or array. Blobs and accounts come
+/// without their length prefix, objects and arrays without their end
+/// marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field<'a> {
+    /// Field code: type << 16 | field.
+    pub code: u32,
+    pub type_code: TypeCode,
+    pub value: &'a [u8],
+}
+
+impl<'a> Field<'a> {
+    pub fn u8(&self) -> Option<u8> {
+        self.fixed::<1>(TypeCode::UInt8).map(|bytes| bytes[0])
+    }
+
+    pub fn u16(&self) -> Option<u16> {
+        self.fixed(TypeCode::UInt16).map(u16::from_be_bytes)
+    }
+
+    pub fn u32(&self) -> Option<u32> {
+        self.fixed(TypeCode::UInt32).map(u32::from_be_bytes)
+    }
+
+    pub fn u64(&self) -> Option<u64> {
+        self.fixed(TypeCode::UInt64).map(u64::from_be_bytes)
+    }
+
+    pub fn hash256(&self) -> Option<[u8; 32]> {
+        self.fixed(TypeCode::Hash256)
+    }
+
+    pub fn account(&self) -> Option<[u8; 20]> {
+        self.fixed(TypeCode::AccountID)
+    }
+
+    pub fn amount(&self) -> Option<Amount> {
+        (self.type_code == TypeCode::Amount).then(|| Amount::from_bytes(self.value))?
+    }
+
+    /// The fields of an inner object, or the entries of an array.
+    pub fn fields(&self) -> Reader<'a> {
+        Reader::new(self.value)
+    }
+
+    fn fixed<const N: usize>(&self, type_code: TypeCode) -> Option<[u8; N]> {
+        (self.type_code == type_code).then(|| self.value.try_into().ok())?
+    }
+}
+
+/// Walks the fields of a serialized object, or the entries of an array, in
+/// order.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Reads `bytes` from the start. They may end at an end marker, as
+    /// `otxn_field` returns objects and arrays, or just end.
+    pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, pos: 0 }
+    }
+
+    /// The next field; `None` at the end, or on malformed input.
+    pub fn next_field(&mut self) -> Option<Field<'a>> {
+        let (field, next) = read_field(self.bytes, self.pos)?;
+        self.pos = next;
+        Some(field)
+    }
+
+    /// Whether every field has been read, so that [`Reader::next_field`]
+    /// returned `None` at the end rather than on malformed input.
+    pub fn is_done(&self) -> bool {
+        match self.bytes.get(self.pos) {
+            Some(&header) => header == OBJECT_END || header == ARRAY_END,
+            None => true,
+        }
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Field<'a>;
+
+    fn next(&mut self) -> Option<Field<'a>> {
+        self.next_field()
+    }
+}
+
+/// First field with code `field` in the serialized object `bytes`, reading
+/// at most `max` fields.
+pub fn find(bytes: &[u8], field: u32, max: u32) -> Option<Field<'_>> {
+    let mut reader = Reader::new(bytes);
+    let mut read = 0;
+    loop {
+        guard(line!(), max + 1);
+        if read == max {
+            return None;
+        }
+        read += 1;
+        let found = reader.next_field()?;
+        if found.code == field {
+            return Some(found);
+        }
+    }
+}
+
+/// Splits a field header at `pos` into its type code, field code and the
+/// position of the value.
+fn read_header(bytes: &[u8], pos: usize) -> Option<(u8, u8, usize)> {
+    let b0 = *bytes.get(pos)?;
+    let (mut type_code, mut field_code, mut pos) = (b0 >> 4, b0 & 0x0F, pos + 1);
+    if type_code == 0 {
+        type_code = *bytes.get(pos)?;
+        pos += 1;
+    }
+    if field_code == 0 {
+        field_code = *bytes.get(pos)?;
+        pos += 1;
+    }
+    Some((type_code, field_code, pos))
+}
+
+/// Start and end of a value of type `type_code` at `pos`, for every type
+/// but objects and arrays.
+fn value_span(bytes: &[u8], type_code: TypeCode, pos: usize) -> Option<(usize, usize)> {
+    if let Some(len) = type_code.fixed_len() {
+        return Some((pos, pos + len));
+    }
+    match type_code {
+        TypeCode::Amount if bytes.get(pos)? & 0x80 != 0 => Some((pos, pos + 48)),
+        TypeCode::Amount => Some((pos, pos + 8)),
+        _ if type_code.is_vl() => {
+            let (blob, next) = read_vl(bytes, pos)?;
+            Some((next - blob.len(), next))
+        }
+        _ => None,
+    }
+}
+
+/// Reads the field at `pos`, returning it and the position after it.
+fn read_field(bytes: &[u8], pos: usize) -> Option<(Field<'_>, usize)> {
+    let (type_code, field_code, pos) = read_header(bytes, pos)?;
+    let type_code = TypeCode::from_u8(type_code)?;
+    let code = (type_code as u32) << 16 | field_code as u32;
+    if let TypeCode::STObject | TypeCode::STArray = type_code {
+        if field_code == 1 {
+            return None;
+        }
+        let end = container_end(bytes, pos)?;
+        return Some((Field { code, type_code, value: &bytes[pos..end] }, end + 1));
+    }
+    let (start, end) = value_span(bytes, type_code, pos)?;
+    Some((Field { code, type_code, value: bytes.get(start..end)? }, end))
+}
+
+/// Position of the end marker closing the object or array whose fields
+/// start at `pos`.
+fn container_end(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    let mut depth = 0u32;
+    loop {
+        guard(line!(), MAX_FIELDS + 1);
+        let (type_code, field_code, next) = read_header(bytes, pos)?;
+        let type_code = TypeCode::from_u8(type_code)?;
+        pos = match type_code {
+            TypeCode::STObject | TypeCode::STArray if field_code == 1 => {
+                if depth == 0 {
+                    return Some(pos);
+                }
+                depth -= 1;
+                next
+            }
+            TypeCode::STObject | TypeCode::STArray => {
+                depth += 1;
+                next
+            }
+            _ => value_span(bytes, type_code, next)?.1,
+        };
+    }
+}
+
+/// Reads a length-prefixed blob at `pos`, returning it and the position
+/// after it.
+pub fn read_vl(buf: &[u8], pos: usize) -> Option<(&[u8], usize)> {
+    let b0 = *buf.get(pos)? as usize;
+    let (len, start) = match b0 {
+        0..=192 => (b0, pos + 1),
+        193..=240 => (193 + (b0 - 193) * 256 + *buf.get(pos + 1)? as usize, pos + 2),
+        241..=254 => {
+            let b1 = *buf.get(pos + 1)? as usize;
+            let b2 = *buf.get(pos + 2)? as usize;
+            (12_481 + (b0 - 241) * 65_536 + b1 * 256 + b2, pos + 3)
+        }
+        _ => return None,
+    };
+    Some((buf.get(start..start + len)?, start + len))
+}
+
+/// Appends serialized fields to a caller-provided buffer.
+pub struct Writer<'a> {
+    pub(crate) buf: &'a mut [u8],
+    pub(crate) len: usize,
+}
+
+impl<'a> Writer<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Writer<'a> {
+        Writer { buf, len: 0 }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn raw(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// Field header for `field` (type << 16 | field code), 1 to 3 bytes.
+    pub fn header(&mut self, field: u32) {
+        let (type_code, field_code) = ((field >> 16) as u8, field as u8);
+        match (type_code < 16, field_code < 16) {
+            (true, true) => self.raw(&[type_code << 4 | field_code]),
+            (true, false) => self.raw(&[type_code << 4, field_code]),
+            (false, true) => self.raw(&[field_code, type_code]),
+            (false, false) => self.raw(&[0, type_code, field_code]),
+        }
+    }
+
+    pub fn u8(&mut self, field: u32, value: u8) {
+        self.typed_header(field, TypeCode::UInt8);
+        self.raw(&[value]);
+    }
+
+    pub fn u16(&mut self, field: u32, value: u16) {
+        self.typed_header(field, TypeCode::UInt16);
+        self.raw(&value.to_be_bytes());
+    }
+
+    pub fn u32(&mut self, field: u32, value: u32) {
+        self.typed_header(field, TypeCode::UInt32);
+        self.raw(&value.to_be_bytes());
+    }
+
+    pub fn u64(&mut self, field: u32, value: u64) {
+        self.typed_header(field, TypeCode::UInt64);
+        self.raw(&value.to_be_bytes());
+    }
+
+    pub fn hash256(&mut self, field: u32, hash: &[u8; 32]) {
+        self.typed_header(field, TypeCode::Hash256);
+        self.raw(hash);
+    }
+
+    /// Writes an Amount field, returning the offset of its value so it can
+    /// be patched later (as the fee is).
+    pub fn amount(&mut self, field: u32, amount: &Amount) -> usize {
+        self.typed_header(field, TypeCode::Amount);
+        let offset = self.len;
+        let mut serialized = [0u8; 48];
+        let len = amount.serialize(&mut serialized);
+        self.raw(&serialized[..len]);
+        offset
+    }
+
+    /// Variable-length blob with its length prefix.
+    pub fn vl(&mut self, field: u32, bytes: &[u8]) {
+        debug_assert!(TypeCode::of(field).is_some_and(TypeCode::is_vl), "field {field:#x} takes no length prefix");
+        self.header(field);
+        self.vl_len(bytes.len());
+        self.raw(bytes);
+    }
+
+    pub fn account(&mut self, field: u32, account: &[u8; 20]) {
+        self.typed_header(field, TypeCode::AccountID);
+        self.vl_len(20);
+        self.raw(account);
+    }
+
+    /// Starts an inner object or array; its fields follow, then
+    /// [`Writer::end_object`] or [`Writer::end_array`].
+    pub fn begin(&mut self, field: u32) {
+        debug_assert!(matches!(TypeCode::of(field), Some(TypeCode::STObject | TypeCode::STArray)));
+        self.header(field);
+    }
+
+    pub fn end_object(&mut self) {
+        self.raw(&[OBJECT_END]);
+    }
+
+    pub fn end_array(&mut self) {
+        self.raw(&[ARRAY_END]);
+    }
+
+    /// Writes back a field as [`Reader`] read it.
+    pub fn field(&mut self, field: &Field) {
+        self.header(field.code);
+        if field.type_code.is_vl() {
+            self.vl_len(field.value.len());
+        }
+        self.raw(field.value);
+        match field.type_code {
+            TypeCode::STObject => self.end_object(),
+            TypeCode::STArray => self.end_array(),
+            _ => {}
+        }
+    }
+
+    fn typed_header(&mut self, field: u32, type_code: TypeCode) {
+        debug_assert_eq!(TypeCode::of(field), Some(type_code), "field {field:#x}");
+        self.header(field);
+    }
+
+    fn vl_len(&mut self, len: usize) {
+        if len <= 192 {
+            self.raw(&[len as u8]);
+        } else if len <= 12_480 {
+            let len = len - 193;
+            self.raw(&[193 + (len >> 8) as u8, len as u8]);
+        } else {
+            let len = len - 12_481;
+            self.raw(&[241 + (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::IouValue;
+    use crate::api::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    /// The OfferCreate from the xrpl.org serialization format docs, signed
+    /// and serialized by rippled.
+    const OFFER_CREATE: &str = concat!(
+        "120007220008000024001ABED82A2380BF2C2019001ABED764D55920AC9391400000000000000000000000000055534400000000000A",
+        "20B3C85F482532A9578DBB3950B85CA06594D165400000037E11D60068400000000000000A732103EE83BB432547885C219634A1BC40",
+        "7A9DB0474145D69737D09CCDC63E1DEE7FE3744630440220143759437C04F7B61F012563AFE90D8DAFC46E86035E1D965A9CED282C97",
+        "D4CE02204CFD241E86F17E011298FC1A39B63386C74306A5DE047E213B0F29EFA4571C2C8114DD76483FACDEE26E60D8A586BB58D09F",
+        "27045C46",
+    );
+
+    fn written(f: impl FnOnce(&mut Writer)) -> Vec<u8> {
+        let mut buf = [0u8; 64];
+        let mut w = Writer::new(&mut buf);
+        f(&mut w);
+        w.as_bytes().to_vec()
+    }
+
+    #[test]
+    fn reads_a_rippled_transaction() {
+        let blob = hex(OFFER_CREATE);
+        let mut reader = Reader::new(&blob);
+        let codes: Vec<_> = reader.by_ref().map(|field| (field.code >> 16, field.code & 0xFFFF)).collect();
+        assert!(reader.is_done());
+        // TransactionType, Flags, Sequence, Expiration, OfferSequence,
+        // TakerPays, TakerGets, Fee, SigningPubKey, TxnSignature, Account
+        assert_eq!(codes, [(1, 2), (2, 2), (2, 4), (2, 10), (2, 25), (6, 4), (6, 5), (6, 8), (7, 3), (7, 4), (8, 1)]);
+
+        let field = |code| find(&blob, code, 16).unwrap();
+        assert_eq!(field(sfTransactionType).u16(), Some(7));
+        assert_eq!(field(sfFlags).u32(), Some(0x0008_0000));
+        assert_eq!(field(sfSequence).u32(), Some(1_752_792));
+        assert_eq!(field(sfFee).amount(), Some(Amount::Xrp(10)));
+        assert_eq!(field((6 << 16) + 5).amount(), Some(Amount::Xrp(15_000_000_000)));
+        let Some(Amount::Iou { value, currency, issuer }) = field((6 << 16) + 4).amount() else {
+            panic!("TakerPays is not a token amount");
+        };
+        assert_eq!(value, IouValue::new(70_728, -1).unwrap());
+        assert_eq!(currency[12..15], *b"USD");
+        assert_eq!(issuer[..], hex("0A20B3C85F482532A9578DBB3950B85CA06594D1"));
+        assert_eq!(field(sfSigningPubKey).value.len(), 33);
+        assert_eq!(field((7 << 16) + 4).value.len(), 70);
+        assert_eq!(field(sfAccount).account().unwrap()[..], hex("DD76483FACDEE26E60D8A586BB58D09F27045C46"));
+        assert_eq!(find(&blob, sfDestination, 16), None);
+    }
+
+    #[test]
+    fn writes_back_what_it_reads() {
+        let blob = hex(OFFER_CREATE);
+        let mut buf = [0u8; 256];
+        let mut w = Writer::new(&mut buf);
+        for field in Reader::new(&blob) {
+            w.field(&field);
+        }
+        assert_eq!(w.as_bytes(), blob);
+    }
+
+    #[test]
+    fn reads_nested_objects_and_arrays() {
+        let mut buf = [0u8; 64];
+        let mut w = Writer::new(&mut buf);
+        w.u32(sfFlags, 1);
+        w.begin(sfMemos);
+        w.begin(sfMemo);
+        w.vl(sfMemoType, b"a");
+        w.end_object();
+        w.begin(sfMemo);
+        w.vl(sfMemoData, b"bc");
+        w.end_object();
+        w.end_array();
+        w.account(sfAccount, &[7; 20]);
+        let blob = w.as_bytes().to_vec();
+        assert_eq!(blob[5..20], hex("F9EA7C0161E1EA7D026263E1F18114"));
+
+        let memos = find(&blob, sfMemos, 8).unwrap();
+        assert_eq!(memos.type_code, TypeCode::STArray);
+        let entries: Vec<_> = memos.fields().collect();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.code == sfMemo));
+        let data = entries[1].fields().next_field().unwrap();
+        assert_eq!((data.code, data.value), (sfMemoData, &b"bc"[..]));
+        assert_eq!(find(&blob, sfAccount, 8).and_then(|field| field.account()), Some([7; 20]));
+    }
+
+    #[test]
+    fn stops_at_end_markers_and_malformed_input() {
+        // As `otxn_field` returns an array: its entries, then the end marker
+        let memos = hex("EA7C0161E1F1");
+        let mut reader = Reader::new(&memos);
+        assert_eq!(reader.next_field().map(|field| field.code), Some(sfMemo));
+        assert_eq!(reader.next_field(), None);
+        assert!(reader.is_done());
+
+        let truncated = hex("EA7C0561");
+        let mut reader = Reader::new(&truncated);
+        assert_eq!(reader.next_field(), None);
+        assert!(!reader.is_done());
+        let unknown_type = hex("9101");
+        assert_eq!(Reader::new(&unknown_type).next_field(), None);
+    }
+
+    #[test]
+    fn checks_value_types() {
+        let blob = written(|w| w.u32(sfSequence, 5));
+        let field = Reader::new(&blob).next_field().unwrap();
+        assert_eq!((field.u32(), field.u16(), field.account()), (Some(5), None, None));
+        assert_eq!(TypeCode::of(sfNFTokenID), Some(TypeCode::Hash256));
+        assert_eq!(TypeCode::of(9 << 16), None);
+    }
+
+    #[test]
+    fn encodes_field_headers() {
+        assert_eq!(written(|w| w.header(sfTransactionType)), [0x12]);
+        assert_eq!(written(|w| w.header(sfNFTokenTaxon)), [0x20, 0x2A]);
+        assert_eq!(written(|w| w.header((16 << 16) + 1)), [0x01, 0x10]);
+        assert_eq!(written(|w| w.header((16 << 16) + 17)), [0x00, 0x10, 0x11]);
+        for field in [sfTransactionType, sfNFTokenTaxon, (16 << 16) + 1, (16 << 16) + 17] {
+            let header = written(|w| w.header(field));
+            assert_eq!(read_header(&header, 0), Some(((field >> 16) as u8, field as u8, header.len())));
+        }
+    }
+
+    #[test]
+    fn encodes_variable_lengths() {
+        let mut buf = [0u8; 16];
+        let mut w = Writer::new(&mut buf);
+        w.vl_len(192);
+        w.vl_len(193);
+        w.vl_len(12_480);
+        w.vl_len(12_481);
+        assert_eq!(w.as_bytes(), [192, 193, 0, 240, 255, 241, 0, 0]);
+    }
+
+    #[test]
+    fn reads_long_blobs() {
+        let mut buf = vec![193, 7];
+        buf.extend([0xAB; 200]);
+        let (blob, next) = read_vl(&buf, 0).unwrap();
+        assert_eq!((blob.len(), next), (200, 202));
+    }
+
+    #[test]
+    fn writes_accounts_with_length_prefix() {
+        let bytes = written(|w| w.account(sfDestination, &[7; 20]));
+        assert_eq!(bytes[..2], [0x83, 0x14]);
+        assert_eq!(bytes[2..], [7; 20]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Reward treasury hook, installed on the account that pays out Spark.
+//!
+//! A player claims with an Invoke to the treasury carrying a `spark-claim`
+//! memo: their account, the cumulative whole Spark the matchmaker has
+//! awarded them, and the matchmaker's ed25519 signature over
+//! `"SPARK-CLAIM" || treasury || account || total`. The matchmaker key is
+//! the 33-byte `MM_KEY` hook parameter.
+//!
+//! The hook keeps each account's claimed total in state and pays out only
+//! the difference, so a replayed claim pays nothing and no claim can exceed
+//! what the matchmaker signed. If the emitted payment fails, the callback
+//! returns the amount to the claimable balance.
+
+use crate::admin::{self, WhenPaused};
+use crate::amount::{Amount, IouValue};
+use crate::api::*;
+use crate::bytes::{array20, eq20};
+use crate::error::HookError;
+use crate::etxn;
+use crate::memo::{self, MAX_MEMOS_LEN};
+use crate::triggers::{self, hook_on};
+use crate::{SPARK_CURRENCY, SPARK_ISSUER};
+
+/// MemoType of a reward claim.
+pub const CLAIM_MEMO_TYPE: &[u8] = b"spark-claim";
+
+/// Transaction types the treasury fires on; deployment derives `HookOn` from these.
+pub const TRIGGERS: &[i64] = &[ttINVOKE];
+
+const HOOK_ON: [u8; 32] = hook_on(TRIGGERS);
+
+/// Paused, the hook rejects claims rather than let them through unhandled.
+const WHEN_PAUSED: WhenPaused = WhenPaused::Rollback;
+
+/// Domain separator prepended to the signed claim message.
+pub const CLAIM_PREFIX: &[u8] = b"SPARK-CLAIM";
+
+/// Claim memo data: account, big-endian total, signature.
+const CLAIM_LEN: usize = 20 + 8 + 64;
+
+/// State key of `account`'s claimed total: "CLM" followed by the account.
+pub fn claimed_key(account: &[u8; 20]) -> [u8; 23] {
+    let mut key = [0u8; 23];
+    key[..3].copy_from_slice(b"CLM");
+    key[3..].copy_from_slice(account);
+    key
+}
+
+/// Message the matchmaker signs to award `account` a cumulative `total`.
+pub fn claim_message(treasury: &[u8; 20], account: &[u8; 20], total: u64) -> [u8; 59] {
+    let mut message = [0u8; 59];
+    message[..11].copy_from_slice(CLAIM_PREFIX);
+    message[11..31].copy_from_slice(treasury);
+    message[31..51].copy_from_slice(account);
+    message[51..].copy_from_slice(&total.to_be_bytes());
+    message
+}
+
+pub fn reward_treasury(tx: &mut HookCtx) -> i32 {
+    if !triggers::declared(tx, &HOOK_ON) {
+        return 0;
+    }
+    admin::commands(tx);
+    admin::check_paused(tx, WHEN_PAUSED);
+    let treasury = tx.hook_account();
+    let mut claimant = [0u8; 20];
+    if tx.otxn_field(sfAccount, &mut claimant) != 20 || eq20(&claimant, &treasury) {
+        return 0;
+    }
+
+    let mut memos = [0u8; MAX_MEMOS_LEN];
+    let len = tx.otxn_field(sfMemos, &mut memos).max(0) as usize;
+    let Some(claim) = memo::find(&memos[..len], CLAIM_MEMO_TYPE) else {
+        HookError::MissingClaimMemo.rollback();
+    };
+    if claim.data.len() != CLAIM_LEN {
+        HookError::MalformedClaim.rollback();
+    }
+    if !eq20(&array20(claim.data), &claimant) {
+        HookError::ClaimForOther.rollback();
+    }
+    let mut total = [0u8; 8];
+    total.copy_from_slice(&claim.data[20..28]);
+    let total = u64::from_be_bytes(total);
+
+    let mut key = [0u8; 33];
+    if admin::param(tx, b"MM_KEY", &mut key) != 33 {
+        HookError::MissingMmKey.rollback();
+    }
+    let message = claim_message(&treasury, &claimant, total);
+    if !tx.util_verify(&message, &claim.data[28..], &key) {
+        HookError::InvalidClaimSignature.rollback();
+    }
+
+    let claimed_key = claimed_key(&claimant);
+    let claimed = read_u64(tx, &claimed_key);
+    if total <= claimed {
+        HookError::NothingToClaim.rollback();
+    }
+    let payout = total - claimed;
+    if tx.state_set(&claimed_key, &total.to_be_bytes()) < 0 {
+        HookError::RecordClaimFailed.rollback();
+    }
+
+    let Some(value) = IouValue::new(payout, 0) else {
+        HookError::ClaimTooLarge.rollback();
+    };
+    let amount = Amount::Iou { value, currency: SPARK_CURRENCY, issuer: SPARK_ISSUER };
+    if tx.etxn_reserve(1) < 0 {
+        HookError::ReservePayoutFailed.rollback();
+    }
+    let Ok(hash) = etxn::emit_payment(tx, &claimant, &amount) else {
+        HookError::EmitPayoutFailed.rollback();
+    };
+    // Remember the payout under its hash so the callback can undo it
+    let mut record = [0u8; 28];
+    record[..20].copy_from_slice(&claimant);
+    record[20..].copy_from_slice(&payout.to_be_bytes());
+    if tx.state_set(&hash, &record) < 0 {
+        HookError::RecordPayoutFailed.rollback();
+    }
+    ACCEPT("Spark reward paid", 0);
+}
+
+/// Callback for emitted payouts: forgets the payout record, and if the
+/// payment failed, lowers the claimant's claimed total again.
+pub fn reward_treasury_cbak(tx: &mut HookCtx, what: u32) -> i32 {
+    let hash = tx.otxn_id();
+    let mut record = [0u8; 28];
+    if tx.state(&hash, &mut record) != 28 {
+        return 0;
+    }
+    tx.state_set(&hash, &[]);
+    if what == 0 {
+        return 0;
+    }
+    let mut claimant = [0u8; 20];
+    claimant.copy_from_slice(&record[..20]);
+    let mut payout = [0u8; 8];
+    payout.copy_from_slice(&record[20..]);
+    let key = claimed_key(&claimant);
+    let claimed = read_u64(tx, &key).saturating_sub(u64::from_be_bytes(payout));
+    tx.state_set(&key, &claimed.to_be_bytes());
+    0
+}
+
+fn read_u64(tx: &HookCtx, key: &[u8]) -> u64 {
+    let mut value = [0u8; 8];
+    if tx.state(key, &mut value) == 8 { u64::from_be_bytes(value) } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{state_key, Exit, Mock, Outcome, Tx};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const TREASURY: [u8; 20] = [0x7E; 20];
+    const PLAYER: [u8; 20] = [0x01; 20];
+
+    fn matchmaker() -> SigningKey {
+        SigningKey::from_bytes(&[9; 32])
+    }
+
+    fn mock() -> Mock {
+        let mut key = vec![0xED];
+        key.extend(matchmaker().verifying_key().as_bytes());
+        Mock::new(TREASURY).param(b"MM_KEY", &key)
+    }
+
+    fn claim(account: [u8; 20], total: u64, signer: &SigningKey) -> Tx {
+        let signature = signer.sign(&claim_message(&TREASURY, &account, total));
+        let mut data = account.to_vec();
+        data.extend(total.to_be_bytes());
+        data.extend(signature.to_bytes());
+        Tx::new(ttINVOKE).field(sfAccount, &PLAYER).field(sfDestination, &TREASURY).memo(CLAIM_MEMO_TYPE, &data)
+    }
+
+    fn claimed(outcome: &Outcome) -> Option<u64> {
+        let value = outcome.state.get(&state_key(&claimed_key(&PLAYER)))?;
+        Some(u64::from_be_bytes(value[..].try_into().unwrap()))
+    }
+
+    #[test]
+    fn pays_out_signed_claim() {
+        let outcome = mock().run(&claim(PLAYER, 15, &matchmaker()), reward_treasury);
+        assert_eq!(outcome.exit, Exit::Accept, "{}", outcome.message);
+        assert_eq!(claimed(&outcome), Some(15));
+        assert_eq!(outcome.emitted.len(), 1);
+
+        let mut amount = [0u8; 48];
+        let spark = Amount::Iou { value: IouValue::new(15, 0).unwrap(), currency: SPARK_CURRENCY, issuer: SPARK_ISSUER };
+        spark.serialize(&mut amount);
+        let blob = &outcome.emitted[0];
+        assert_eq!(blob[..3], [0x12, 0x00, 0x00]);
+        assert!(blob.windows(49).any(|field| field[0] == 0x61 && field[1..] == amount));
+    }
+
+    #[test]
+    fn pays_only_the_difference_and_rejects_replays() {
+        let seeded = mock().state(&claimed_key(&PLAYER), &10u64.to_be_bytes());
+        let outcome = seeded.run(&claim(PLAYER, 15, &matchmaker()), reward_treasury);
+        assert_eq!(claimed(&outcome), Some(15));
+        let amount = IouValue::new(5, 0).unwrap().to_bytes();
+        assert!(outcome.emitted[0].windows(8).any(|bytes| bytes == amount));
+
+        let replay = seeded.run(&claim(PLAYER, 10, &matchmaker()), reward_treasury);
+        assert_eq!((replay.exit, replay.code), (Exit::Rollback, HookError::NothingToClaim.code()));
+        assert!(replay.emitted.is_empty());
+    }
+
+    #[test]
+    fn rejects_forged_or_foreign_claims() {
+        let forged = mock().run(&claim(PLAYER, 1_000, &SigningKey::from_bytes(&[1; 32])), reward_treasury);
+        assert_eq!((forged.exit, forged.code), (Exit::Rollback, HookError::InvalidClaimSignature.code()));
+
+        let foreign = mock().run(&claim([0x02; 20], 15, &matchmaker()), reward_treasury);
+        assert_eq!((foreign.exit, foreign.code), (Exit::Rollback, HookError::ClaimForOther.code()));
+
+        let unsigned = Tx::new(ttINVOKE).field(sfAccount, &PLAYER);
+        assert_eq!(mock().run(&unsigned, reward_treasury).code, HookError::MissingClaimMemo.code());
+    }
+
+    #[test]
+    fn requires_matchmaker_key() {
+        let outcome = Mock::new(TREASURY).run(&claim(PLAYER, 15, &matchmaker()), reward_treasury);
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, HookError::MissingMmKey.code()));
+    }
+
+    #[test]
+    fn callback_restores_balance_when_payout_fails() {
+        let outcome = mock().run(&claim(PLAYER, 15, &matchmaker()), reward_treasury);
+        let (hash, _) = outcome.state.iter().find(|(_, record)| record.len() == 28).unwrap();
+        let mut after = Mock::new(TREASURY);
+        for (key, value) in &outcome.state {
+            after = after.state(key, value);
+        }
+        let payout = Tx::new(ttPAYMENT).id(*hash);
+
+        let failed = after.callback(&payout, reward_treasury_cbak, 1);
+        assert_eq!(claimed(&failed), Some(0));
+        assert_eq!(failed.state.len(), 1);
+
+        let applied = after.callback(&payout, reward_treasury_cbak, 0);
+        assert_eq!(claimed(&applied), Some(15));
+        assert_eq!(applied.state.len(), 1);
+    }
+}
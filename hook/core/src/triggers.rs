@@ -0,0 +1,69 @@
+//! Transaction types a hook fires on, and the `HookOn` mask that tells
+//! rippled so.
+//!
+//! `HookOn` is 256 bits, one per transaction type, counted from the least
+//! significant bit of the last byte. A set bit means the hook does *not*
+//! fire, except for bit 22 (`ttHOOK_SET`), which is inverted. Each hook
+//! declares its `TRIGGERS`; deployment turns them into `HookOn` with
+//! [`hook_on`] and the hook checks them with [`declared`].
+
+use crate::api::{HookCtx, ttHOOK_SET};
+
+/// `HookOn` for a hook that fires on exactly `triggers`.
+pub const fn hook_on(triggers: &[i64]) -> [u8; 32] {
+    let mut mask = [0xFFu8; 32];
+    let mut i = 0;
+    while i < triggers.len() {
+        let tt = triggers[i];
+        assert!(0 <= tt && tt < 256, "transaction types are below 256");
+        mask[31 - tt as usize / 8] &= !(1 << (tt % 8));
+        i += 1;
+    }
+    mask[31 - ttHOOK_SET as usize / 8] ^= 1 << (ttHOOK_SET % 8);
+    mask
+}
+
+/// Whether a hook installed with `hook_on` fires on transaction type `tt`.
+pub const fn fires_on(hook_on: &[u8; 32], tt: i64) -> bool {
+    if tt < 0 || tt >= 256 {
+        return false;
+    }
+    let skipped = hook_on[31 - tt as usize / 8] >> (tt % 8) & 1 == 1;
+    skipped == (tt == ttHOOK_SET)
+}
+
+/// Whether the originating transaction is of a type the hook declared in
+/// `hook_on`. Debug builds panic instead of returning `false`, since
+/// `HookOn` should have kept the hook from running at all.
+pub fn declared(tx: &HookCtx, hook_on: &[u8; 32]) -> bool {
+    let tt = tx.otxn_type();
+    let fires = fires_on(hook_on, tt);
+    debug_assert!(fires, "hook triggered by undeclared transaction type {tt}");
+    fires
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ttINVOKE, ttNFTOKEN_MINT, ttPAYMENT};
+
+    #[test]
+    fn masks_declared_types() {
+        let mut payment = [0xFF; 32];
+        payment[29] = 0xBF; // ttHOOK_SET inverted
+        payment[31] = 0xFE;
+        assert_eq!(hook_on(&[ttPAYMENT]), payment);
+
+        let invoke = hook_on(&[ttINVOKE]);
+        assert_eq!((invoke[19], invoke[29], invoke[31]), (0xF7, 0xBF, 0xFF));
+    }
+
+    #[test]
+    fn fires_only_on_declared_types() {
+        let mask = hook_on(&[ttPAYMENT, ttHOOK_SET]);
+        assert!(fires_on(&mask, ttPAYMENT) && fires_on(&mask, ttHOOK_SET));
+        assert!(!fires_on(&mask, ttINVOKE) && !fires_on(&mask, ttNFTOKEN_MINT));
+        assert!(!fires_on(&mask, -1) && !fires_on(&mask, 256));
+        assert!(!fires_on(&hook_on(&[]), ttHOOK_SET));
+    }
+}
@@ -0,0 +1,34 @@
+//! C ABI over [`creature_crafter_hook::dna`]. The SDK's tests load its wasm
+//! build and check `statsFromDNA` against it on top of the shared vectors.
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+use creature_crafter_hook::dna::{Stats, DNA_LEN};
+
+/// Input buffer for [`pet_stats`] callers that cannot pass their own
+/// pointer, such as JavaScript writing into wasm memory.
+static mut DNA_BUFFER: [u8; DNA_LEN] = [0; DNA_LEN];
+
+/// Address of a [`DNA_LEN`]-byte buffer to write DNA into before calling
+/// [`pet_stats`].
+#[no_mangle]
+pub extern "C" fn pet_dna_buffer() -> *mut u8 {
+    core::ptr::addr_of_mut!(DNA_BUFFER) as *mut u8
+}
+
+/// The packed stats ([`Stats::pack`]) of the `len` bytes of hex DNA at
+/// `dna`, or -1 if they are not valid DNA.
+///
+/// # Safety
+///
+/// `dna` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pet_stats(dna: *const u8, len: u32) -> i64 {
+    if dna.is_null() {
+        return -1;
+    }
+    let dna = unsafe { core::slice::from_raw_parts(dna, len as usize) };
+    match Stats::from_dna(dna) {
+        Some(stats) => stats.pack() as i64,
+        None => -1,
+    }
+}
@@ -0,0 +1,17 @@
+//! Egg shop hook: exports [`creature_crafter_hook::egg_shop::egg_shop`].
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+#[cfg(target_arch = "wasm32")]
+use creature_crafter_hook::api::{enter, enter_cbak};
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn hook(_reserved: u32) -> i64 {
+    enter(creature_crafter_hook::egg_shop::egg_shop)
+}
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn cbak(what: u32) -> i64 {
+    enter_cbak(creature_crafter_hook::egg_shop::egg_shop_cbak, what)
+}
@@ -0,0 +1,11 @@
+//! Hatch hook: exports [`creature_crafter_hook::hatch::hatch`].
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+#[cfg(target_arch = "wasm32")]
+use creature_crafter_hook::api::enter;
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn hook(_reserved: u32) -> i64 {
+    enter(creature_crafter_hook::hatch::hatch)
+}
@@ -0,0 +1,17 @@
+//! Reward treasury hook: exports [`creature_crafter_hook::treasury::reward_treasury`].
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+#[cfg(target_arch = "wasm32")]
+use creature_crafter_hook::api::{enter, enter_cbak};
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn hook(_reserved: u32) -> i64 {
+    enter(creature_crafter_hook::treasury::reward_treasury)
+}
+
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn cbak(what: u32) -> i64 {
+    enter_cbak(creature_crafter_hook::treasury::reward_treasury_cbak, what)
+}
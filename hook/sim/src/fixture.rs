@@ -0,0 +1,309 @@
+//! Declarative hook tests: one JSON file per case, run through the
+//! simulator by `tests/fixtures.rs` against every fixture under
+//! `hook/fixtures/`.
+//!
+//! ```json
+//! {
+//!   "description": "Burns 1% of a Spark payment to the issuer",
+//!   "hook": "burn",
+//!   "hook_account": "r...",
+//!   "ledger_seq": 1000,
+//!   "params": { "BURN_BPS": "0064" },
+//!   "state": { "4255524E4544": "0000000000000001" },
+//!   "tx": { "TransactionType": "Payment", "Account": "r...", ... },
+//!   "expect": {
+//!     "result": "accept",
+//!     "code": 0,
+//!     "message": "Spark burned",
+//!     "state_writes": [{ "key": "...", "value": "..." }],
+//!     "emitted": [{ "TransactionType": "Payment", "Destination": "r..." }]
+//!   }
+//! }
+//! ```
+//!
+//! `hook` names a hook in this workspace (see `HOOKS`) and runs its built
+//! `<hook>_hook.wasm`; `"callback": 0` (or 1) runs its `cbak` instead, with
+//! that `what`. Hook parameters are ASCII names with hex values, as in the
+//! SetHook manifest; state keys and values are hex, keys padded to 32 bytes
+//! the way the ledger pads them. `tx` is the originating transaction as
+//! rippled prints it (see [`json`](crate::json)).
+//!
+//! `state_writes` lists every `state_set` in order, a deletion with an
+//! empty value; `emitted` lists every emitted transaction, each checked
+//! only on the fields given. Both default to none, so a fixture that
+//! leaves them out expects the hook to write and emit nothing.
+
+use std::collections::BTreeMap;
+
+use creature_crafter_hook::error::HookError;
+use creature_crafter_hook::mock::{field_of, state_key};
+use creature_crafter_hook::HOOKS;
+use serde::Deserialize;
+use serde_json::Value;
+
+use hook_codec::address::decode_account;
+use hook_codec::definitions::{self, TypeCode};
+use hook_codec::{field_value, hex};
+
+use crate::{json, Error, Exit, Hook, Mock, Outcome, Tx};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    #[serde(default)]
+    pub description: String,
+    pub hook: String,
+    pub hook_account: String,
+    pub ledger_seq: Option<u32>,
+    pub callback: Option<u32>,
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    #[serde(default)]
+    pub state: BTreeMap<String, String>,
+    pub tx: Value,
+    pub expect: Expect,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expect {
+    pub result: Result,
+    pub code: i64,
+    pub message: Option<String>,
+    #[serde(default)]
+    pub state_writes: Vec<StateWrite>,
+    #[serde(default)]
+    pub emitted: Vec<Value>,
+}
+
+/// How the hook is expected to end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Result {
+    Accept,
+    Rollback,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateWrite {
+    pub key: String,
+    pub value: String,
+}
+
+impl Fixture {
+    pub fn parse(text: &str) -> std::result::Result<Fixture, String> {
+        let fixture: Fixture = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if !HOOKS.iter().any(|info| info.name == fixture.hook) {
+            let names: Vec<_> = HOOKS.iter().map(|info| info.name).collect();
+            return Err(format!("unknown hook {:?}, expected one of {}", fixture.hook, names.join(", ")));
+        }
+        Ok(fixture)
+    }
+
+    /// File name of the built hook the fixture runs.
+    pub fn wasm_name(&self) -> String {
+        format!("{}_hook.wasm", self.hook)
+    }
+
+    /// The ledger the hook runs in.
+    pub fn mock(&self) -> std::result::Result<Mock, String> {
+        let mut mock = Mock::new(decode_account(&self.hook_account)?);
+        if let Some(seq) = self.ledger_seq {
+            mock = mock.ledger_seq(seq);
+        }
+        for (name, value) in &self.params {
+            mock = mock.param(name.as_bytes(), &hex::decode(value).map_err(|e| format!("params.{name}: {e}"))?);
+        }
+        for (key, value) in &self.state {
+            let key = state_key_bytes(key)?;
+            mock = mock.state(&key, &hex::decode(value).map_err(|e| format!("state: {e}"))?);
+        }
+        Ok(mock)
+    }
+
+    pub fn tx(&self) -> std::result::Result<Tx, String> {
+        json::tx(&self.tx).map_err(|e| format!("tx: {e}"))
+    }
+
+    /// Runs the fixture on `hook` and checks the outcome: `Ok` with every
+    /// way it differs from `expect`, so empty when it passes.
+    pub fn run(&self, hook: &Hook) -> std::result::Result<Vec<String>, String> {
+        let (mock, tx) = (self.mock()?, self.tx()?);
+        let outcome = match self.callback {
+            Some(what) => hook.callback(&mock, &tx, what),
+            None => hook.run(&mock, &tx),
+        };
+        outcome.map(|outcome| self.check(&outcome)).map_err(|e: Error| e.to_string())
+    }
+
+    /// Every way `outcome` differs from `expect`.
+    pub fn check(&self, outcome: &Outcome) -> Vec<String> {
+        let expect = &self.expect;
+        let mut mismatches = Vec::new();
+        let exit = match outcome.exit {
+            Exit::Accept => Result::Accept,
+            Exit::Rollback => Result::Rollback,
+        };
+        if exit != expect.result || outcome.code != expect.code {
+            mismatches.push(format!(
+                "expected {:?} with code {}, got {exit:?} with code {} ({:?})",
+                expect.result,
+                describe(expect.code),
+                describe(outcome.code),
+                outcome.message,
+            ));
+        }
+        if let Some(message) = expect.message.as_ref().filter(|message| **message != outcome.message) {
+            mismatches.push(format!("expected message {message:?}, got {:?}", outcome.message));
+        }
+
+        let expected: std::result::Result<Vec<_>, String> = expect
+            .state_writes
+            .iter()
+            .map(|write| Ok((state_key(&state_key_bytes(&write.key)?), hex::decode(&write.value)?)))
+            .collect();
+        match expected {
+            Ok(expected) if expected != outcome.state_writes => mismatches.push(format!(
+                "expected state writes {}, got {}",
+                describe_writes(&expected),
+                describe_writes(&outcome.state_writes)
+            )),
+            Ok(_) => {}
+            Err(e) => mismatches.push(format!("state_writes: {e}")),
+        }
+
+        if expect.emitted.len() != outcome.emitted.len() {
+            mismatches.push(format!(
+                "expected {} emitted transactions, got {}",
+                expect.emitted.len(),
+                outcome.emitted.len()
+            ));
+        }
+        for (i, (expected, blob)) in expect.emitted.iter().zip(&outcome.emitted).enumerate() {
+            let Some(fields) = expected.as_object() else {
+                mismatches.push(format!("emitted[{i}] is not an object"));
+                continue;
+            };
+            for (name, value) in fields {
+                match emitted_field(blob, name, value) {
+                    Ok(None) => {}
+                    Ok(Some(actual)) => mismatches.push(format!("emitted[{i}].{name}: expected {value}, got {actual}")),
+                    Err(e) => mismatches.push(format!("emitted[{i}].{name}: {e}")),
+                }
+            }
+        }
+        mismatches
+    }
+}
+
+/// `None` if field `name` of the serialized transaction `blob` is `value`,
+/// otherwise what it is instead, in hex.
+fn emitted_field(blob: &[u8], name: &str, value: &Value) -> std::result::Result<Option<String>, String> {
+    let field = definitions::field(name)?;
+    let mut expected = field_value(field, value)?;
+    if matches!(field.type_code, TypeCode::STObject | TypeCode::STArray) {
+        // `field_of` drops the end marker `otxn_field` keeps
+        expected.pop();
+    }
+    Ok(match field_of(blob, field.code()) {
+        Some(actual) if actual == expected => None,
+        Some(actual) => Some(hex::encode(&actual)),
+        None => Some("no such field".to_string()),
+    })
+}
+
+/// State keys are hex, up to 32 bytes.
+fn state_key_bytes(key: &str) -> std::result::Result<Vec<u8>, String> {
+    let bytes = hex::decode(key)?;
+    if bytes.len() > 32 {
+        return Err(format!("state key {key} is over 32 bytes"));
+    }
+    Ok(bytes)
+}
+
+fn describe(code: i64) -> String {
+    match HookError::from_code(code) {
+        Some(error) => format!("{code} ({error:?})"),
+        None => code.to_string(),
+    }
+}
+
+fn describe_writes(writes: &[([u8; 32], Vec<u8>)]) -> String {
+    let writes: Vec<_> = writes.iter().map(|(key, value)| format!("{}={}", hex::encode(key), hex::encode(value))).collect();
+    format!("[{}]", writes.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HOOK_ACCOUNT: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    fn fixture(expect: Value) -> Fixture {
+        Fixture::parse(
+            &json!({
+                "hook": "burn",
+                "hook_account": HOOK_ACCOUNT,
+                "tx": { "TransactionType": "Invoke" },
+                "expect": expect,
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    fn outcome(exit: Exit, code: i64) -> Outcome {
+        Outcome {
+            exit,
+            code,
+            message: "done".to_string(),
+            state: BTreeMap::new(),
+            state_writes: Vec::new(),
+            emitted: Vec::new(),
+            traces: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn checks_exit_code_and_message() {
+        let fixture = fixture(json!({ "result": "rollback", "code": 105, "message": "done" }));
+        assert!(fixture.check(&outcome(Exit::Rollback, 105)).is_empty());
+        let mismatches = fixture.check(&outcome(Exit::Accept, 0));
+        assert_eq!(mismatches, ["expected Rollback with code 105 (Paused), got Accept with code 0 (\"done\")"]);
+    }
+
+    #[test]
+    fn checks_state_writes_and_emitted_fields() {
+        let fixture = fixture(json!({
+            "result": "accept",
+            "code": 0,
+            "state_writes": [{ "key": "4B4559", "value": "" }],
+            "emitted": [{ "TransactionType": "Payment", "Destination": HOOK_ACCOUNT }],
+        }));
+        let mut outcome = outcome(Exit::Accept, 0);
+        outcome.state_writes.push((state_key(b"KEY"), Vec::new()));
+        let mut payment = vec![0x12, 0x00, 0x00, 0x83, 0x14];
+        payment.extend(decode_account(HOOK_ACCOUNT).unwrap());
+        outcome.emitted.push(payment);
+        assert_eq!(fixture.check(&outcome), Vec::<String>::new());
+
+        outcome.state_writes.clear();
+        outcome.emitted[0][2] = 99;
+        let mismatches = fixture.check(&outcome);
+        assert_eq!(mismatches.len(), 2, "{mismatches:?}");
+        assert!(mismatches[0].starts_with("expected state writes ["));
+        assert_eq!(mismatches[1], "emitted[0].TransactionType: expected \"Payment\", got 0063");
+    }
+
+    #[test]
+    fn rejects_unknown_hooks_and_keys() {
+        let fixture = json!({ "hook": "mint", "hook_account": HOOK_ACCOUNT, "tx": {}, "expect": { "result": "accept", "code": 0 } });
+        assert!(Fixture::parse(&fixture.to_string()).unwrap_err().contains("unknown hook"));
+        let fixture = json!({ "hook": "burn", "hook_account": HOOK_ACCOUNT, "tx": {}, "expect": { "result": "ok", "code": 0 } });
+        assert!(Fixture::parse(&fixture.to_string()).is_err());
+        let fixture = json!({ "hook": "burn", "hook_account": HOOK_ACCOUNT, "tx": {}, "expext": {} });
+        assert!(Fixture::parse(&fixture.to_string()).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Transactions in rippled's JSON form, as `tx` returns them and fixtures
+//! spell them, turned into a [`Tx`] for the simulator.
+//!
+//! Fields are serialized by `hook_codec`, as `otxn_field` returns them;
+//! one it does not know is an error rather than silently dropped, since the
+//! hook might read it. Lower-case keys (`hash`, `meta`, `ledger_index`,
+//! ...) are API metadata, not transaction fields, and are skipped; `hash`
+//! becomes the transaction ID.
+
+use creature_crafter_hook::api::sfHookParameters;
+use hook_codec::definitions::{self, value_of, TRANSACTION_TYPES};
+use hook_codec::{field_value, hex};
+use serde_json::Value;
+
+use crate::Tx;
+
+/// The transaction in `json`, either bare or wrapped in the `result` of a
+/// `tx` response.
+pub fn tx(json: &Value) -> Result<Tx, String> {
+    let json = json.get("result").unwrap_or(json);
+    let object = json.as_object().ok_or("transaction is not a JSON object")?;
+    let tt = match object.get("TransactionType") {
+        Some(Value::String(name)) => value_of(TRANSACTION_TYPES, name).ok_or_else(|| format!("unknown TransactionType {name:?}"))?,
+        _ => return Err("TransactionType missing".to_string()),
+    };
+    let mut tx = Tx::new(tt.into());
+    for (name, value) in object {
+        if name.starts_with(|c: char| c.is_ascii_lowercase()) {
+            continue;
+        }
+        let field = definitions::field(name)?;
+        if field.code() == sfHookParameters {
+            tx = hook_parameters(tx, value)?;
+            continue;
+        }
+        tx = tx.field(field.code(), &field_value(field, value).map_err(|e| format!("{name}: {e}"))?);
+    }
+    if let Some(hash) = object.get("hash") {
+        let hash = hash.as_str().ok_or("hash is not a string")?;
+        tx = tx.id(hex::decode_array(hash).map_err(|e| format!("hash: {e}"))?);
+    }
+    Ok(tx)
+}
+
+/// Adds the `HookParameters` entries in `value` to `tx`, to be read with
+/// `otxn_param`.
+fn hook_parameters(mut tx: Tx, value: &Value) -> Result<Tx, String> {
+    for entry in value.as_array().ok_or("HookParameters is not an array")? {
+        let parameter = &entry["HookParameter"];
+        let name = hex::decode(string(&parameter["HookParameterName"])?)?;
+        let value = match &parameter["HookParameterValue"] {
+            Value::Null => Vec::new(),
+            value => hex::decode(string(value)?)?,
+        };
+        tx = tx.param(&name, &value);
+    }
+    Ok(tx)
+}
+
+fn string(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| format!("{value} is not a string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use creature_crafter_hook::api::*;
+    use creature_crafter_hook::mock::field_of;
+    use hook_codec::address::decode_account;
+    use serde_json::json;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    #[test]
+    fn converts_a_tx_response() {
+        let response = json!({ "result": {
+            "TransactionType": "Invoke",
+            "Account": GENESIS,
+            "Memos": [{ "Memo": { "MemoData": "0102", "MemoType": "6869" } }],
+            "HookParameters": [{ "HookParameter": { "HookParameterName": "4F50", "HookParameterValue": "01" } }],
+            "hash": "AB".repeat(32),
+            "ledger_index": 5,
+            "meta": {},
+        }});
+        let tx = tx(&response).unwrap();
+        let expected = Tx::new(ttINVOKE)
+            .field(sfTransactionType, &[0, 99])
+            .field(sfAccount, &decode_account(GENESIS).unwrap())
+            .memo(b"hi", &[1, 2])
+            .param(b"OP", &[1])
+            .id([0xAB; 32]);
+        assert_eq!(format!("{tx:?}"), format!("{expected:?}"));
+
+        // Inner objects serialize in canonical order whatever the JSON order
+        let memos = field_value(definitions::field("Memos").unwrap(), &response["result"]["Memos"]).unwrap();
+        assert_eq!(field_of(&memos, sfMemo).unwrap(), [0x7C, 2, b'h', b'i', 0x7D, 2, 1, 2]);
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_types() {
+        assert!(tx(&json!({ "TransactionType": "Payment", "Bogus": [] })).unwrap_err().contains("Bogus"));
+        assert!(tx(&json!({ "TransactionType": "Bogus" })).is_err());
+        assert!(tx(&json!({ "Account": GENESIS })).is_err());
+    }
+}
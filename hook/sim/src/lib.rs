@@ -0,0 +1,489 @@
+//! Runs compiled hook wasm against an in-memory ledger.
+//!
+//! [`Hook`] loads a built `*_hook.wasm` into the wasmi interpreter and links
+//! its imports to a [`Session`], the same host API `cargo test` runs hooks
+//! against natively through [`Mock`]. So a [`Mock`] and [`Tx`] set up for a
+//! unit test can be replayed against the artifact that is actually deployed,
+//! and the [`Outcome`] (exit, code, message, state, emitted transactions)
+//! compared.
+//!
+//! Guard budgets are enforced as on-ledger: exceeding one rolls back with
+//! `GUARD_VIOLATION`. A fuel limit stops hooks that loop without guards.
+//!
+//! [`fixture`] describes hook tests as JSON, with transactions in rippled's
+//! JSON form (see [`json`]).
+
+pub mod fixture;
+pub mod json;
+
+use std::fmt;
+
+use creature_crafter_hook::api::{GUARD_VIOLATION, OUT_OF_BOUNDS};
+use creature_crafter_hook::mock::Session;
+pub use creature_crafter_hook::mock::{Exit, Mock, Outcome, Tx};
+use wasmi::{Caller, Config, Engine, Extern, Linker, Module, Store};
+
+/// Instructions a run may execute before it is stopped. Far beyond what the
+/// guards of any hook in this workspace allow.
+const FUEL: u64 = 100_000_000;
+
+/// A compiled hook, ready to run any number of times.
+pub struct Hook {
+    engine: Engine,
+    module: Module,
+}
+
+/// Why a run did not produce an [`Outcome`].
+#[derive(Debug)]
+pub enum Error {
+    /// The wasm did not validate.
+    Load(wasmi::Error),
+    /// The module lacks the `hook` or `cbak` export being run, or it has the
+    /// wrong signature.
+    MissingExport(&'static str),
+    /// The module imports a function the host does not provide.
+    Link(wasmi::Error),
+    /// Execution trapped: `unreachable`, a memory fault, or out of fuel.
+    Trap(wasmi::Error),
+    /// The entry point returned this value without calling `accept` or
+    /// `rollback`.
+    Returned(i64),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Load(e) => write!(f, "invalid hook wasm: {e}"),
+            Error::MissingExport(name) => write!(f, "no `{name}` export of type (i32) -> i64"),
+            Error::Link(e) => write!(f, "cannot link hook: {e}"),
+            Error::Trap(e) => write!(f, "hook trapped: {e}"),
+            Error::Returned(value) => write!(f, "hook returned {value} without accept or rollback"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// One host call the hook made, recorded by [`Hook::run_traced`] and
+/// [`Hook::callback_traced`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    pub name: &'static str,
+    /// Arguments other than pointers and lengths: field codes, slot
+    /// numbers, error codes and the like.
+    pub args: Vec<i64>,
+    /// Contents of the buffers the hook passed in.
+    pub inputs: Vec<Vec<u8>>,
+    /// What the host wrote into the hook's buffer.
+    pub output: Vec<u8>,
+    /// The return value; `None` for calls that ended the run.
+    pub result: Option<i64>,
+}
+
+/// Store data: the run in progress, the calls made so far when tracing and,
+/// once the hook has ended the run, how.
+struct Host {
+    session: Session,
+    calls: Option<Vec<Call>>,
+    exit: Option<(Exit, Vec<u8>, i64)>,
+}
+
+impl Hook {
+    pub fn new(wasm: &[u8]) -> Result<Hook, Error> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(Error::Load)?;
+        Ok(Hook { engine, module })
+    }
+
+    /// Runs the `hook` export on `tx`.
+    pub fn run(&self, mock: &Mock, tx: &Tx) -> Result<Outcome, Error> {
+        self.execute(mock, tx, "hook", 0, None).0
+    }
+
+    /// Runs the `cbak` export for the emitted transaction `tx`; `what` is 0
+    /// if it was applied and 1 if it failed.
+    pub fn callback(&self, mock: &Mock, tx: &Tx, what: u32) -> Result<Outcome, Error> {
+        self.execute(mock, tx, "cbak", what, None).0
+    }
+
+    /// [`Hook::run`], also returning every host call made, in order, however
+    /// the run ended.
+    pub fn run_traced(&self, mock: &Mock, tx: &Tx) -> (Result<Outcome, Error>, Vec<Call>) {
+        let (result, calls) = self.execute(mock, tx, "hook", 0, Some(Vec::new()));
+        (result, calls.unwrap_or_default())
+    }
+
+    /// [`Hook::callback`], also returning every host call made.
+    pub fn callback_traced(&self, mock: &Mock, tx: &Tx, what: u32) -> (Result<Outcome, Error>, Vec<Call>) {
+        let (result, calls) = self.execute(mock, tx, "cbak", what, Some(Vec::new()));
+        (result, calls.unwrap_or_default())
+    }
+
+    fn execute(
+        &self,
+        mock: &Mock,
+        tx: &Tx,
+        export: &'static str,
+        arg: u32,
+        calls: Option<Vec<Call>>,
+    ) -> (Result<Outcome, Error>, Option<Vec<Call>>) {
+        let host = Host { session: Session::new(mock, tx), calls, exit: None };
+        let mut store = Store::new(&self.engine, host);
+        store.set_fuel(FUEL).expect("fuel metering is enabled");
+        let result = linker(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(Error::Link)
+            .and_then(|instance| {
+                instance.get_typed_func::<u32, i64>(&store, export).map_err(|_| Error::MissingExport(export))
+            })
+            .map(|entry| entry.call(&mut store, arg));
+        let host = store.into_data();
+        let outcome = match (host.exit, result) {
+            (_, Err(e)) => Err(e),
+            (Some((exit, message, code)), _) => Ok(host.session.finish(exit, &message, code)),
+            (None, Ok(Ok(value))) => Err(Error::Returned(value)),
+            (None, Ok(Err(e))) => Err(Error::Trap(e)),
+        };
+        (outcome, host.calls)
+    }
+}
+
+/// Ends the run: records how, then traps out of the guest.
+fn halt(caller: &mut Caller<'_, Host>, exit: Exit, message: Vec<u8>, code: i64) -> wasmi::Error {
+    caller.data_mut().exit = Some((exit, message, code));
+    wasmi::Error::i32_exit(0)
+}
+
+fn record(host: &mut Host, call: impl FnOnce() -> Call) {
+    if let Some(calls) = &mut host.calls {
+        calls.push(call());
+    }
+}
+
+/// Calls `f` with the session, a copy of the guest buffer at `write`, and
+/// copies of the guest buffers at `reads`, then copies the write buffer
+/// back. `OUT_OF_BOUNDS` if any of them lies outside guest memory.
+fn call<const N: usize>(
+    caller: &mut Caller<'_, Host>,
+    name: &'static str,
+    args: &[i64],
+    write: (u32, u32),
+    reads: [(u32, u32); N],
+    f: impl FnOnce(&mut Session, &mut [u8], [&[u8]; N]) -> i64,
+) -> i64 {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory);
+    let (data, host): (&mut [u8], &mut Host) = match memory {
+        Some(memory) => memory.data_and_store_mut(&mut *caller),
+        None => (&mut [], caller.data_mut()),
+    };
+    let inputs: Option<Vec<Vec<u8>>> = reads.iter().map(|&read| Some(data[range(data, read)?].to_vec())).collect();
+    let (result, inputs, output) = match (range(data, write), inputs) {
+        (Some(out), Some(inputs)) => {
+            let mut buf = data[out.clone()].to_vec();
+            let result = f(&mut host.session, &mut buf, std::array::from_fn(|i| inputs[i].as_slice()));
+            data[out].copy_from_slice(&buf);
+            buf.truncate(result.clamp(0, buf.len() as i64) as usize);
+            (result, inputs, buf)
+        }
+        _ => (OUT_OF_BOUNDS, Vec::new(), Vec::new()),
+    };
+    record(host, || Call { name, args: args.to_vec(), inputs, output, result: Some(result) });
+    result
+}
+
+fn range(data: &[u8], (ptr, len): (u32, u32)) -> Option<std::ops::Range<usize>> {
+    let start = ptr as usize;
+    let end = start.checked_add(len as usize)?;
+    (end <= data.len()).then_some(start..end)
+}
+
+/// The hook API under `env`, with the signatures `api` imports.
+fn linker(engine: &Engine) -> Linker<Host> {
+    let mut linker = Linker::new(engine);
+    let env = "env";
+    linker
+        .func_wrap(env, "_g", |mut caller: Caller<'_, Host>, id: u32, maxiter: u32| {
+            let within = caller.data_mut().session.guard(id, maxiter);
+            let result = within.then_some(1);
+            record(caller.data_mut(), || Call {
+                name: "_g",
+                args: vec![id.into(), maxiter.into()],
+                inputs: Vec::new(),
+                output: Vec::new(),
+                result,
+            });
+            if within {
+                return Ok(1);
+            }
+            Err(halt(&mut caller, Exit::Rollback, b"guard violation".to_vec(), GUARD_VIOLATION))
+        })
+        .unwrap()
+        .func_wrap(env, "accept", |mut caller: Caller<'_, Host>, ptr: u32, len: u32, code: i64| {
+            Err::<i64, _>(end(&mut caller, "accept", Exit::Accept, (ptr, len), code))
+        })
+        .unwrap()
+        .func_wrap(env, "rollback", |mut caller: Caller<'_, Host>, ptr: u32, len: u32, code: i64| {
+            Err::<i64, _>(end(&mut caller, "rollback", Exit::Rollback, (ptr, len), code))
+        })
+        .unwrap()
+        .func_wrap(env, "otxn_type", |mut caller: Caller<'_, Host>| {
+            call(&mut caller, "otxn_type", &[], (0, 0), [], |s, _, []| s.otxn_type())
+        })
+        .unwrap()
+        .func_wrap(env, "otxn_field", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, field: u32| {
+            call(&mut caller, "otxn_field", &[field.into()], (wptr, wlen), [], |s, buf, []| s.otxn_field(buf, field))
+        })
+        .unwrap()
+        .func_wrap(env, "otxn_id", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, _flags: u32| {
+            call(&mut caller, "otxn_id", &[], (wptr, wlen), [], |s, buf, []| s.otxn_id(buf))
+        })
+        .unwrap()
+        .func_wrap(env, "hook_account", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32| {
+            call(&mut caller, "hook_account", &[], (wptr, wlen), [], |s, buf, []| s.hook_account(buf))
+        })
+        .unwrap()
+        .func_wrap(env, "hook_param", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, rptr: u32, rlen: u32| {
+            call(&mut caller, "hook_param", &[], (wptr, wlen), [(rptr, rlen)], |s, buf, [name]| s.hook_param(buf, name))
+        })
+        .unwrap()
+        .func_wrap(env, "otxn_param", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, rptr: u32, rlen: u32| {
+            call(&mut caller, "otxn_param", &[], (wptr, wlen), [(rptr, rlen)], |s, buf, [name]| s.otxn_param(buf, name))
+        })
+        .unwrap()
+        .func_wrap(env, "state", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, kptr: u32, klen: u32| {
+            call(&mut caller, "state", &[], (wptr, wlen), [(kptr, klen)], |s, buf, [key]| s.state(buf, key))
+        })
+        .unwrap()
+        .func_wrap(env, "state_set", |mut caller: Caller<'_, Host>, rptr: u32, rlen: u32, kptr: u32, klen: u32| {
+            let reads = [(rptr, rlen), (kptr, klen)];
+            call(&mut caller, "state_set", &[], (0, 0), reads, |s, _, [data, key]| s.state_set(data, key))
+        })
+        .unwrap()
+        .func_wrap(env, "ledger_seq", |mut caller: Caller<'_, Host>| {
+            call(&mut caller, "ledger_seq", &[], (0, 0), [], |s, _, []| s.ledger_seq())
+        })
+        .unwrap()
+        .func_wrap(
+            env,
+            "util_verify",
+            |mut caller: Caller<'_, Host>, dptr: u32, dlen: u32, sptr: u32, slen: u32, kptr: u32, klen: u32| {
+                let reads = [(dptr, dlen), (sptr, slen), (kptr, klen)];
+                call(&mut caller, "util_verify", &[], (0, 0), reads, |s, _, [data, signature, key]| {
+                    s.util_verify(data, signature, key)
+                })
+            },
+        )
+        .unwrap()
+        .func_wrap(env, "util_sha512h", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, rptr: u32, rlen: u32| {
+            call(&mut caller, "util_sha512h", &[], (wptr, wlen), [(rptr, rlen)], |s, hash, [data]| s.util_sha512h(hash, data))
+        })
+        .unwrap()
+        .func_wrap(env, "slot_set", |mut caller: Caller<'_, Host>, rptr: u32, rlen: u32, slot_no: u32| {
+            call(&mut caller, "slot_set", &[slot_no.into()], (0, 0), [(rptr, rlen)], |s, _, [keylet]| s.slot_set(keylet, slot_no))
+        })
+        .unwrap()
+        .func_wrap(env, "slot_subfield", |mut caller: Caller<'_, Host>, parent: u32, field: u32, slot_no: u32| {
+            let args = [parent.into(), field.into(), slot_no.into()];
+            call(&mut caller, "slot_subfield", &args, (0, 0), [], |s, _, []| s.slot_subfield(parent, field, slot_no))
+        })
+        .unwrap()
+        .func_wrap(env, "slot_subarray", |mut caller: Caller<'_, Host>, parent: u32, index: u32, slot_no: u32| {
+            let args = [parent.into(), index.into(), slot_no.into()];
+            call(&mut caller, "slot_subarray", &args, (0, 0), [], |s, _, []| s.slot_subarray(parent, index, slot_no))
+        })
+        .unwrap()
+        .func_wrap(env, "slot_count", |mut caller: Caller<'_, Host>, slot_no: u32| {
+            call(&mut caller, "slot_count", &[slot_no.into()], (0, 0), [], |s, _, []| s.slot_count(slot_no))
+        })
+        .unwrap()
+        .func_wrap(env, "slot", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, slot_no: u32| {
+            call(&mut caller, "slot", &[slot_no.into()], (wptr, wlen), [], |s, buf, []| s.slot(buf, slot_no))
+        })
+        .unwrap()
+        .func_wrap(env, "etxn_reserve", |mut caller: Caller<'_, Host>, count: u32| {
+            call(&mut caller, "etxn_reserve", &[count.into()], (0, 0), [], |s, _, []| s.etxn_reserve(count))
+        })
+        .unwrap()
+        .func_wrap(env, "etxn_details", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32| {
+            call(&mut caller, "etxn_details", &[], (wptr, wlen), [], |s, buf, []| s.etxn_details(buf))
+        })
+        .unwrap()
+        .func_wrap(env, "etxn_fee_base", |mut caller: Caller<'_, Host>, rptr: u32, rlen: u32| {
+            call(&mut caller, "etxn_fee_base", &[], (0, 0), [(rptr, rlen)], |s, _, [blob]| s.etxn_fee_base(blob))
+        })
+        .unwrap()
+        .func_wrap(env, "emit", |mut caller: Caller<'_, Host>, wptr: u32, wlen: u32, rptr: u32, rlen: u32| {
+            call(&mut caller, "emit", &[], (wptr, wlen), [(rptr, rlen)], |s, hash, [blob]| s.emit(hash, blob))
+        })
+        .unwrap()
+        .func_wrap(
+            env,
+            "trace",
+            |mut caller: Caller<'_, Host>, mptr: u32, mlen: u32, dptr: u32, dlen: u32, as_hex: u32| {
+                let reads = [(mptr, mlen), (dptr, dlen)];
+                call(&mut caller, "trace", &[as_hex.into()], (0, 0), reads, |s, _, [msg, data]| {
+                    s.trace(msg, data, as_hex != 0);
+                    0
+                })
+            },
+        )
+        .unwrap();
+    linker
+}
+
+/// `accept` or `rollback` with the message at `message`, which reads as
+/// empty if it lies outside guest memory.
+fn end(caller: &mut Caller<'_, Host>, name: &'static str, exit: Exit, message: (u32, u32), code: i64) -> wasmi::Error {
+    let text = match caller.get_export("memory").and_then(Extern::into_memory) {
+        Some(memory) => {
+            let data = memory.data(&*caller);
+            range(data, message).map(|message| data[message].to_vec()).unwrap_or_default()
+        }
+        None => Vec::new(),
+    };
+    let inputs = vec![text.clone()];
+    record(caller.data_mut(), || Call { name, args: vec![code], inputs, output: Vec::new(), result: None });
+    halt(caller, exit, text, code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use creature_crafter_hook::api::{sfAccount, ttINVOKE, ttPAYMENT};
+    use creature_crafter_hook::mock::state_key;
+
+    const HOOK_ACCOUNT: [u8; 20] = [0xE6; 20];
+
+    fn hook(body: &str) -> Hook {
+        let source = format!(
+            r#"(module
+                (import "env" "_g" (func $g (param i32 i32) (result i32)))
+                (import "env" "accept" (func $accept (param i32 i32 i64) (result i64)))
+                (import "env" "rollback" (func $rollback (param i32 i32 i64) (result i64)))
+                (import "env" "otxn_type" (func $otxn_type (result i64)))
+                (import "env" "otxn_field" (func $otxn_field (param i32 i32 i32) (result i64)))
+                (import "env" "state" (func $state (param i32 i32 i32 i32) (result i64)))
+                (import "env" "state_set" (func $state_set (param i32 i32 i32 i32) (result i64)))
+                (import "env" "etxn_reserve" (func $etxn_reserve (param i32) (result i64)))
+                (import "env" "emit" (func $emit (param i32 i32 i32 i32) (result i64)))
+                (import "env" "trace" (func $trace (param i32 i32 i32 i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "done")
+                (data (i32.const 16) "KEY")
+                {body})"#
+        );
+        Hook::new(&wat::parse_str(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn reports_accept_and_rollback() {
+        let accepting = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (drop (call $accept (i32.const 0) (i32.const 4) (call $otxn_type))) (i64.const 0))"#,
+        );
+        let outcome = accepting.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttPAYMENT)).unwrap();
+        assert_eq!((outcome.exit, outcome.code, outcome.message.as_str()), (Exit::Accept, ttPAYMENT, "done"));
+
+        let rolling_back = hook(
+            r#"(func (export "cbak") (param i32) (result i64)
+                (drop (call $rollback (i32.const 0) (i32.const 4) (i64.extend_i32_u (local.get 0)))) (i64.const 0))"#,
+        );
+        let outcome = rolling_back.callback(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE), 1).unwrap();
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, 1));
+        assert!(matches!(rolling_back.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)), Err(Error::MissingExport("hook"))));
+    }
+
+    #[test]
+    fn reads_fields_and_writes_state() {
+        // Stores the sender under "KEY" and traces it
+        let hook = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (drop (call $otxn_field (i32.const 32) (i32.const 20) (i32.const 0x80001)))
+                (drop (call $state_set (i32.const 32) (i32.const 20) (i32.const 16) (i32.const 3)))
+                (drop (call $trace (i32.const 16) (i32.const 3) (i32.const 32) (i32.const 20) (i32.const 0)))
+                (drop (call $accept (i32.const 0) (i32.const 0) (i64.const 0))) (i64.const 0))"#,
+        );
+        assert_eq!(sfAccount, 0x80001);
+        let tx = Tx::new(ttINVOKE).field(sfAccount, &[0x01; 20]);
+        let outcome = hook.run(&Mock::new(HOOK_ACCOUNT), &tx).unwrap();
+        assert_eq!(outcome.state.get(&state_key(b"KEY")), Some(&vec![0x01; 20]));
+        assert_eq!(outcome.traces, vec![("KEY".to_string(), vec![0x01; 20])]);
+    }
+
+    #[test]
+    fn traces_every_host_call() {
+        let hook = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (drop (call $otxn_field (i32.const 32) (i32.const 20) (i32.const 0x80001)))
+                (drop (call $state_set (i32.const 32) (i32.const 20) (i32.const 16) (i32.const 3)))
+                (drop (call $rollback (i32.const 0) (i32.const 4) (i64.const 7))) (i64.const 0))"#,
+        );
+        let tx = Tx::new(ttINVOKE).field(sfAccount, &[0x01; 20]);
+        let (outcome, calls) = hook.run_traced(&Mock::new(HOOK_ACCOUNT), &tx);
+        assert_eq!(outcome.unwrap().code, 7);
+        let call = |name, args: &[i64], inputs: &[&[u8]], output: &[u8], result| Call {
+            name,
+            args: args.to_vec(),
+            inputs: inputs.iter().map(|input| input.to_vec()).collect(),
+            output: output.to_vec(),
+            result,
+        };
+        assert_eq!(
+            calls,
+            [
+                call("otxn_field", &[sfAccount as i64], &[], &[0x01; 20], Some(20)),
+                call("state_set", &[], &[&[0x01; 20], b"KEY"], &[], Some(20)),
+                call("rollback", &[7], &[b"done"], &[], None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rolls_back_on_guard_violation() {
+        let hook = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (loop $spin (drop (call $g (i32.const 1) (i32.const 10))) (br $spin)) (i64.const 0))"#,
+        );
+        let outcome = hook.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)).unwrap();
+        assert_eq!((outcome.exit, outcome.code), (Exit::Rollback, GUARD_VIOLATION));
+    }
+
+    #[test]
+    fn collects_emitted_transactions() {
+        let hook = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (drop (call $etxn_reserve (i32.const 1)))
+                (drop (call $emit (i32.const 64) (i32.const 32) (i32.const 0) (i32.const 4)))
+                (drop (call $accept (i32.const 0) (i32.const 0) (call $emit (i32.const 64) (i32.const 32) (i32.const 0) (i32.const 4))))
+                (i64.const 0))"#,
+        );
+        let outcome = hook.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)).unwrap();
+        assert_eq!(outcome.emitted, vec![b"done".to_vec()]);
+        assert_eq!(outcome.code, creature_crafter_hook::api::TOO_MANY_EMITTED_TXN);
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_buffers() {
+        let hook = hook(
+            r#"(func (export "hook") (param i32) (result i64)
+                (drop (call $accept (i32.const 0) (i32.const 0)
+                    (call $otxn_field (i32.const 0xFFF0) (i32.const 20) (i32.const 0x80001))))
+                (i64.const 0))"#,
+        );
+        let tx = Tx::new(ttINVOKE).field(sfAccount, &[0x01; 20]);
+        assert_eq!(hook.run(&Mock::new(HOOK_ACCOUNT), &tx).unwrap().code, OUT_OF_BOUNDS);
+    }
+
+    #[test]
+    fn reports_traps_and_plain_returns() {
+        let trapping = hook(r#"(func (export "hook") (param i32) (result i64) unreachable)"#);
+        assert!(matches!(trapping.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)), Err(Error::Trap(_))));
+        let spinning = hook(r#"(func (export "hook") (param i32) (result i64) (loop $spin (br $spin)) (i64.const 0))"#);
+        assert!(matches!(spinning.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)), Err(Error::Trap(_))));
+        let returning = hook(r#"(func (export "hook") (param i32) (result i64) (i64.const 7))"#);
+        assert!(matches!(returning.run(&Mock::new(HOOK_ACCOUNT), &Tx::new(ttINVOKE)), Err(Error::Returned(7))));
+    }
+}
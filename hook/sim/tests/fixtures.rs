@@ -0,0 +1,91 @@
+//! Runs every fixture under `hook/fixtures/` through the simulator against
+//! the hooks' release wasm; see `hook_sim::fixture` for the format. The test
+//! builds the hooks itself first, so fixtures always run against the current
+//! source; it needs the `wasm32-unknown-unknown` target installed, and a
+//! failed build or a fixture naming a hook that does not exist fails it.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use creature_crafter_hook::HOOKS;
+use hook_sim::fixture::Fixture;
+use hook_sim::Hook;
+
+fn workspace() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+/// Where the release wasm is built, honouring `CARGO_TARGET_DIR`.
+fn wasm_dir() -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| workspace().join("target"), PathBuf::from);
+    target.join("wasm32-unknown-unknown/release")
+}
+
+/// Release-builds every hook in [`HOOKS`] for wasm, with the cargo running
+/// this test.
+fn build_hooks() {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut build = Command::new(cargo);
+    build.current_dir(workspace()).args(["build", "--release", "--target", "wasm32-unknown-unknown"]);
+    for hook in HOOKS {
+        build.args(["-p", &format!("{}-hook", hook.name.replace('_', "-"))]);
+    }
+    let status = build.status().unwrap_or_else(|e| panic!("running {build:?}: {e}"));
+    assert!(status.success(), "building the hooks failed ({status}); is the wasm32-unknown-unknown target installed?");
+}
+
+/// Every `.json` file under `dir`, in path order.
+fn fixture_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            fixture_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn fixtures() {
+    let root = workspace().join("fixtures");
+    let mut files = Vec::new();
+    fixture_files(&root, &mut files);
+    assert!(!files.is_empty(), "no fixtures under {}", root.display());
+    build_hooks();
+
+    let mut hooks: BTreeMap<String, Result<Hook, String>> = BTreeMap::new();
+    let mut failures = Vec::new();
+    for file in &files {
+        let name = file.strip_prefix(&root).unwrap().display().to_string();
+        let fixture = match std::fs::read_to_string(file).map_err(|e| e.to_string()).and_then(|text| Fixture::parse(&text)) {
+            Ok(fixture) => fixture,
+            Err(e) => {
+                failures.push(format!("{name}: {e}"));
+                continue;
+            }
+        };
+        let wasm = fixture.wasm_name();
+        let hook = hooks.entry(wasm.clone()).or_insert_with(|| {
+            let path = wasm_dir().join(&wasm);
+            let bytes = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+            Hook::new(&bytes).map_err(|e| format!("{wasm}: {e}"))
+        });
+        let hook = match hook {
+            Ok(hook) => hook,
+            Err(e) => {
+                failures.push(format!("{name}: {e}"));
+                continue;
+            }
+        };
+        match fixture.run(hook) {
+            Ok(mismatches) if mismatches.is_empty() => {}
+            Ok(mismatches) => failures.push(format!("{name}: {}\n    {}", fixture.description, mismatches.join("\n    "))),
+            Err(e) => failures.push(format!("{name}: {e}")),
+        }
+    }
+
+    assert!(failures.is_empty(), "{} fixtures failed:\n{}", failures.len(), failures.join("\n"));
+}
@@ -0,0 +1,338 @@
+//! Static checks on a built hook `.wasm`, mirroring what SetHook validates:
+//! only hook API imports, exactly `hook` (and optionally `cbak`) exported,
+//! a `_g` guard at the head of every loop, and a bounded worst-case
+//! instruction count.
+//!
+//! Like SetHook, each function is costed on its own: every instruction
+//! counts once and a call counts as one instruction. A guard's `maxiter`
+//! caps its loop for the whole run, so a loop body counts `maxiter` times,
+//! divided by the `maxiter` of the enclosing loop when nested. Every
+//! function must fit the instruction budget.
+
+use std::collections::HashMap;
+
+use wasmparser::{BinaryReaderError, ExternalKind, KnownCustom, Name, Operator, Parser, Payload, TypeRef};
+
+/// Functions rippled's hook API provides, all imported from `env`.
+pub const HOOK_API: &[&str] = &[
+    "_g", "accept", "rollback",
+    "util_raddr", "util_accid", "util_verify", "util_sha512h", "util_keylet",
+    "sto_validate", "sto_subfield", "sto_subarray", "sto_emplace", "sto_erase",
+    "etxn_burden", "etxn_details", "etxn_fee_base", "etxn_nonce", "etxn_reserve", "etxn_generation", "emit",
+    "float_set", "float_multiply", "float_mulratio", "float_negate", "float_compare", "float_sum",
+    "float_sto", "float_sto_set", "float_invert", "float_divide", "float_one", "float_mantissa",
+    "float_sign", "float_int", "float_log", "float_root",
+    "fee_base", "ledger_seq", "ledger_last_hash", "ledger_last_time", "ledger_nonce", "ledger_keylet",
+    "hook_account", "hook_hash", "hook_param", "hook_param_set", "hook_skip", "hook_pos", "hook_again",
+    "otxn_burden", "otxn_field", "otxn_generation", "otxn_id", "otxn_type", "otxn_slot", "otxn_param",
+    "slot", "slot_clear", "slot_count", "slot_set", "slot_size", "slot_subarray", "slot_subfield",
+    "slot_type", "slot_float",
+    "state", "state_set", "state_foreign", "state_foreign_set",
+    "trace", "trace_num", "trace_float",
+];
+
+/// Limits a hook must stay within.
+#[derive(Clone, Copy, Debug)]
+pub struct Budget {
+    /// Largest acceptable `.wasm`, in bytes.
+    pub max_size: usize,
+    /// Largest acceptable worst-case instruction count of any function.
+    pub max_instructions: u64,
+}
+
+impl Default for Budget {
+    fn default() -> Budget {
+        Budget { max_size: 64 * 1024, max_instructions: 0xFFFF }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub size: usize,
+    /// Worst-case instruction count of each exported function; `u64::MAX`
+    /// if it is unbounded.
+    pub instructions: Vec<(String, u64)>,
+    /// The costliest function and its worst-case instruction count.
+    pub costliest: Option<(String, u64)>,
+    pub violations: Vec<String>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Everything the checks need from the module.
+#[derive(Default)]
+struct Module<'a> {
+    imported_functions: u32,
+    guard: Option<u32>,
+    bodies: Vec<wasmparser::FunctionBody<'a>>,
+    names: HashMap<u32, String>,
+}
+
+impl Module<'_> {
+    fn describe(&self, function: u32) -> String {
+        match self.names.get(&function) {
+            Some(name) => format!("function {function} ({name})"),
+            None => format!("function {function}"),
+        }
+    }
+}
+
+pub fn check(wasm: &[u8], budget: &Budget) -> Result<Report, BinaryReaderError> {
+    let mut report = Report { size: wasm.len(), ..Report::default() };
+    let mut module = Module::default();
+    let mut exports = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    let import = import?;
+                    let TypeRef::Func(_) = import.ty else {
+                        report.violations.push(format!("imports {}.{}, which is not a function", import.module, import.name));
+                        continue;
+                    };
+                    if import.module != "env" || !HOOK_API.contains(&import.name) {
+                        report.violations.push(format!("imports {}.{}, which is not a hook API function", import.module, import.name));
+                    }
+                    if import.module == "env" && import.name == "_g" {
+                        module.guard = Some(module.imported_functions);
+                    }
+                    module.imported_functions += 1;
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    if export.kind == ExternalKind::Func {
+                        exports.push((export.name.to_string(), export.index));
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => module.bodies.push(body),
+            Payload::CustomSection(reader) => {
+                if let KnownCustom::Name(names) = reader.as_known() {
+                    for name in names {
+                        if let Ok(Name::Function(map)) = name {
+                            for naming in map.into_iter().flatten() {
+                                module.names.insert(naming.index, naming.name.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !exports.iter().any(|(name, _)| name == "hook") {
+        report.violations.push("does not export `hook`".to_string());
+    }
+    for (name, _) in &exports {
+        if name != "hook" && name != "cbak" {
+            report.violations.push(format!("exports function `{name}`; hooks may only export `hook` and `cbak`"));
+        }
+    }
+    if budget.max_size < report.size {
+        report.violations.push(format!("is {} bytes, over the {} byte budget", report.size, budget.max_size));
+    }
+
+    let mut costs = HashMap::new();
+    for (i, body) in module.bodies.iter().enumerate() {
+        let function = module.imported_functions + i as u32;
+        let cost = function_cost(&module, function, body, &mut report.violations)?;
+        if cost > budget.max_instructions {
+            report.violations.push(format!(
+                "{} may run {} instructions, over the {} budget",
+                module.describe(function),
+                describe_cost(cost),
+                budget.max_instructions
+            ));
+        }
+        if report.costliest.as_ref().is_none_or(|(_, max)| *max < cost) {
+            report.costliest = Some((module.describe(function), cost));
+        }
+        costs.insert(function, cost);
+    }
+    for (name, index) in exports {
+        report.instructions.push((name, costs.get(&index).copied().unwrap_or(0)));
+    }
+    Ok(report)
+}
+
+pub fn describe_cost(cost: u64) -> String {
+    if cost == u64::MAX {
+        "unbounded".to_string()
+    } else {
+        cost.to_string()
+    }
+}
+
+/// An open block; loop bodies run `iterations` times per entry (`None` if
+/// unguarded). `maxiter` is the guard of the innermost loop enclosing or
+/// being this block, 1 outside loops.
+struct Frame {
+    cost: u64,
+    iterations: Option<u64>,
+    maxiter: u64,
+}
+
+/// Progress through the `i32.const id; i32.const maxiter; call $_g` a loop
+/// must start with.
+enum GuardScan {
+    None,
+    Id,
+    MaxIter,
+    Call(u64),
+}
+
+/// Worst-case instruction count of `function`, reporting loops that do not
+/// start with a guard (and so count as unbounded) to `violations`.
+fn function_cost(
+    module: &Module,
+    function: u32,
+    body: &wasmparser::FunctionBody,
+    violations: &mut Vec<String>,
+) -> Result<u64, BinaryReaderError> {
+    let mut reader = body.get_operators_reader()?;
+    let mut frames = vec![Frame { cost: 0, iterations: Some(1), maxiter: 1 }];
+    let mut scan = GuardScan::None;
+    let mut loop_offset = 0;
+
+    while !reader.eof() {
+        let (op, offset) = reader.read_with_offset()?;
+        scan = match (scan, &op) {
+            (GuardScan::None, _) => GuardScan::None,
+            (GuardScan::Id, Operator::I32Const { .. }) => GuardScan::MaxIter,
+            (GuardScan::MaxIter, Operator::I32Const { value }) => GuardScan::Call(*value as u32 as u64),
+            (GuardScan::Call(maxiter), Operator::Call { function_index }) if Some(*function_index) == module.guard => {
+                let outer = frames[frames.len() - 2].maxiter;
+                let top = frames.last_mut().unwrap();
+                top.iterations = Some(maxiter.div_ceil(outer).max(1));
+                top.maxiter = maxiter.max(1);
+                GuardScan::None
+            }
+            _ => {
+                violations.push(format!(
+                    "{}: loop at offset {loop_offset:#x} does not start with a _g guard call",
+                    module.describe(function)
+                ));
+                GuardScan::None
+            }
+        };
+
+        let top = frames.last_mut().unwrap();
+        top.cost = top.cost.saturating_add(1);
+        match op {
+            Operator::Block { .. } | Operator::If { .. } => {
+                let maxiter = top.maxiter;
+                frames.push(Frame { cost: 0, iterations: Some(1), maxiter });
+            }
+            Operator::Loop { .. } => {
+                let maxiter = top.maxiter;
+                frames.push(Frame { cost: 0, iterations: None, maxiter });
+                scan = GuardScan::Id;
+                loop_offset = offset;
+            }
+            Operator::End => {
+                let frame = frames.pop().unwrap();
+                let cost = frame.iterations.map_or(u64::MAX, |n| frame.cost.saturating_mul(n));
+                match frames.last_mut() {
+                    Some(parent) => parent.cost = parent.cost.saturating_add(cost),
+                    None => return Ok(cost),
+                }
+            }
+            Operator::CallIndirect { .. } | Operator::ReturnCallIndirect { .. } => {
+                violations.push(format!(
+                    "{}: indirect call at offset {offset:#x} cannot be checked",
+                    module.describe(function)
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(frames.first().map_or(0, |frame| frame.cost))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_wat(source: &str) -> Report {
+        check(&wat::parse_str(source).unwrap(), &Budget::default()).unwrap()
+    }
+
+    const GUARDED: &str = r#"(module
+        (import "env" "_g" (func $g (param i32 i32) (result i32)))
+        (import "env" "accept" (func $accept (param i32 i32 i64) (result i64)))
+        (memory (export "memory") 1)
+        (func (export "hook") (param i32) (result i64)
+            (local i32)
+            (loop $l
+                (drop (call $g (i32.const 1) (i32.const 10)))
+                (local.set 1 (i32.add (local.get 1) (i32.const 1)))
+                (br_if $l (i32.lt_u (local.get 1) (i32.const 10))))
+            (call $accept (i32.const 0) (i32.const 0) (i64.const 0))))"#;
+
+    #[test]
+    fn accepts_a_guarded_hook() {
+        let report = check_wat(GUARDED);
+        assert!(report.passed(), "{:?}", report.violations);
+        let (name, cost) = &report.instructions[0];
+        assert_eq!(name, "hook");
+        assert!(*cost > 100 && *cost < 200, "{cost}");
+    }
+
+    #[test]
+    fn rejects_unguarded_loops() {
+        let report = check_wat(
+            r#"(module
+                (func (export "hook") (param i32) (result i64)
+                    (loop $l (br $l))
+                    (i64.const 0)))"#,
+        );
+        assert!(report.violations.iter().any(|v| v.contains("does not start with a _g guard")));
+        assert!(report.violations.iter().any(|v| v.contains("may run unbounded instructions")));
+    }
+
+    #[test]
+    fn rejects_foreign_imports_and_exports() {
+        let report = check_wat(
+            r#"(module
+                (import "env" "memcpy" (func (param i32 i32 i32) (result i32)))
+                (import "wasi" "accept" (func (param i32 i32 i64) (result i64)))
+                (func (export "cbak") (param i32) (result i64) (i64.const 0))
+                (func (export "helper") (result i32) (i32.const 0)))"#,
+        );
+        let violations = report.violations.join("\n");
+        assert!(violations.contains("env.memcpy"));
+        assert!(violations.contains("wasi.accept"));
+        assert!(violations.contains("does not export `hook`"));
+        assert!(violations.contains("`helper`"));
+    }
+
+    #[test]
+    fn counts_nested_guards_once_per_run() {
+        let nested = GUARDED.replace(
+            "(drop (call $g (i32.const 1) (i32.const 10)))",
+            "(drop (call $g (i32.const 1) (i32.const 10)))
+             (loop $inner (drop (call $g (i32.const 2) (i32.const 10000))))",
+        );
+        let report = check_wat(&nested);
+        let cost = report.instructions[0].1;
+        assert!(cost > 10_000 * 4 && cost < 2 * 10_000 * 4, "{cost}");
+        let budget = Budget { max_instructions: 10_000, ..Budget::default() };
+        let report = check(&wat::parse_str(&nested).unwrap(), &budget).unwrap();
+        assert!(report.violations.iter().any(|v| v.contains("over the 10000 budget")));
+    }
+
+    #[test]
+    fn enforces_size_budget() {
+        let wasm = wat::parse_str(GUARDED).unwrap();
+        let report = check(&wasm, &Budget { max_size: 16, ..Budget::default() }).unwrap();
+        assert!(report.violations.iter().any(|v| v.contains("byte budget")));
+    }
+}
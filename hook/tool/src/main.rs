@@ -0,0 +1,135 @@
+//! Host-side tooling for the hooks in this workspace.
+//!
+//! ```text
+//! hook-tool check [--max-size BYTES] [--max-instructions N] FILE.wasm...
+//! hook-tool set-hook MANIFEST.toml FILE.wasm
+//! hook-tool replay [--state STATE.json] [--objects OBJECTS.json] [--callback WHAT] MANIFEST.toml FILE.wasm TX.json
+//! ```
+//!
+//! `set-hook` prints `{"tx_blob": ..., "tx_json": ...}` for an unsigned
+//! SetHook transaction; see [`sethook`] for the manifest format. `replay`
+//! runs a transaction from rippled's `tx` through the hook offline and
+//! prints each host call it makes; see [`replay`].
+
+mod check;
+mod replay;
+mod sethook;
+
+use std::process::ExitCode;
+
+use check::Budget;
+
+const USAGE: &str = "usage: hook-tool check [--max-size BYTES] [--max-instructions N] FILE.wasm...
+       hook-tool set-hook MANIFEST.toml FILE.wasm
+       hook-tool replay [--state STATE.json] [--objects OBJECTS.json] [--callback WHAT] MANIFEST.toml FILE.wasm TX.json";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "check" => run_check(rest),
+        Some((command, rest)) if command == "set-hook" => run_set_hook(rest),
+        Some((command, rest)) if command == "replay" => run_replay(rest),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run_check(args: &[String]) -> Result<ExitCode, String> {
+    let mut budget = Budget::default();
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-size" => budget.max_size = number(args.next(), arg)?,
+            "--max-instructions" => budget.max_instructions = number(args.next(), arg)?,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}\n{USAGE}")),
+            file => files.push(file),
+        }
+    }
+    if files.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut failed = false;
+    for file in files {
+        let wasm = std::fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+        let report = check::check(&wasm, &budget).map_err(|e| format!("{file}: invalid wasm: {e}"))?;
+        println!("{file}: {} bytes (budget {})", report.size, budget.max_size);
+        for (export, cost) in &report.instructions {
+            let cost = check::describe_cost(*cost);
+            println!("  {export}: worst case {cost} instructions (budget {})", budget.max_instructions);
+        }
+        if let Some((function, cost)) = &report.costliest {
+            println!("  costliest: {function}, worst case {} instructions", check::describe_cost(*cost));
+        }
+        for violation in &report.violations {
+            println!("  error: {violation}");
+        }
+        failed |= !report.passed();
+    }
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+fn run_set_hook(args: &[String]) -> Result<ExitCode, String> {
+    let [manifest, file] = args else {
+        return Err(USAGE.to_string());
+    };
+    let text = std::fs::read_to_string(manifest).map_err(|e| format!("{manifest}: {e}"))?;
+    let manifest = sethook::parse_manifest(&text).map_err(|e| format!("{manifest}: {e}"))?;
+    let wasm = std::fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+
+    // rippled would reject the SetHook anyway; say why before anyone pays the fee
+    let report = check::check(&wasm, &Budget::default()).map_err(|e| format!("{file}: invalid wasm: {e}"))?;
+    if !report.passed() {
+        for violation in &report.violations {
+            eprintln!("{file}: error: {violation}");
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let built = sethook::build(&manifest, &wasm)?;
+    println!("{}", serde_json::to_string_pretty(&built.to_json()).expect("JSON values serialize"));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_replay(args: &[String]) -> Result<ExitCode, String> {
+    let (mut state, mut objects, mut callback) = (None, None, None);
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--state" => state = Some(read_json(args.next(), arg)?),
+            "--objects" => objects = Some(read_json(args.next(), arg)?),
+            "--callback" => callback = Some(number(args.next(), arg)?),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}\n{USAGE}")),
+            file => files.push(file),
+        }
+    }
+    let [manifest, file, tx] = files[..] else {
+        return Err(USAGE.to_string());
+    };
+    let text = std::fs::read_to_string(manifest).map_err(|e| format!("{manifest}: {e}"))?;
+    let manifest = sethook::parse_manifest(&text).map_err(|e| format!("{manifest}: {e}"))?;
+    let wasm = std::fs::read(file).map_err(|e| format!("{file}: {e}"))?;
+    let tx = read_json(Some(&tx.to_string()), "TX.json")?;
+
+    let replay = replay::replay(&manifest, &wasm, &tx, state.as_ref(), objects.as_ref(), callback)?;
+    println!("{}", replay::report(&replay, &manifest, &tx));
+    Ok(if replay.result.is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn read_json(file: Option<&String>, flag: &str) -> Result<serde_json::Value, String> {
+    let file = file.ok_or_else(|| format!("{flag} needs a file\n{USAGE}"))?;
+    let text = std::fs::read_to_string(file).map_err(|e| format!("{file}: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| format!("{file}: {e}"))
+}
+
+fn number<T: std::str::FromStr>(value: Option<&String>, flag: &str) -> Result<T, String> {
+    value.and_then(|v| v.parse().ok()).ok_or_else(|| format!("{flag} needs a number\n{USAGE}"))
+}
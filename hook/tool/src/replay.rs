@@ -0,0 +1,319 @@
+//! `hook-tool replay`: runs a transaction fetched from rippled through a
+//! built hook offline and prints every host call the hook made, for
+//! working out why it burned, paid or rolled back what it did.
+//!
+//! The hook account and parameters come from the hook's SetHook manifest
+//! (see [`sethook`](crate::sethook)). The transaction is `tx` output, or
+//! just its `result`; its `ledger_index` becomes the ledger sequence the
+//! hook sees. Optionally:
+//!
+//! - `--state` takes `account_namespace` output for the hook's namespace,
+//!   or a bare array of `HookState` entries, as the state before the
+//!   transaction.
+//! - `--objects` takes `ledger_entry` output, binary or JSON, or an array
+//!   of them, for the ledger objects the hook slots (an owner's NFToken
+//!   pages, say).
+//! - `--callback WHAT` runs `cbak` instead, for an emitted transaction that
+//!   was applied (0) or failed (1).
+
+use creature_crafter_hook::api::*;
+use creature_crafter_hook::error::HookError;
+use creature_crafter_hook::mock::field_of;
+use hook_codec::address::decode_account;
+use hook_codec::definitions::field_by_code;
+use hook_codec::hex;
+use hook_sim::{json, Call, Error, Exit, Hook, Mock, Outcome, Tx};
+use serde_json::Value;
+
+use crate::sethook::Manifest;
+
+/// A hook state entry: key, then data.
+type StateEntry = (Vec<u8>, Vec<u8>);
+
+/// A ledger object: keylet (entry type, then key), then serialized fields.
+type LedgerObject = ([u8; 34], Vec<u8>);
+
+/// What the hook saw and did.
+pub struct Replay {
+    pub calls: Vec<Call>,
+    pub result: Result<Outcome, Error>,
+}
+
+/// Replays `tx` through `wasm` installed as `manifest` describes.
+pub fn replay(
+    manifest: &Manifest,
+    wasm: &[u8],
+    tx: &Value,
+    state: Option<&Value>,
+    objects: Option<&Value>,
+    callback: Option<u32>,
+) -> Result<Replay, String> {
+    let hook = Hook::new(wasm).map_err(|e| e.to_string())?;
+    let mock = mock(manifest, tx, state, objects)?;
+    let tx: Tx = json::tx(tx)?;
+    let (result, calls) = match callback {
+        Some(what) => hook.callback_traced(&mock, &tx, what),
+        None => hook.run_traced(&mock, &tx),
+    };
+    Ok(Replay { calls, result })
+}
+
+fn mock(manifest: &Manifest, tx: &Value, state: Option<&Value>, objects: Option<&Value>) -> Result<Mock, String> {
+    let mut mock = Mock::new(decode_account(&manifest.account)?);
+    for (name, value) in &manifest.parameters {
+        mock = mock.param(name.as_bytes(), &hex::decode(value).map_err(|e| format!("parameter {name}: {e}"))?);
+    }
+    if let Some(seq) = result(tx).get("ledger_index").and_then(Value::as_u64) {
+        mock = mock.ledger_seq(u32::try_from(seq).map_err(|_| format!("ledger_index {seq} out of range"))?);
+    }
+    for (key, value) in state.map(state_entries).transpose()?.unwrap_or_default() {
+        mock = mock.state(&key, &value);
+    }
+    for (keylet, fields) in objects.map(ledger_objects).transpose()?.unwrap_or_default() {
+        mock = mock.object(keylet, &fields);
+    }
+    Ok(mock)
+}
+
+/// The `result` of an RPC response, or the value itself if it is bare.
+fn result(json: &Value) -> &Value {
+    json.get("result").unwrap_or(json)
+}
+
+/// `HookStateKey`/`HookStateData` pairs of a state dump.
+fn state_entries(json: &Value) -> Result<Vec<StateEntry>, String> {
+    let json = result(json);
+    let entries = json.get("namespace_entries").unwrap_or(json);
+    let entries = entries.as_array().ok_or("state: expected account_namespace output or an array of HookState entries")?;
+    entries
+        .iter()
+        .map(|entry| {
+            let field = |name| entry[name].as_str().ok_or_else(|| format!("state: entry without {name}"));
+            Ok((hex::decode(field("HookStateKey")?)?, hex::decode(field("HookStateData")?)?))
+        })
+        .collect()
+}
+
+/// Keylets and serialized fields of `ledger_entry` responses.
+fn ledger_objects(json: &Value) -> Result<Vec<LedgerObject>, String> {
+    let responses = match json {
+        Value::Array(responses) => responses.iter().collect(),
+        response => vec![response],
+    };
+    responses
+        .into_iter()
+        .map(|response| {
+            let response = result(response);
+            let index = response["index"].as_str().ok_or("objects: expected ledger_entry output")?;
+            let fields = match (response["node_binary"].as_str(), response.get("node")) {
+                (Some(binary), _) => hex::decode(binary)?,
+                (None, Some(node)) => hook_codec::encode(node).map_err(|e| format!("objects: {index}: {e}"))?,
+                (None, None) => return Err(format!("objects: {index} has neither node nor node_binary")),
+            };
+            let entry_type = field_of(&fields, sfLedgerEntryType).ok_or(format!("objects: {index} has no LedgerEntryType"))?;
+            let mut keylet = [0u8; 34];
+            keylet[..2].copy_from_slice(&entry_type);
+            keylet[2..].copy_from_slice(&hex::decode_array::<32>(index)?);
+            Ok((keylet, fields))
+        })
+        .collect()
+}
+
+/// The replay as text: one line per host call, then how the hook ended and
+/// what it left behind, then what the ledger recorded if `tx` has metadata.
+pub fn report(replay: &Replay, manifest: &Manifest, tx: &Value) -> String {
+    let mut lines: Vec<String> = replay.calls.iter().enumerate().map(|(i, call)| format!("{:>4}  {}", i + 1, describe_call(call))).collect();
+    match &replay.result {
+        Ok(outcome) => {
+            let exit = match outcome.exit {
+                Exit::Accept => "accept",
+                Exit::Rollback => "rollback",
+            };
+            lines.push(format!("{exit}, code {}: {:?}", describe_code(outcome.code), outcome.message));
+            for (key, value) in &outcome.state_writes {
+                lines.push(format!("state {} = {}", hex::encode(key), hex::encode(value)));
+            }
+            for blob in &outcome.emitted {
+                lines.push(format!("emit {}", hex::encode(blob)));
+            }
+        }
+        Err(e) => lines.push(format!("error: {e}")),
+    }
+    lines.extend(recorded(result(tx), &manifest.account));
+    lines.join("\n")
+}
+
+/// `HookExecutions` of `tx`'s metadata for the hook on `account`.
+fn recorded(tx: &Value, account: &str) -> Vec<String> {
+    let executions = tx["meta"]["HookExecutions"].as_array().map(Vec::as_slice).unwrap_or_default();
+    executions
+        .iter()
+        .map(|execution| &execution["HookExecution"])
+        .filter(|execution| execution["HookAccount"] == account)
+        .map(|execution| {
+            // The ledger keeps negative codes as their magnitude with the top bit set
+            let code = match execution["HookReturnCode"].as_str().map(|code| u64::from_str_radix(code, 16)) {
+                Some(Ok(code)) if code >> 63 == 1 => describe_code(-((code & !(1 << 63)) as i64)),
+                Some(Ok(code)) => describe_code(code as i64),
+                _ => execution["HookReturnCode"].to_string(),
+            };
+            let message = execution["HookReturnString"].as_str().and_then(|text| hex::decode(text).ok()).unwrap_or_default();
+            format!("on ledger: HookResult {}, code {code}: {:?}", execution["HookResult"], String::from_utf8_lossy(&message))
+        })
+        .collect()
+}
+
+/// `otxn_field(Amount) = 48 -> D4...`: arguments, then inputs, then the
+/// result and anything written back.
+fn describe_call(call: &Call) -> String {
+    let mut args: Vec<String> = call.args.iter().map(i64::to_string).collect();
+    match call.name {
+        "otxn_field" => args[0] = field_name(call.args[0]),
+        "slot_subfield" => args[1] = field_name(call.args[1]),
+        "accept" | "rollback" => args[0] = describe_code(call.args[0]),
+        _ => {}
+    }
+    args.extend(call.inputs.iter().map(|input| describe_bytes(input)));
+    let mut line = format!("{}({})", call.name, args.join(", "));
+    if let Some(result) = call.result {
+        line += &format!(" = {}", describe_result(result));
+    }
+    if !call.output.is_empty() {
+        line += &format!(" -> {}", describe_bytes(&call.output));
+    }
+    line
+}
+
+fn field_name(field: i64) -> String {
+    match u32::try_from(field).ok().and_then(field_by_code) {
+        Some(field) => field.name.to_string(),
+        None => format!("{:#x}", field),
+    }
+}
+
+/// Readable text in quotes, anything else in hex.
+fn describe_bytes(bytes: &[u8]) -> String {
+    if bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        format!("{:?}", String::from_utf8_lossy(bytes))
+    } else {
+        hex::encode(bytes)
+    }
+}
+
+fn describe_result(result: i64) -> String {
+    let name = match result {
+        OUT_OF_BOUNDS => "OUT_OF_BOUNDS",
+        INTERNAL_ERROR => "INTERNAL_ERROR",
+        TOO_BIG => "TOO_BIG",
+        TOO_SMALL => "TOO_SMALL",
+        DOESNT_EXIST => "DOESNT_EXIST",
+        NO_FREE_SLOTS => "NO_FREE_SLOTS",
+        INVALID_ARGUMENT => "INVALID_ARGUMENT",
+        ALREADY_SET => "ALREADY_SET",
+        PREREQUISITE_NOT_MET => "PREREQUISITE_NOT_MET",
+        TOO_MANY_EMITTED_TXN => "TOO_MANY_EMITTED_TXN",
+        NOT_AN_ARRAY => "NOT_AN_ARRAY",
+        NOT_AN_OBJECT => "NOT_AN_OBJECT",
+        _ => return result.to_string(),
+    };
+    format!("{result} {name}")
+}
+
+fn describe_code(code: i64) -> String {
+    match HookError::from_code(code) {
+        Some(error) => format!("{code} {error:?}"),
+        None if code == GUARD_VIOLATION => format!("{code} GUARD_VIOLATION"),
+        None => code.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sethook::parse_manifest;
+    use serde_json::json;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    fn manifest() -> Manifest {
+        let text = format!(
+            "account = \"{GENESIS}\"\nsequence = 1\nfee = \"10\"\nhook = \"burn\"\nnamespace = \"{}\"\n[parameters]\nBURN_BPS = \"0064\"",
+            "00".repeat(32)
+        );
+        parse_manifest(&text).unwrap()
+    }
+
+    #[test]
+    fn reads_state_dumps_and_ledger_objects() {
+        let dump = json!({ "result": { "namespace_entries": [
+            { "HookStateKey": "00".repeat(26) + "4255524E4544", "HookStateData": "0000000000000002", "LedgerEntryType": "HookState" },
+        ]}});
+        let entries = state_entries(&dump).unwrap();
+        assert_eq!(entries[0].0[26..], *b"BURNED");
+        assert_eq!(state_entries(&dump["result"]["namespace_entries"]).unwrap(), entries);
+        assert!(state_entries(&json!({ "result": {} })).is_err());
+
+        let page = json!({ "result": { "index": "AB".repeat(32), "node_binary": "1100502200000000" } });
+        let objects = ledger_objects(&json!([page])).unwrap();
+        assert_eq!(objects[0].0[..3], [0x00, 0x50, 0xAB]);
+        assert_eq!(objects[0].1, [0x11, 0x00, 0x50, 0x22, 0, 0, 0, 0]);
+        assert_eq!(ledger_objects(&page).unwrap(), objects);
+        let page = json!({ "result": { "index": "AB".repeat(32), "node": { "LedgerEntryType": "NFTokenPage", "Flags": 0 } } });
+        assert_eq!(ledger_objects(&page).unwrap(), objects);
+        assert!(ledger_objects(&json!({ "result": { "index": "AB".repeat(32) } })).is_err());
+        assert!(ledger_objects(&json!({ "result": { "index": "AB".repeat(32), "node": { "Bogus": 1 } } })).is_err());
+    }
+
+    #[test]
+    fn describes_calls() {
+        let call = Call {
+            name: "otxn_field",
+            args: vec![sfAmount as i64],
+            inputs: Vec::new(),
+            output: vec![0x40, 0, 0, 0, 0, 0, 0, 10],
+            result: Some(8),
+        };
+        assert_eq!(describe_call(&call), "otxn_field(Amount) = 8 -> 400000000000000A");
+        let call = Call { name: "hook_param", args: Vec::new(), inputs: vec![b"BURN_TO".to_vec()], output: Vec::new(), result: Some(DOESNT_EXIST) };
+        assert_eq!(describe_call(&call), "hook_param(\"BURN_TO\") = -5 DOESNT_EXIST");
+        let call = Call { name: "accept", args: vec![210], inputs: vec![b"Not a Spark payment".to_vec()], output: Vec::new(), result: None };
+        assert_eq!(describe_call(&call), "accept(210 NotSpark, \"Not a Spark payment\")");
+    }
+
+    #[test]
+    fn replays_with_parameters_and_ledger_metadata() {
+        // Accepts with the length of BURN_BPS as its code
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "hook_param" (func $hook_param (param i32 i32 i32 i32) (result i64)))
+                (import "env" "accept" (func $accept (param i32 i32 i64) (result i64)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "BURN_BPS")
+                (func (export "hook") (param i32) (result i64)
+                    (drop (call $accept (i32.const 0) (i32.const 0)
+                        (call $hook_param (i32.const 16) (i32.const 8) (i32.const 0) (i32.const 8))))
+                    (i64.const 0)))"#,
+        )
+        .unwrap();
+        let tx = json!({ "result": {
+            "TransactionType": "Invoke",
+            "Account": GENESIS,
+            "ledger_index": 12,
+            "meta": { "HookExecutions": [{ "HookExecution": {
+                "HookAccount": GENESIS, "HookResult": 3, "HookReturnCode": "8000000000000010", "HookReturnString": "",
+            }}]},
+        }});
+        let replay = replay(&manifest(), &wasm, &tx, None, None, None).unwrap();
+        assert_eq!(replay.result.as_ref().unwrap().code, 2);
+        assert_eq!(
+            report(&replay, &manifest(), &tx),
+            [
+                "   1  hook_param(\"BURN_BPS\") = 2 -> 0064",
+                "   2  accept(2, \"\")",
+                "accept, code 2: \"\"",
+                "on ledger: HookResult 3, code -16 GUARD_VIOLATION: \"\"",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! `hook-tool set-hook`: an unsigned SetHook transaction installing a
+//! compiled hook, built offline from the wasm and a TOML manifest:
+//!
+//! ```toml
+//! account = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh"
+//! sequence = 4
+//! fee = "2000000"            # drops
+//! hook = "burn"              # HookOn from the hook's declared triggers
+//! namespace = "<32 bytes of hex>"
+//! override = true           # replace a hook already at position 0
+//!
+//! [parameters]              # ASCII name = hex value
+//! MM_KEY = "ED..."
+//!
+//! [[grants]]
+//! hook_hash = "<32 bytes of hex>"
+//! authorize = "r..."         # optional
+//! ```
+//!
+//! Instead of `hook`, `hook_on` may give the mask as 32 bytes of hex.
+//!
+//! The result is the serialized blob and its JSON form, ready for a signer
+//! to fill in `SigningPubKey` and `TxnSignature`.
+
+use std::collections::BTreeMap;
+
+use creature_crafter_hook::amount::Amount;
+use creature_crafter_hook::api::*;
+use creature_crafter_hook::triggers::hook_on;
+use creature_crafter_hook::stobject::Writer;
+use creature_crafter_hook::HOOKS;
+use hook_codec::address::decode_account;
+use hook_codec::hex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Longest `HookParameterName` rippled accepts.
+pub const MAX_PARAMETER_NAME_LEN: usize = 32;
+/// Longest `HookParameterValue` rippled accepts.
+pub const MAX_PARAMETER_VALUE_LEN: usize = 256;
+/// Most `HookParameters` on one hook.
+pub const MAX_PARAMETERS: usize = 16;
+/// Most `HookGrants` on one hook.
+pub const MAX_GRANTS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub account: String,
+    pub sequence: u32,
+    /// Fee in drops, as a string the way rippled's JSON writes XRP amounts.
+    pub fee: String,
+    /// Only needed on networks with an ID above 1024.
+    pub network_id: Option<u32>,
+    /// Name of a hook in this workspace, whose triggers give `HookOn`.
+    pub hook: Option<String>,
+    pub hook_on: Option<String>,
+    pub namespace: String,
+    #[serde(default, rename = "override")]
+    pub replace: bool,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Grant {
+    pub hook_hash: String,
+    pub authorize: Option<String>,
+}
+
+/// A built SetHook transaction.
+#[derive(Debug)]
+pub struct SetHook {
+    pub blob: Vec<u8>,
+    pub json: Value,
+}
+
+impl SetHook {
+    /// `{"tx_blob": ..., "tx_json": ...}`, what a signer or `submit` takes.
+    pub fn to_json(&self) -> Value {
+        json!({ "tx_blob": hex::encode(&self.blob), "tx_json": self.json })
+    }
+}
+
+pub fn parse_manifest(text: &str) -> Result<Manifest, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Builds the SetHook transaction installing `wasm` as described by
+/// `manifest`.
+pub fn build(manifest: &Manifest, wasm: &[u8]) -> Result<SetHook, String> {
+    let account = decode_account(&manifest.account)?;
+    let fee: u64 = manifest.fee.parse().map_err(|_| format!("fee {:?} is not a number of drops", manifest.fee))?;
+    let hook_on = resolve_hook_on(manifest)?;
+    let namespace: [u8; 32] = hex::decode_array(&manifest.namespace).map_err(|e| format!("namespace: {e}"))?;
+    let flags = if manifest.replace { hsfOVERRIDE } else { 0 };
+
+    if manifest.parameters.len() > MAX_PARAMETERS {
+        return Err(format!("{} parameters, at most {MAX_PARAMETERS} allowed", manifest.parameters.len()));
+    }
+    let mut parameters = Vec::new();
+    for (name, value) in &manifest.parameters {
+        let value = hex::decode(value).map_err(|e| format!("parameter {name}: {e}"))?;
+        if name.is_empty() || name.len() > MAX_PARAMETER_NAME_LEN || !name.is_ascii() {
+            return Err(format!("parameter name {name:?} must be 1 to {MAX_PARAMETER_NAME_LEN} ASCII characters"));
+        }
+        if value.len() > MAX_PARAMETER_VALUE_LEN {
+            return Err(format!("parameter {name} is over {MAX_PARAMETER_VALUE_LEN} bytes"));
+        }
+        parameters.push((name.as_bytes(), value));
+    }
+
+    if manifest.grants.len() > MAX_GRANTS {
+        return Err(format!("{} grants, at most {MAX_GRANTS} allowed", manifest.grants.len()));
+    }
+    let mut grants = Vec::new();
+    for grant in &manifest.grants {
+        let hash: [u8; 32] = hex::decode_array(&grant.hook_hash).map_err(|e| format!("grant hook_hash: {e}"))?;
+        let authorize = grant.authorize.as_deref().map(decode_account).transpose()?;
+        grants.push((hash, authorize));
+    }
+
+    // Fields in canonical order: type code, then field code
+    let mut buf = vec![0u8; wasm.len() + 1024 + parameters.len() * 300 + grants.len() * 64];
+    let mut w = Writer::new(&mut buf);
+    w.u16(sfTransactionType, ttHOOK_SET as u16);
+    if let Some(network_id) = manifest.network_id {
+        w.u32(sfNetworkID, network_id);
+    }
+    w.u32(sfFlags, 0);
+    w.u32(sfSequence, manifest.sequence);
+    w.amount(sfFee, &Amount::Xrp(fee));
+    w.vl(sfSigningPubKey, &[]);
+    w.account(sfAccount, &account);
+
+    w.begin(sfHooks);
+    w.begin(sfHook);
+    w.u16(sfHookApiVersion, 0);
+    w.u32(sfFlags, flags);
+    w.hash256(sfHookOn, &hook_on);
+    w.hash256(sfHookNamespace, &namespace);
+    w.vl(sfCreateCode, wasm);
+    if !parameters.is_empty() {
+        w.begin(sfHookParameters);
+        for (name, value) in &parameters {
+            w.begin(sfHookParameter);
+            w.vl(sfHookParameterName, name);
+            w.vl(sfHookParameterValue, value);
+            w.end_object();
+        }
+        w.end_array();
+    }
+    if !grants.is_empty() {
+        w.begin(sfHookGrants);
+        for (hash, authorize) in &grants {
+            w.begin(sfHookGrant);
+            w.hash256(sfHookHash, hash);
+            if let Some(authorize) = authorize {
+                w.account(sfAuthorize, authorize);
+            }
+            w.end_object();
+        }
+        w.end_array();
+    }
+    w.end_object();
+    w.end_array();
+    let blob = w.as_bytes().to_vec();
+
+    let mut hook = json!({
+        "HookApiVersion": 0,
+        "Flags": flags,
+        "HookOn": hex::encode(&hook_on),
+        "HookNamespace": hex::encode(&namespace),
+        "CreateCode": hex::encode(wasm),
+    });
+    if !parameters.is_empty() {
+        hook["HookParameters"] = parameters
+            .iter()
+            .map(|(name, value)| {
+                json!({ "HookParameter": {
+                    "HookParameterName": hex::encode(name),
+                    "HookParameterValue": hex::encode(value),
+                }})
+            })
+            .collect();
+    }
+    if !grants.is_empty() {
+        hook["HookGrants"] = manifest
+            .grants
+            .iter()
+            .zip(&grants)
+            .map(|(grant, (hash, _))| {
+                let mut entry = json!({ "HookHash": hex::encode(hash) });
+                if let Some(authorize) = &grant.authorize {
+                    entry["Authorize"] = json!(authorize);
+                }
+                json!({ "HookGrant": entry })
+            })
+            .collect();
+    }
+    let mut tx = json!({
+        "TransactionType": "SetHook",
+        "Account": manifest.account,
+        "Flags": 0,
+        "Sequence": manifest.sequence,
+        "Fee": fee.to_string(),
+        "SigningPubKey": "",
+        "Hooks": [{ "Hook": hook }],
+    });
+    if let Some(network_id) = manifest.network_id {
+        tx["NetworkID"] = json!(network_id);
+    }
+    Ok(SetHook { blob, json: tx })
+}
+
+/// `HookOn` from the manifest: the declared triggers of `hook`, or the
+/// explicit `hook_on` mask.
+fn resolve_hook_on(manifest: &Manifest) -> Result<[u8; 32], String> {
+    match (&manifest.hook, &manifest.hook_on) {
+        (Some(name), None) => match HOOKS.iter().find(|info| info.name == name) {
+            Some(info) => Ok(hook_on(info.triggers)),
+            None => {
+                let names: Vec<_> = HOOKS.iter().map(|info| info.name).collect();
+                Err(format!("unknown hook {name:?}, expected one of {}", names.join(", ")))
+            }
+        },
+        (None, Some(mask)) => hex::decode_array(mask).map_err(|e| format!("hook_on: {e}")),
+        _ => Err("set exactly one of `hook` and `hook_on`".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+    const GENESIS_ID: &str = "B5F762798A53D543A014CAF8B297CFF8F2F937E8";
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn manifest(extra: &str) -> Manifest {
+        parse_manifest(&format!(
+            r#"
+            account = "{GENESIS}"
+            sequence = 4
+            fee = "2000000"
+            hook = "burn"
+            namespace = "{}"
+            {extra}
+            "#,
+            "AB".repeat(32),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn serializes_a_minimal_set_hook() {
+        let built = build(&manifest(""), WASM).unwrap();
+        let expected = [
+            "120016",                   // TransactionType 22
+            "2200000000240000000468",   // Flags 0, Sequence 4, Fee
+            "40000000001E8480",         // 2000000 drops
+            "7300",                     // empty SigningPubKey
+            "8114",                     // Account
+            GENESIS_ID,
+            "FB",                       // Hooks
+            "EE",                       // Hook
+            "10140000",                 // HookApiVersion 0
+            "2200000000",               // Flags 0
+            "5014",                     // HookOn: Payment and Invoke
+            &"FF".repeat(19),
+            "F7",
+            &"FF".repeat(9),
+            "BFFFFE",
+            "5020",                     // HookNamespace
+            &"AB".repeat(32),
+            "7B08",                     // CreateCode, 8 bytes
+            "0061736D01000000",
+            "E1F1",
+        ]
+        .concat();
+        assert_eq!(hex::encode(&built.blob), expected);
+        assert_eq!(built.json["TransactionType"], "SetHook");
+        assert_eq!(built.json["Hooks"][0]["Hook"]["CreateCode"], "0061736D01000000");
+        assert_eq!(built.to_json()["tx_blob"], expected);
+        // The JSON form is the same transaction
+        assert_eq!(hook_codec::encode(&built.json).unwrap(), built.blob);
+    }
+
+    #[test]
+    fn serializes_parameters_and_grants() {
+        let built = build(
+            &manifest(&format!(
+                r#"
+                network_id = 21337
+                override = true
+                [parameters]
+                MM_KEY = "0102"
+                [[grants]]
+                hook_hash = "{}"
+                authorize = "{GENESIS}"
+                "#,
+                "CD".repeat(32)
+            )),
+            WASM,
+        )
+        .unwrap();
+        let blob = hex::encode(&built.blob);
+        assert!(blob.starts_with("12001621000053592200000000"), "{blob}");
+        assert!(blob.contains("2200000001"));
+        let parameters = ["F013", "E017", "7018064D4D5F4B4559", "7019020102", "E1F1"].concat();
+        let grants = ["F014", "E018", "501F", &"CD".repeat(32), "8514", GENESIS_ID, "E1F1"].concat();
+        assert!(blob.ends_with(&format!("{parameters}{grants}E1F1")), "{blob}");
+        let hook = &built.json["Hooks"][0]["Hook"];
+        assert_eq!(hook["HookParameters"][0]["HookParameter"]["HookParameterName"], "4D4D5F4B4559");
+        assert_eq!(hook["HookGrants"][0]["HookGrant"]["Authorize"], GENESIS);
+        assert_eq!(built.json["NetworkID"], 21337);
+        assert_eq!(hook_codec::encode(&built.json).unwrap(), built.blob);
+    }
+
+    #[test]
+    fn rejects_bad_manifests() {
+        assert!(build(&manifest("[parameters]\nMM_KEY = \"xyz\""), WASM).is_err());
+        let long_name = format!("[parameters]\n{} = \"00\"", "N".repeat(33));
+        assert!(build(&manifest(&long_name), WASM).is_err());
+        assert!(parse_manifest("account = \"r\"\nunknown = 1").is_err());
+        let mut bad = manifest("");
+        bad.hook = Some("mint".into());
+        assert!(build(&bad, WASM).unwrap_err().contains("unknown hook"));
+        bad.hook = None;
+        bad.hook_on = Some("FF".into());
+        assert!(build(&bad, WASM).is_err());
+        bad.hook_on = Some("00".repeat(32));
+        assert!(build(&bad, WASM).is_ok());
+        bad.hook = Some("burn".into());
+        assert!(build(&bad, WASM).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+// Checks statsFromDNA against the reference values the hooks are tested
+// against, and against the hooks' own code: the dna-abi crate's wasm
+// export, which `npm test` builds first. Run from sdk/ with `npm test`.
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { DNA_LEN, statsFromDNA } from "./dna";
+
+const DNA_WASM =
+  "../hook/target/wasm32-unknown-unknown/release/creature_crafter_dna.wasm";
+
+interface DnaVector {
+  dna: string;
+  strength: number;
+  speed: number;
+  intelligence: number;
+  endurance: number;
+}
+
+test("matches the hook DNA vectors", () => {
+  const vectors: DnaVector[] = JSON.parse(
+    readFileSync("../hook/core/vectors/dna_stats.json", "utf8")
+  );
+  assert.ok(vectors.length > 0);
+  for (const { dna, strength, speed, intelligence, endurance } of vectors) {
+    assert.deepEqual(
+      statsFromDNA(dna),
+      { strength, speed, intelligence, endurance },
+      dna
+    );
+  }
+});
+
+test("rejects malformed DNA", () => {
+  for (const dna of [
+    "0123456789abcdef0123456789abcde",
+    "0123456789abcdef0123456789abcdeg",
+    "0123456789abcdef0123456789abcdef0",
+  ]) {
+    assert.equal(statsFromDNA(dna), null, dna);
+  }
+});
+
+test("agrees with the hooks' wasm export", () => {
+  const wasm = new WebAssembly.Instance(
+    new WebAssembly.Module(readFileSync(DNA_WASM))
+  );
+  const { memory, pet_dna_buffer, pet_stats } = wasm.exports as {
+    memory: WebAssembly.Memory;
+    pet_dna_buffer: () => number;
+    pet_stats: (dna: number, len: number) => bigint;
+  };
+  const buffer = pet_dna_buffer();
+  const packed = (dna: string) => {
+    new Uint8Array(memory.buffer, buffer, DNA_LEN).set(
+      Buffer.from(dna.padEnd(DNA_LEN, "0"), "latin1")
+    );
+    return pet_stats(buffer, Math.min(dna.length, DNA_LEN));
+  };
+
+  // Mixed-case DNA from a fixed xorshift sequence, plus malformed strings
+  let seed = 0x2545f491;
+  const next = () => {
+    seed ^= seed << 13;
+    seed ^= seed >>> 17;
+    seed ^= seed << 5;
+    return seed >>> 0;
+  };
+  const samples = ["", "0".repeat(DNA_LEN - 1), "g".repeat(DNA_LEN)];
+  for (let i = 0; i < 1000; i++) {
+    let dna = "";
+    while (dna.length < DNA_LEN) {
+      dna += next().toString(16).padStart(8, "0");
+    }
+    samples.push(i % 2 ? dna : dna.toUpperCase());
+  }
+
+  for (const dna of samples) {
+    const stats = statsFromDNA(dna);
+    const expected =
+      stats === null
+        ? -1n
+        : BigInt(
+            ((stats.strength << 24) |
+              (stats.speed << 16) |
+              (stats.intelligence << 8) |
+              stats.endurance) >>>
+              0
+          );
+    assert.equal(packed(dna), expected, dna);
+  }
+});
@@ -0,0 +1,39 @@
+/**
+ * Pet stats derived from DNA exactly as the hooks and the backend derive
+ * them (hook/core/src/dna.rs). Each stat comes from one 4-byte quarter of
+ * the 32-character hex DNA, in the order Strength, Speed, Intelligence,
+ * Endurance: the quarter is XORed with its index times the 32-bit golden
+ * ratio, run through the MurmurHash3 finalizer and reduced to 1..100.
+ */
+import type { PetStats } from "./index";
+
+/** Length of a hex DNA string: 16 bytes. */
+export const DNA_LEN = 32;
+
+const STAT_SALT = 0x9e3779b9;
+
+/** Stats of hex `dna`, or null unless it is exactly DNA_LEN hex characters. */
+export function statsFromDNA(dna: string): PetStats | null {
+  if (dna.length !== DNA_LEN || !/^[0-9a-fA-F]+$/.test(dna)) {
+    return null;
+  }
+  const stat = (index: number) => {
+    const word = parseInt(dna.slice(index * 8, index * 8 + 8), 16);
+    return 1 + (fmix32(word ^ Math.imul(index, STAT_SALT)) % 100);
+  };
+  return {
+    strength: stat(0),
+    speed: stat(1),
+    intelligence: stat(2),
+    endurance: stat(3),
+  };
+}
+
+/** The MurmurHash3 finalizer over an unsigned 32-bit word. */
+function fmix32(h: number): number {
+  h ^= h >>> 16;
+  h = Math.imul(h, 0x85ebca6b);
+  h ^= h >>> 13;
+  h = Math.imul(h, 0xc2b2ae35);
+  return (h ^ (h >>> 16)) >>> 0;
+}
@@ -0,0 +1,13 @@
+// Checks that HookReturnCode values read from metadata look up. Run from
+// sdk/ with `npm test`.
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { decodeHookReturnCode, describeHookError } from "./hookErrors";
+
+test("decodes sign-bit encoded return codes", () => {
+  assert.equal(decodeHookReturnCode("0"), 0);
+  assert.equal(decodeHookReturnCode("1F5"), 501);
+  assert.equal(decodeHookReturnCode("8000000000000001"), -1);
+  assert.equal(describeHookError(decodeHookReturnCode("8000000000000001")), "panic");
+  assert.throws(() => decodeHookReturnCode("not hex"));
+});
@@ -0,0 +1,97 @@
+/**
+ * Messages for the codes the game hooks roll back or accept with, as
+ * recorded in a transaction's HookReturnCode. Mirrors HookError in
+ * hook/core/src/error.rs; a hook test keeps the two in step.
+ *
+ * Metadata does not hold these codes as they are: rippled stores
+ * HookReturnCode as a UInt64 in hex with a negative code sign-bit
+ * encoded, so -1 reads "8000000000000001". Look codes from metadata up
+ * via decodeHookReturnCode.
+ */
+export const HOOK_ERRORS: Readonly<Record<number, string>> = {
+  "-1": "panic",
+  100: "Invalid ADMIN parameter",
+  101: "Only the admin can send admin commands",
+  102: "Admin commands must be multi-signed",
+  103: "Malformed admin command",
+  104: "Could not apply admin command",
+  105: "Hooks are paused",
+  201: "Invalid BURN_BPS parameter",
+  202: "Invalid BURN_ON parameter",
+  203: "Invalid BURN_TO parameter",
+  210: "Not a Spark payment",
+  211: "Spark issuance or redemption",
+  212: "Payment direction not burned",
+  213: "Exempt account",
+  214: "Burn below minimum",
+  215: "Payment to the burn account",
+  216: "Partial Spark payment not burned",
+  220: "Could not reserve burn",
+  221: "Could not emit burn",
+  222: "Could not record burn",
+  301: "Invalid EGG_PRICE parameter",
+  302: "Partial payments cannot buy eggs",
+  303: "Payment does not match egg price",
+  304: "Could not record pending egg",
+  305: "Could not reserve egg mint",
+  306: "Could not emit egg mint",
+  307: "Could not read buyer account",
+  308: "No failed egg mint to retry",
+  401: "Missing pet-hatch memo",
+  402: "Malformed hatch request",
+  403: "Not an egg from this shop",
+  404: "Egg already hatched",
+  405: "Sender does not own the egg",
+  406: "Could not record DNA",
+  501: "Missing pet-battle memo",
+  502: "Malformed battle request",
+  503: "A pet cannot fight itself",
+  504: "Pet has not hatched",
+  505: "DNA does not match the hatched pet",
+  506: "Sender does not own the challenger",
+  507: "Could not record battle",
+  508: "Sender does not own the opponent",
+  509: "No such open battle",
+  510: "Battle already accepted",
+  511: "Battle not accepted yet",
+  512: "Reveal does not match the commitment",
+  513: "Challenger cannot accept their own battle",
+  514: "Challenger can still reveal",
+  515: "Pet already has an open challenge",
+  516: "Only the challenger can cancel before the challenge expires",
+  601: "Missing spark-claim memo",
+  602: "Malformed claim",
+  603: "Claim is for another account",
+  604: "MM_KEY parameter not set",
+  605: "Invalid claim signature",
+  606: "Nothing left to claim",
+  607: "Could not record claim",
+  608: "Claim too large",
+  609: "Could not reserve payout",
+  610: "Could not emit payout",
+  611: "Could not record payout",
+};
+
+/**
+ * Short message for a hook return code, for telling users why a
+ * transaction was rejected
+ */
+export function describeHookError(code: number): string {
+  if (code === 0) {
+    return "OK";
+  }
+  return HOOK_ERRORS[code] ?? `Unknown hook error ${code}`;
+}
+
+/**
+ * Reads a HookReturnCode as transaction metadata records it: hex, with
+ * the top bit set and the magnitude below it for a negative code
+ */
+export function decodeHookReturnCode(field: string): number {
+  if (!/^[0-9a-fA-F]{1,16}$/.test(field)) {
+    throw new Error(`Bad HookReturnCode ${JSON.stringify(field)}`);
+  }
+  const raw = BigInt(`0x${field}`);
+  const magnitude = Number(raw & 0x7fffffffffffffffn);
+  return raw >> 63n ? -magnitude : magnitude;
+}
@@ -42,7 +42,7 @@ export declare class CreatureCrafterSDK {
   createTestWallet(): Promise<Wallet>;
   buyEgg(wallet: Wallet, eggShopAddress: string, priceXRP?: string): Promise<any>;
   getOwnedNFTs(address: string): Promise<NFT[]>;
-  mintNFT(issuerWallet: Wallet, uri: string, transferFee?: number, flags?: number): Promise<any>;
+  claimEggs(wallet: Wallet, eggShopAddress: string): Promise<any[]>;
   findBattle(address: string, petId: string, matchmakerUrl: string): Promise<BattleResult>;
   claimReward(wallet: Wallet, amount: number): Promise<any>;
   getBalance(address: string): Promise<string>;
@@ -50,4 +50,6 @@ export declare class CreatureCrafterSDK {
 
 export declare const sdk: CreatureCrafterSDK;
 
-export { Client, Wallet, xrpToDrops, dropsToXrp } from 'xrpl';
\ No newline at end of file
+export { Client, Wallet, xrpToDrops, dropsToXrp } from 'xrpl';
+export { HOOK_ERRORS, describeHookError, decodeHookReturnCode } from './hookErrors';
+export { DNA_LEN, statsFromDNA } from './dna';
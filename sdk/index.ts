@@ -9,7 +9,7 @@ import {
   Wallet,
   xrpToDrops,
   dropsToXrp,
-  NFTokenMint,
+  NFTokenAcceptOffer,
   AccountNFTs,
   Payment,
 } from "xrpl";
@@ -85,7 +85,9 @@ export class CreatureCrafterSDK {
   }
 
   /**
-   * Buy an egg NFT by sending XRP to the egg shop address
+   * Buy an egg NFT by sending XRP to the egg shop address. The egg-shop
+   * hook mints the egg in the same transaction and offers it to the buyer;
+   * claim it with claimEggs.
    */
   async buyEgg(
     wallet: Wallet,
@@ -132,33 +134,35 @@ export class CreatureCrafterSDK {
   }
 
   /**
-   * Mint an NFT (for server-side use)
+   * Accept the egg offers the egg-shop hook has made to this wallet
    */
-  async mintNFT(
-    issuerWallet: Wallet,
-    uri: string,
-    transferFee = 0,
-    flags = 8
-  ): Promise<any> {
+  async claimEggs(wallet: Wallet, eggShopAddress: string): Promise<any[]> {
     await this.connect();
 
-    // Convert URI to hex
-    const hexUri = this.stringToHex(uri);
-
-    const tx: NFTokenMint = {
-      TransactionType: "NFTokenMint",
-      Account: issuerWallet.address,
-      URI: hexUri,
-      Flags: flags,
-      TransferFee: transferFee,
-      NFTokenTaxon: 0,
-    };
-
-    const prepared = await this.client.autofill(tx);
-    const signed = issuerWallet.sign(prepared);
-    const result = await this.client.submitAndWait(signed.tx_blob);
+    const response = await this.client.request({
+      command: "account_objects",
+      account: eggShopAddress,
+      type: "nft_offer",
+    });
+    const offers = response.result.account_objects.filter(
+      (offer: any) =>
+        offer.Destination === wallet.address && offer.Amount === "0"
+    );
+
+    const results = [];
+    for (const offer of offers) {
+      const tx: NFTokenAcceptOffer = {
+        TransactionType: "NFTokenAcceptOffer",
+        Account: wallet.address,
+        NFTokenSellOffer: offer.index,
+      };
+
+      const prepared = await this.client.autofill(tx);
+      const signed = wallet.sign(prepared);
+      results.push(await this.client.submitAndWait(signed.tx_blob));
+    }
 
-    return result;
+    return results;
   }
 
   /**
@@ -216,3 +220,5 @@ export const sdk = new CreatureCrafterSDK();
 
 // Export types and utility functions
 export { Client, Wallet, xrpToDrops, dropsToXrp };
+export * from "./hookErrors";
+export * from "./dna";
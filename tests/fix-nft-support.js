@@ -1,194 +0,0 @@
-// Script to fix NFT support in the XRPL node
-// This script updates the xrpl-direct.ts file to handle the case when NFT features are disabled
-
-const fs = require('fs');
-const path = require('path');
-
-const directFilePath = path.resolve(__dirname, '../frontend/src/xrpl-direct.ts');
-
-// Check if file exists
-if (!fs.existsSync(directFilePath)) {
-  console.error(`File not found: ${directFilePath}`);
-  process.exit(1);
-}
-
-// Read the file
-let fileContent = fs.readFileSync(directFilePath, 'utf8');
-
-// Add mocking support for NFT operations
-const mockingCode = `
-// Mock NFT operations when real NFT features are disabled on the node
-let useMockNFTOperations = false;
-let mockNFTs = [];
-
-// Check if NFT operations need to be mocked
-async function checkNFTFeatureSupport(client) {
-  try {
-    // Get server info to check amendments
-    const serverInfo = await client.request('server_info');
-    const amendments = serverInfo?.result?.info?.amendments || [];
-    
-    // Check if NFT amendments are enabled
-    const hasNFTSupport = amendments.some(a => 
-      a.includes('NFToken') || a.includes('NonFungibleTokens')
-    );
-    
-    if (!hasNFTSupport) {
-      console.warn('NFT features not enabled on XRPL node. Using mock NFT operations.');
-      useMockNFTOperations = true;
-    } else {
-      console.log('NFT features are enabled on XRPL node.');
-      useMockNFTOperations = false;
-    }
-    
-    return hasNFTSupport;
-  } catch (err) {
-    console.warn('Error checking NFT feature support:', err);
-    // Default to mock mode on errors
-    useMockNFTOperations = true;
-    return false;
-  }
-}
-`;
-
-// Replace the mintNFT function with one that handles disabled features
-const findMintNFTFunc = /\/\/ Mint an NFT\nexport async function mintNFT\(wallet: Wallet, uri: string\): Promise<any> \{/;
-
-const newMintNFTFunc = `// Mint an NFT
-export async function mintNFT(wallet: Wallet, uri: string): Promise<any> {
-  const client = await getClient();
-  console.log(\`Minting NFT with URI: \${uri}\`);
-  
-  try {
-    // Check if NFT features are supported
-    await checkNFTFeatureSupport(client);
-    
-    // If NFT features are disabled, use mock implementation
-    if (useMockNFTOperations) {
-      console.log('Using mock NFT implementation...');
-      return mockMintNFT(wallet, uri);
-    }
-    
-    // Real implementation continues below
-`;
-
-// Add mock implementation
-const mockImplementation = `
-// Mock implementation of NFT minting for when the feature is disabled
-async function mockMintNFT(wallet: Wallet, uri: string): Promise<any> {
-  console.log(\`Mock minting NFT with URI: \${uri}\`);
-  
-  // Convert URI to hex (same as real implementation)
-  const hexUri = Array.from(new TextEncoder().encode(uri))
-    .map(b => b.toString(16).padStart(2, '0'))
-    .join('')
-    .toUpperCase();
-  
-  // Generate a fake NFT TokenID
-  const tokenIdPrefix = "00081388";
-  const randomPart = Array.from({ length: 56 }, () => 
-    Math.floor(Math.random() * 16).toString(16)
-  ).join('');
-  const mockTokenId = tokenIdPrefix + randomPart;
-  
-  // Create a mock NFT object
-  const mockNFT = {
-    NFTokenID: mockTokenId,
-    URI: hexUri,
-    Issuer: wallet.address,
-    SerialNumber: mockNFTs.length + 1,
-    Flags: 8,
-    Sequence: Date.now(),
-    isMock: true  // Flag to indicate this is a mock NFT
-  };
-  
-  // Add to mock storage
-  mockNFTs.push(mockNFT);
-  
-  // Wait to simulate network delay
-  await new Promise(resolve => setTimeout(resolve, 2000));
-  
-  // Create a response that mimics the real API response
-  const mockResponse = {
-    result: {
-      engine_result: "tesSUCCESS",
-      engine_result_code: 0,
-      engine_result_message: "The transaction was applied. Only final in a validated ledger.",
-      status: "success",
-      tx_blob: "mock_tx_blob",
-      tx_json: {
-        Account: wallet.address,
-        Fee: "12",
-        NFTokenTaxon: 0,
-        Sequence: Date.now(),
-        TransactionType: "NFTokenMint",
-        hash: \`mock_hash_\${Date.now()}\`,
-        URI: hexUri
-      }
-    },
-    nfts: mockNFTs,
-    nft_count: mockNFTs.length,
-    tx_hash: \`mock_hash_\${Date.now()}\`,
-    verified: true,
-    isMock: true
-  };
-  
-  console.log('Mock NFT minted successfully!');
-  return mockResponse;
-}
-
-// Mock implementation of getOwnedNFTs for when the feature is disabled
-async function mockGetOwnedNFTs(address: string): Promise<any[]> {
-  console.log(\`Mock fetching NFTs for address: \${address}\`);
-  
-  // Return the mock NFTs
-  // In a real implementation, you'd filter by owner, but for this demo
-  // we'll return all mock NFTs regardless of address
-  return mockNFTs;
-}
-`;
-
-// Modify the getOwnedNFTs function
-const findGetOwnedNFTsFunc = /\/\/ Get NFTs owned by an address\nexport async function getOwnedNFTs\(address: string\): Promise<any\[\]> \{/;
-
-const newGetOwnedNFTsFunc = `// Get NFTs owned by an address
-export async function getOwnedNFTs(address: string): Promise<any[]> {
-  const client = await getClient();
-  
-  // Check if NFT features are supported
-  if (useMockNFTOperations) {
-    return mockGetOwnedNFTs(address);
-  }
-  
-  try {
-`;
-
-// Update the file content
-if (!fileContent.includes('useMockNFTOperations')) {
-  // Add mocking support
-  fileContent = fileContent.replace(
-    '// Create a singleton client', 
-    mockingCode + '\n// Create a singleton client'
-  );
-
-  // Update mintNFT function
-  fileContent = fileContent.replace(findMintNFTFunc, newMintNFTFunc);
-
-  // Update getOwnedNFTs function
-  fileContent = fileContent.replace(findGetOwnedNFTsFunc, newGetOwnedNFTsFunc);
-
-  // Add mock implementation at the end of the file
-  fileContent += '\n' + mockImplementation;
-
-  // Write back to file
-  fs.writeFileSync(directFilePath, fileContent);
-  
-  console.log('Successfully updated xrpl-direct.ts with NFT feature compatibility!');
-  console.log('The app will now use mock NFT operations when real NFT features are disabled.');
-  console.log('This ensures the app can be used even without NFT support in the XRPL node.');
-} else {
-  console.log('File already contains mock NFT operations. No changes made.');
-}
-
-console.log('All done! Restart your frontend to apply the changes:');
-console.log('docker-compose restart frontend');
\ No newline at end of file
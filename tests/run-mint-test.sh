@@ -35,9 +35,7 @@ else
   echo "If you're seeing 'The transaction requires logic that is currently disabled'"
   echo "You need to enable NFT features on your XRPL node."
   echo ""
-  echo "Options to fix:"
-  echo "1. Run the NFT fix script: node fix-nft-support.js (uses mocks)"
-  echo "2. Enable NFT features in XRPL: ./enable-nft-features.sh (requires restart)"
+  echo "To fix, enable NFT features in XRPL: ./enable-nft-features.sh (requires restart)"
 fi
 
 echo "Test completed. Check the screenshots in ./screenshots directory"
\ No newline at end of file